@@ -1,4 +1,161 @@
+use lez_multisig_ffi::multisig_queries;
+use multisig_core::MultisigError;
+
+/// Times a propose command runs when other proposals keep taking its index.
+const PROPOSE_ATTEMPTS: usize = 3;
+
 #[tokio::main]
 async fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // `multisig decode-error <code | failure message>` — map a failed
+    // transaction back to the named program error (codes are listed in the IDL).
+    if args.get(1).map(String::as_str) == Some("decode-error") {
+        let input = args[2..].join(" ");
+        let err = match input.trim().parse::<u32>() {
+            Ok(code) => MultisigError::from_code(code),
+            Err(_) => MultisigError::from_error_text(&input),
+        };
+        match err {
+            Some(e) => println!("{}", e),
+            None => {
+                eprintln!("Not a MultisigError: {}", input);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // `multisig sign-approval --program-id <hex> --create-key <hex> --proposal-index <n> --signer <account>`
+    // — sign a proposal's approval digest with the signer's wallet key
+    // (NSSA_WALLET_HOME_DIR) for a relayer; nothing is sent on-chain.
+    if args.get(1).map(String::as_str) == Some("sign-approval") {
+        let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+        let request = serde_json::json!({
+            "program_id_hex": flag("--program-id"),
+            "create_key": flag("--create-key"),
+            "proposal_index": flag("--proposal-index").and_then(|i| i.parse::<u64>().ok()),
+            "signer": flag("--signer"),
+        })
+        .to_string();
+        // The query starts its own tokio runtime, which can't nest in this one
+        let result = std::thread::spawn(move || multisig_queries::sign_approval(&request)).join().unwrap();
+        print_result(&result);
+        return;
+    }
+
+    // `multisig sign-voter-call --program-id <hex> --create-key <hex> --signer <account> --instruction <json>`
+    // — sign an instruction for act-as-voter with the member's wallet key, at
+    // their voter record's current nonce; nothing is sent on-chain.
+    if args.get(1).map(String::as_str) == Some("sign-voter-call") {
+        let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+        let instruction = flag("--instruction")
+            .and_then(|i| serde_json::from_str::<serde_json::Value>(&i).ok());
+        let request = serde_json::json!({
+            "program_id_hex": flag("--program-id"),
+            "create_key": flag("--create-key"),
+            "signer": flag("--signer"),
+            "instruction": instruction,
+        })
+        .to_string();
+        let result = std::thread::spawn(move || multisig_queries::sign_voter_call(&request)).join().unwrap();
+        print_result(&result);
+        return;
+    }
+
+    // `multisig next-proposal-index --program-id <hex> --create-key <hex>` — the
+    // index the next propose-* command must pass as --proposal-index, and its
+    // proposal PDA. propose-* commands run without --proposal-index fetch it
+    // themselves (see propose_at_next_index).
+    if args.get(1).map(String::as_str) == Some("next-proposal-index") {
+        let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+        let request = serde_json::json!({
+            "program_id_hex": flag("--program-id"),
+            "create_key": flag("--create-key"),
+        })
+        .to_string();
+        let result = std::thread::spawn(move || multisig_queries::next_proposal_index(&request)).join().unwrap();
+        print_result(&result);
+        return;
+    }
+
+    // `multisig bundle-signatures <approval.json>...` — verify the collected
+    // sign-approval outputs, each against its own digest, and print the
+    // `signatures` argument for approve-with-signatures.
+    if args.get(1).map(String::as_str) == Some("bundle-signatures") {
+        let mut approvals = Vec::new();
+        for path in args.iter().skip(2) {
+            let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
+            });
+            approvals.push(serde_json::from_str::<serde_json::Value>(&text).unwrap_or_else(|e| {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
+            }));
+        }
+        let request = serde_json::json!({ "approvals": approvals }).to_string();
+        print_result(&multisig_queries::bundle_signatures(&request));
+        return;
+    }
+
+    // `multisig ... propose* ...` without --proposal-index: run it at the
+    // multisig's next proposal index, retrying on ProposalIndexConflict.
+    if args.iter().any(|a| a.starts_with("propose")) && !args.iter().any(|a| a == "--proposal-index") {
+        propose_at_next_index(&args);
+    }
+
     lez_cli::run().await;
 }
+
+/// Run a propose command at the next proposal index of its
+/// --multisig-state-account, filling in --proposal-index and, unless given,
+/// --proposal-account. While it fails with ProposalIndexConflict (another
+/// proposal took the index first) the index is fetched again and the command
+/// rerun, up to PROPOSE_ATTEMPTS times. Exits with the command's status.
+fn propose_at_next_index(args: &[String]) -> ! {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+    let request = serde_json::json!({ "multisig_state": flag("--multisig-state-account") }).to_string();
+    let exe = std::env::current_exe().unwrap_or_else(|e| {
+        eprintln!("current_exe: {}", e);
+        std::process::exit(1);
+    });
+    let mut attempt = 1;
+    loop {
+        let query = request.clone();
+        let next = std::thread::spawn(move || multisig_queries::next_proposal_index(&query)).join().unwrap();
+        let next: serde_json::Value = serde_json::from_str(&next).unwrap_or_default();
+        if next["success"] != true {
+            print_result(&next.to_string());
+        }
+        let mut command = args[1..].to_vec();
+        command.extend(["--proposal-index".to_string(), next["proposal_index"].to_string()]);
+        if flag("--proposal-account").is_none() {
+            command.extend(["--proposal-account".to_string(), next["proposal_pda"].as_str().unwrap_or_default().to_string()]);
+        }
+        let output = std::process::Command::new(&exe).args(&command).output().unwrap_or_else(|e| {
+            eprintln!("{}: {}", exe.display(), e);
+            std::process::exit(1);
+        });
+        let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+        let conflict = MultisigError::from_error_text(&text) == Some(MultisigError::ProposalIndexConflict);
+        if output.status.success() || !conflict || attempt == PROPOSE_ATTEMPTS {
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            std::process::exit(output.status.code().unwrap_or(1));
+        }
+        eprintln!("Proposal index {} was taken, retrying", next["proposal_index"]);
+        attempt += 1;
+    }
+}
+
+/// Print a query's JSON result, exiting non-zero when it failed.
+fn print_result(result: &str) {
+    println!("{}", result);
+    let ok = serde_json::from_str::<serde_json::Value>(result)
+        .map(|v| v["success"] == true)
+        .unwrap_or(false);
+    if !ok {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,282 @@
+//! End-to-end test for batch proposals paying out of vaults.
+//!
+//! Flow:
+//! 1. Deploy token program + multisig program, create a token
+//! 2. Create a 1-of-1 multisig, fund vaults 0 and 1
+//! 3. ProposeBatch paying alice, bob and carol from vault 0 and dave from vault 1
+//! 4. Execute step by step: the first step pays alice and dave, and each later
+//!    payment from vault 0 runs in its own Execute against the vault's new balance
+//! 5. Every balance checked once the proposal is Executed
+//!
+//! Prerequisites: as e2e_multisig (SEQUENCER_URL, MULTISIG_PROGRAM, TOKEN_PROGRAM).
+
+use std::time::Duration;
+
+use nssa::{
+    AccountId, PrivateKey, ProgramDeploymentTransaction, PublicKey, PublicTransaction,
+    program::Program,
+    public_transaction::{Message, WitnessSet},
+};
+use multisig_core::{Instruction, Proposal, ProposalCall, ProposalStatus};
+use lez_multisig_ffi::{
+    compute_multisig_state_pda, compute_proposal_pda, compute_vault_pda, vault_pda_seed_bytes,
+};
+use common::sequencer_client::SequencerClient;
+use token_core::{Instruction as TokenInstruction, TokenHolding};
+
+const BLOCK_WAIT_SECS: u64 = 15;
+
+fn account_id_from_key(key: &PrivateKey) -> AccountId {
+    let pk = PublicKey::new_from_private_key(key);
+    AccountId::from(&pk)
+}
+
+fn sequencer_client() -> SequencerClient {
+    let url = std::env::var("SEQUENCER_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3040".to_string());
+    SequencerClient::new(url.parse().unwrap()).expect("Failed to create sequencer client")
+}
+
+async fn submit_tx(client: &SequencerClient, tx: PublicTransaction) {
+    let response = client.send_tx_public(tx).await.expect("Failed to submit tx");
+    let tx_hash = response.tx_hash.clone();
+    println!("  tx_hash: {}", tx_hash);
+
+    let max_wait = Duration::from_secs(BLOCK_WAIT_SECS * 3);
+    let poll_interval = Duration::from_secs(3);
+    let start = std::time::Instant::now();
+
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        match client.get_transaction_by_hash(tx_hash.clone()).await {
+            Ok(resp) if resp.transaction.is_some() => {
+                println!("  ✅ tx included in block");
+                return;
+            }
+            _ => {
+                if start.elapsed() > max_wait {
+                    panic!("❌ Transaction {} not included after {:?}", tx_hash, max_wait);
+                }
+            }
+        }
+    }
+}
+
+async fn get_nonce(client: &SequencerClient, account_id: AccountId) -> u128 {
+    client.get_account(account_id).await
+        .map(|r| r.account.nonce)
+        .unwrap_or(0)
+}
+
+async fn get_balance(client: &SequencerClient, account_id: AccountId) -> Option<u128> {
+    let resp = client.get_account(account_id).await.ok()?;
+    let data: Vec<u8> = resp.account.data.into();
+    let holding: TokenHolding = borsh::from_slice(&data).ok()?;
+    match holding {
+        TokenHolding::Fungible { balance, .. } => Some(balance),
+        _ => None,
+    }
+}
+
+async fn get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Option<Proposal> {
+    let account = client.get_account(proposal_id).await.ok()?;
+    let data: Vec<u8> = account.account.data.into();
+    borsh::from_slice(&data).ok()
+}
+
+fn deploy_program(bytecode: Vec<u8>) -> (ProgramDeploymentTransaction, nssa::ProgramId) {
+    let program = Program::new(bytecode.clone()).expect("Invalid program");
+    let program_id = program.id();
+    let msg = nssa::program_deployment_transaction::Message::new(bytecode);
+    (ProgramDeploymentTransaction::new(msg), program_id)
+}
+
+/// Build and sign a single-signer multisig transaction.
+fn signed_tx(
+    program_id: nssa::ProgramId,
+    accounts: Vec<AccountId>,
+    nonce: u128,
+    instruction: Instruction,
+    key: &PrivateKey,
+) -> PublicTransaction {
+    let msg = Message::try_new(program_id, accounts, vec![nonce], instruction).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[key]);
+    PublicTransaction::new(msg, ws)
+}
+
+/// A token transfer of `amount` from a multisig vault, signed by the vault's seed.
+fn vault_payment(
+    token_program_id: nssa::ProgramId,
+    create_key: &[u8; 32],
+    vault_index: u8,
+    vault: AccountId,
+    recipient: AccountId,
+    amount: u128,
+) -> ProposalCall {
+    let transfer = TokenInstruction::Transfer { amount_to_transfer: amount };
+    ProposalCall {
+        target_program_id: token_program_id,
+        target_instruction_data: risc0_zkvm::serde::to_vec(&transfer).unwrap(),
+        target_accounts: vec![*vault.value(), *recipient.value()],
+        pda_seeds: vec![vault_pda_seed_bytes(create_key, vault_index)],
+        authorized_indices: vec![0],
+    }
+}
+
+#[tokio::test]
+async fn test_batch_payments_from_vaults() {
+    let client = sequencer_client();
+
+    // ── Deploy programs ─────────────────────────────────────────────────
+    println!("📦 Deploying programs...");
+    let token_path = std::env::var("TOKEN_PROGRAM")
+        .unwrap_or_else(|_| {
+            let home = std::env::var("HOME").expect("HOME env var not set");
+            format!("{}/lssa/artifacts/program_methods/token.bin", home)
+        });
+    let token_bytecode = std::fs::read(&token_path)
+        .unwrap_or_else(|_| panic!("Cannot read token binary at '{}'", token_path));
+    let (token_deploy_tx, token_program_id) = deploy_program(token_bytecode);
+
+    let multisig_path = std::env::var("MULTISIG_PROGRAM")
+        .unwrap_or_else(|_| panic!("MULTISIG_PROGRAM env var not set"));
+    let multisig_bytecode = std::fs::read(&multisig_path)
+        .unwrap_or_else(|_| panic!("Cannot read multisig binary at '{}'", multisig_path));
+    let (multisig_deploy_tx, program_id) = deploy_program(multisig_bytecode);
+
+    for (name, tx) in [("token", token_deploy_tx), ("multisig", multisig_deploy_tx)] {
+        match client.send_tx_program(tx).await {
+            Ok(r) => {
+                println!("  {} deployed: {}", name, r.tx_hash);
+                tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS)).await;
+            }
+            Err(e) => println!("  {} deploy skipped: {}", name, e),
+        }
+    }
+
+    // ── STEP 1: Create token ────────────────────────────────────────────
+    println!("\n═══ STEP 1: Create fungible token ═══");
+    let def_id = account_id_from_key(&PrivateKey::new_os_random());
+    let minter_holding_key = PrivateKey::new_os_random();
+    let minter_holding_id = account_id_from_key(&minter_holding_key);
+
+    let msg = Message::try_new(
+        token_program_id,
+        vec![def_id, minter_holding_id],
+        vec![],
+        TokenInstruction::NewFungibleDefinition { name: "BatchToken".to_string(), total_supply: 1_000_000 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    assert_eq!(get_balance(&client, minter_holding_id).await, Some(1_000_000));
+
+    // ── STEP 2: Create 1-of-1 multisig, fund vaults 0 and 1 ─────────────
+    println!("\n═══ STEP 2: Create 1-of-1 multisig, fund two vaults ═══");
+    let key1 = PrivateKey::new_os_random();
+    let m1 = account_id_from_key(&key1);
+    let create_key: [u8; 32] = *account_id_from_key(&PrivateKey::new_os_random()).value();
+    let multisig_state_id = compute_multisig_state_pda(&program_id, &create_key);
+    let vault_0 = compute_vault_pda(&program_id, &create_key, 0);
+    let vault_1 = compute_vault_pda(&program_id, &create_key, 1);
+
+    let msg = Message::try_new(
+        program_id,
+        vec![multisig_state_id, m1],
+        vec![],
+        Instruction::CreateMultisig {
+            create_key,
+            threshold: 1,
+            members: vec![*m1.value()],
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
+        },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    for vault in [vault_0, vault_1] {
+        let msg = Message::try_new(
+            token_program_id,
+            vec![minter_holding_id, vault],
+            vec![get_nonce(&client, minter_holding_id).await],
+            TokenInstruction::Transfer { amount_to_transfer: 500 },
+        ).unwrap();
+        let ws = WitnessSet::for_message(&msg, &[&minter_holding_key]);
+        submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+        assert_eq!(get_balance(&client, vault).await, Some(500));
+    }
+    println!("  ✅ Vaults 0 and 1 hold 500 each");
+
+    let alice = account_id_from_key(&PrivateKey::new_os_random());
+    let bob = account_id_from_key(&PrivateKey::new_os_random());
+    let carol = account_id_from_key(&PrivateKey::new_os_random());
+    let dave = account_id_from_key(&PrivateKey::new_os_random());
+    let proposal_id = compute_proposal_pda(&program_id, &create_key, 1);
+
+    // ── STEP 3: One vote for four payments, three from vault 0 ──────────
+    println!("\n═══ STEP 3: ProposeBatch paying four contributors ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_id],
+        get_nonce(&client, m1).await,
+        Instruction::ProposeBatch {
+            calls: vec![
+                vault_payment(token_program_id, &create_key, 0, vault_0, alice, 200),
+                vault_payment(token_program_id, &create_key, 1, vault_1, dave, 100),
+                vault_payment(token_program_id, &create_key, 0, vault_0, bob, 100),
+                vault_payment(token_program_id, &create_key, 0, vault_0, carol, 50),
+            ],
+            expiry: 0,
+            create_key,
+            proposal_index: 1,
+        },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+    let proposal = get_proposal(&client, proposal_id).await.expect("proposal stored");
+    assert_eq!(proposal.calls.len(), 4);
+    // The first step runs the calls up to the second payment from vault 0
+    assert_eq!(
+        proposal.execute_accounts(),
+        vec![*vault_0.value(), *alice.value(), *vault_1.value(), *dave.value()]
+    );
+
+    // ── STEP 4: Execute each step with the accounts it asks for ─────────
+    println!("\n═══ STEP 4: Execute the batch step by step ═══");
+    let expected_steps: [&[AccountId]; 3] = [&[alice, dave], &[bob], &[carol]];
+    for (step, paid) in expected_steps.iter().enumerate() {
+        let proposal = get_proposal(&client, proposal_id).await.expect("proposal stored");
+        assert_eq!(proposal.status, ProposalStatus::Active, "step {} should still be pending", step + 1);
+        let mut accounts = vec![multisig_state_id, m1, proposal_id];
+        accounts.extend(proposal.execute_accounts().into_iter().map(AccountId::new));
+        let tx = signed_tx(
+            program_id,
+            accounts,
+            get_nonce(&client, m1).await,
+            Instruction::Execute { create_key, proposal_index: 1 },
+            &key1,
+        );
+        submit_tx(&client, tx).await;
+        for recipient in paid.iter() {
+            assert!(get_balance(&client, *recipient).await.is_some(), "step {} should have paid {}", step + 1, recipient);
+        }
+        println!("  ✅ Step {} executed", step + 1);
+    }
+
+    // ── STEP 5: Every payment landed ────────────────────────────────────
+    println!("\n═══ STEP 5: Check balances ═══");
+    let proposal = get_proposal(&client, proposal_id).await.expect("proposal stored");
+    assert_eq!(proposal.status, ProposalStatus::Executed);
+    assert_eq!(proposal.executed_calls, 4);
+    assert_eq!(get_balance(&client, vault_0).await, Some(150));
+    assert_eq!(get_balance(&client, alice).await, Some(200));
+    assert_eq!(get_balance(&client, bob).await, Some(100));
+    assert_eq!(get_balance(&client, carol).await, Some(50));
+    assert_eq!(get_balance(&client, vault_1).await, Some(400));
+    assert_eq!(get_balance(&client, dave).await, Some(100));
+
+    println!("\n🎉 Batch payments e2e test PASSED!");
+}
@@ -0,0 +1,325 @@
+//! End-to-end test for CloseProposal.
+//!
+//! Flow:
+//! 1. Deploy multisig program, create a 2-of-3 multisig
+//! 2. Proposal 1 → approve → execute (Executed)
+//! 3. Proposal 2 → reject ×2 (Rejected)
+//! 4. Proposal 3 stays Active → CloseProposal must fail
+//! 5. CloseProposal(2, older = [1]) → both proposal accounts wiped
+//! 6. Closed PDA is reuse-safe: approve/close on it fail, re-proposing into it fails
+
+use std::time::Duration;
+
+use nssa::{
+    AccountId, PrivateKey, ProgramDeploymentTransaction, PublicKey, PublicTransaction,
+    program::Program,
+    public_transaction::{Message, WitnessSet},
+};
+use multisig_core::{Instruction, MultisigState, Proposal, ProposalStatus};
+use lez_multisig_ffi::{compute_multisig_state_pda, compute_proposal_pda};
+use common::sequencer_client::SequencerClient;
+
+const BLOCK_WAIT_SECS: u64 = 15;
+
+fn account_id_from_key(key: &PrivateKey) -> AccountId {
+    let pk = PublicKey::new_from_private_key(key);
+    AccountId::from(&pk)
+}
+
+fn sequencer_client() -> SequencerClient {
+    let url = std::env::var("SEQUENCER_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3040".to_string());
+    SequencerClient::new(url.parse().unwrap()).expect("Failed to create sequencer client")
+}
+
+async fn submit_tx(client: &SequencerClient, tx: PublicTransaction) {
+    let response = client.send_tx_public(tx).await.expect("Failed to submit tx");
+    let tx_hash = response.tx_hash.clone();
+    println!("  tx_hash: {}", tx_hash);
+
+    let max_wait = Duration::from_secs(BLOCK_WAIT_SECS * 3);
+    let poll_interval = Duration::from_secs(3);
+    let start = std::time::Instant::now();
+
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        match client.get_transaction_by_hash(tx_hash.clone()).await {
+            Ok(resp) if resp.transaction.is_some() => {
+                println!("  ✅ tx included in block");
+                return;
+            }
+            _ => {
+                if start.elapsed() > max_wait {
+                    panic!("❌ Transaction {} not included after {:?}", tx_hash, max_wait);
+                }
+            }
+        }
+    }
+}
+
+/// Submit a tx that we expect to fail (not get included).
+/// Returns true if it was correctly rejected/not included.
+async fn submit_tx_expect_failure(client: &SequencerClient, tx: PublicTransaction) -> bool {
+    match client.send_tx_public(tx).await {
+        Err(_) => {
+            println!("  ✅ Transaction rejected at submission (expected)");
+            return true;
+        }
+        Ok(response) => {
+            let tx_hash = response.tx_hash.clone();
+            println!("  tx_hash: {} (expecting non-inclusion)", tx_hash);
+            // Wait a bit and check it wasn't included
+            tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS * 2)).await;
+            match client.get_transaction_by_hash(tx_hash.clone()).await {
+                Ok(resp) if resp.transaction.is_some() => {
+                    println!("  ❌ Transaction was unexpectedly included!");
+                    false
+                }
+                _ => {
+                    println!("  ✅ Transaction not included (expected failure)");
+                    true
+                }
+            }
+        }
+    }
+}
+
+async fn get_nonce(client: &SequencerClient, account_id: AccountId) -> u128 {
+    client.get_account(account_id).await
+        .map(|r| r.account.nonce)
+        .unwrap_or(0)
+}
+
+async fn get_multisig_state(client: &SequencerClient, state_id: AccountId) -> MultisigState {
+    let account = client.get_account(state_id).await.expect("Failed to get multisig state");
+    let data: Vec<u8> = account.account.data.into();
+    borsh::from_slice(&data).expect("Failed to deserialize multisig state")
+}
+
+async fn get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Proposal {
+    let account = client.get_account(proposal_id).await.expect("Failed to get proposal");
+    let data: Vec<u8> = account.account.data.into();
+    borsh::from_slice(&data).expect("Failed to deserialize proposal")
+}
+
+fn deploy_program(bytecode: Vec<u8>) -> (ProgramDeploymentTransaction, nssa::ProgramId) {
+    let program = Program::new(bytecode.clone()).expect("Invalid program");
+    let program_id = program.id();
+    let msg = nssa::program_deployment_transaction::Message::new(bytecode);
+    (ProgramDeploymentTransaction::new(msg), program_id)
+}
+
+async fn get_proposal_data(client: &SequencerClient, proposal_id: AccountId) -> Vec<u8> {
+    let account = client.get_account(proposal_id).await.expect("Failed to get proposal");
+    account.account.data.into()
+}
+
+/// Build and sign a single-signer multisig transaction.
+fn signed_tx(
+    program_id: nssa::ProgramId,
+    accounts: Vec<AccountId>,
+    nonce: u128,
+    instruction: Instruction,
+    key: &PrivateKey,
+) -> PublicTransaction {
+    let msg = Message::try_new(program_id, accounts, vec![nonce], instruction).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[key]);
+    PublicTransaction::new(msg, ws)
+}
+
+#[tokio::test]
+async fn test_close_proposal() {
+    let client = sequencer_client();
+
+    // ── Deploy multisig program ─────────────────────────────────────────
+    println!("📦 Deploying multisig program...");
+    let multisig_path = std::env::var("MULTISIG_PROGRAM")
+        .unwrap_or_else(|_| panic!("MULTISIG_PROGRAM env var not set"));
+    let multisig_bytecode = std::fs::read(&multisig_path)
+        .unwrap_or_else(|_| panic!("Cannot read multisig binary at '{}'", multisig_path));
+    let (deploy_tx, program_id) = deploy_program(multisig_bytecode);
+
+    match client.send_tx_program(deploy_tx).await {
+        Ok(r) => {
+            println!("  Deployed: {}", r.tx_hash);
+            tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS)).await;
+        }
+        Err(e) => println!("  Deploy skipped (already deployed): {}", e),
+    }
+
+    // ── STEP 1: Create 2-of-3 multisig ──────────────────────────────────
+    println!("\n═══ STEP 1: Create 2-of-3 multisig ═══");
+    let key1 = PrivateKey::new_os_random();
+    let key2 = PrivateKey::new_os_random();
+    let key3 = PrivateKey::new_os_random();
+    let m1 = account_id_from_key(&key1);
+    let m2 = account_id_from_key(&key2);
+    let m3 = account_id_from_key(&key3);
+
+    let create_key: [u8; 32] = *AccountId::from(
+        &PublicKey::new_from_private_key(&PrivateKey::new_os_random())
+    ).value();
+
+    let multisig_state_id = compute_multisig_state_pda(&program_id, &create_key);
+    println!("  State PDA: {}", multisig_state_id);
+
+    let msg = Message::try_new(
+        program_id,
+        vec![multisig_state_id, m1, m2, m3],
+        vec![],
+        Instruction::CreateMultisig {
+            create_key,
+            threshold: 2,
+            members: vec![*m1.value(), *m2.value(), *m3.value()],
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
+        },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    println!("  ✅ 2-of-3 multisig created");
+
+    let proposal_1 = compute_proposal_pda(&program_id, &create_key, 1);
+    let proposal_2 = compute_proposal_pda(&program_id, &create_key, 2);
+    let proposal_3 = compute_proposal_pda(&program_id, &create_key, 3);
+
+    // ── STEP 2: Proposal 1 → Executed ───────────────────────────────────
+    println!("\n═══ STEP 2: Proposal 1 → approve → execute ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_1],
+        get_nonce(&client, m1).await,
+        Instruction::ProposeChangeThreshold { new_threshold: 2, create_key, proposal_index: 1 },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m2, proposal_1],
+        get_nonce(&client, m2).await,
+        Instruction::Approve { create_key, proposal_index: 1 },
+        &key2,
+    );
+    submit_tx(&client, tx).await;
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_1],
+        get_nonce(&client, m1).await,
+        Instruction::Execute { create_key, proposal_index: 1 },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+    assert_eq!(get_proposal(&client, proposal_1).await.status, ProposalStatus::Executed);
+    println!("  ✅ Proposal 1 executed");
+
+    // ── STEP 3: Proposal 2 → Rejected ───────────────────────────────────
+    println!("\n═══ STEP 3: Proposal 2 → reject ×2 ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_2],
+        get_nonce(&client, m1).await,
+        Instruction::ProposeChangeThreshold { new_threshold: 3, create_key, proposal_index: 2 },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+    for (key, id) in [(&key2, m2), (&key3, m3)] {
+        let tx = signed_tx(
+            program_id,
+            vec![multisig_state_id, id, proposal_2],
+            get_nonce(&client, id).await,
+            Instruction::Reject { create_key, proposal_index: 2 },
+            key,
+        );
+        submit_tx(&client, tx).await;
+    }
+    assert_eq!(get_proposal(&client, proposal_2).await.status, ProposalStatus::Rejected);
+    println!("  ✅ Proposal 2 rejected");
+
+    // ── STEP 4: Active proposal cannot be closed ────────────────────────
+    println!("\n═══ STEP 4: CloseProposal on an Active proposal must fail ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_3],
+        get_nonce(&client, m1).await,
+        Instruction::ProposeChangeThreshold { new_threshold: 1, create_key, proposal_index: 3 },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_3],
+        get_nonce(&client, m1).await,
+        Instruction::CloseProposal { create_key, proposal_index: 3 },
+        &key1,
+    );
+    let failed = submit_tx_expect_failure(&client, tx).await;
+    assert!(failed, "Closing an Active proposal should fail");
+    assert_eq!(get_proposal(&client, proposal_3).await.status, ProposalStatus::Active);
+    println!("  ✅ Active proposal untouched");
+
+    // An Active proposal passed as an older proposal must also block the close
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_2, proposal_3],
+        get_nonce(&client, m1).await,
+        Instruction::CloseProposal { create_key, proposal_index: 2 },
+        &key1,
+    );
+    let failed = submit_tx_expect_failure(&client, tx).await;
+    assert!(failed, "Closing with a newer/Active proposal in the batch should fail");
+
+    // ── STEP 5: Close proposal 2 together with proposal 1 ───────────────
+    println!("\n═══ STEP 5: CloseProposal(2, older = [1]) ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m2, proposal_2, proposal_1],
+        get_nonce(&client, m2).await,
+        Instruction::CloseProposal { create_key, proposal_index: 2 },
+        &key2,
+    );
+    submit_tx(&client, tx).await;
+    assert!(get_proposal_data(&client, proposal_1).await.is_empty(), "Proposal 1 data should be wiped");
+    assert!(get_proposal_data(&client, proposal_2).await.is_empty(), "Proposal 2 data should be wiped");
+    println!("  ✅ Proposals 1 and 2 closed");
+
+    // ── STEP 6: Closed PDAs are reuse-safe ──────────────────────────────
+    println!("\n═══ STEP 6: Closed PDA cannot be reused ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m3, proposal_2],
+        get_nonce(&client, m3).await,
+        Instruction::Approve { create_key, proposal_index: 2 },
+        &key3,
+    );
+    assert!(submit_tx_expect_failure(&client, tx).await, "Approve on a closed proposal should fail");
+
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_2],
+        get_nonce(&client, m1).await,
+        Instruction::CloseProposal { create_key, proposal_index: 2 },
+        &key1,
+    );
+    assert!(submit_tx_expect_failure(&client, tx).await, "Closing twice should fail");
+
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_2],
+        get_nonce(&client, m1).await,
+        Instruction::ProposeChangeThreshold { new_threshold: 3, create_key, proposal_index: 2 },
+        &key1,
+    );
+    assert!(submit_tx_expect_failure(&client, tx).await, "Proposing into a closed PDA should fail");
+    assert!(get_proposal_data(&client, proposal_2).await.is_empty());
+
+    let state = get_multisig_state(&client, multisig_state_id).await;
+    assert_eq!(state.transaction_index, 3, "Failed proposals must not advance the index");
+
+    println!("\n🎉 CloseProposal e2e test PASSED!");
+    println!("   - Active proposals cannot be closed ✅");
+    println!("   - Executed + Rejected proposals closed in one call ✅");
+    println!("   - Closed PDA rejects approve / close / re-propose ✅");
+}
@@ -0,0 +1,329 @@
+//! End-to-end test for the FFI client: a proposal made with
+//! `lez_multisig_propose` and executed with `lez_multisig_execute`.
+//!
+//! The generated wrappers derive the multisig_state and proposal PDAs
+//! themselves, so this fails if their derivation drifts from the program's.
+//!
+//! Flow:
+//! 1. Deploy token program + multisig program
+//! 2. Create a wallet account (wallet CLI) and a 1-of-1 multisig with it as member
+//! 3. Fund vault 0 with tokens
+//! 4. lez_multisig_propose a vault → recipient transfer (auto-approved, threshold reached)
+//! 5. lez_multisig_execute it and check the balances
+//!
+//! Prerequisites (in addition to e2e_multisig's):
+//! - WALLET env var pointing to the lssa wallet CLI (default: $HOME/lssa/target/release/wallet)
+//! - NSSA_WALLET_HOME_DIR: a wallet directory whose config points at the sequencer
+//!   (default: a fresh directory under the system temp dir)
+
+use std::ffi::{CStr, CString};
+use std::io::Write;
+use std::os::raw::c_char;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use nssa::{
+    AccountId, PrivateKey, ProgramDeploymentTransaction, PublicKey, PublicTransaction,
+    program::Program,
+    public_transaction::{Message, WitnessSet},
+};
+use multisig_core::{Instruction, Proposal, ProposalStatus};
+use lez_multisig_ffi::{
+    compute_multisig_state_pda, compute_proposal_pda, compute_vault_pda, vault_pda_seed_bytes,
+};
+use common::sequencer_client::SequencerClient;
+use token_core::{Instruction as TokenInstruction, TokenHolding};
+
+const BLOCK_WAIT_SECS: u64 = 15;
+
+fn account_id_from_key(key: &PrivateKey) -> AccountId {
+    let pk = PublicKey::new_from_private_key(key);
+    AccountId::from(&pk)
+}
+
+fn sequencer_url() -> String {
+    std::env::var("SEQUENCER_URL").unwrap_or_else(|_| "http://127.0.0.1:3040".to_string())
+}
+
+fn sequencer_client() -> SequencerClient {
+    SequencerClient::new(sequencer_url().parse().unwrap()).expect("Failed to create sequencer client")
+}
+
+async fn submit_tx(client: &SequencerClient, tx: PublicTransaction) {
+    let response = client.send_tx_public(tx).await.expect("Failed to submit tx");
+    let tx_hash = response.tx_hash.clone();
+    println!("  tx_hash: {}", tx_hash);
+
+    let max_wait = Duration::from_secs(BLOCK_WAIT_SECS * 3);
+    let poll_interval = Duration::from_secs(3);
+    let start = std::time::Instant::now();
+
+    loop {
+        tokio::time::sleep(poll_interval).await;
+
+        match client.get_transaction_by_hash(tx_hash.clone()).await {
+            Ok(resp) if resp.transaction.is_some() => {
+                println!("  ✅ tx included in block");
+                return;
+            }
+            _ => {
+                if start.elapsed() > max_wait {
+                    panic!(
+                        "❌ Transaction {} was NOT included after {:?}. Check sequencer logs for rejection reason.",
+                        tx_hash, max_wait
+                    );
+                }
+            }
+        }
+    }
+}
+
+async fn get_nonce(client: &SequencerClient, account_id: AccountId) -> u128 {
+    client.get_account(account_id).await
+        .map(|r| r.account.nonce)
+        .unwrap_or(0)
+}
+
+async fn get_balance(client: &SequencerClient, account_id: AccountId) -> Option<u128> {
+    let resp = client.get_account(account_id).await.ok()?;
+    let data: Vec<u8> = resp.account.data.into();
+    let holding: TokenHolding = borsh::from_slice(&data).ok()?;
+    match holding {
+        TokenHolding::Fungible { balance, .. } => Some(balance),
+        _ => None,
+    }
+}
+
+async fn try_get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Option<Proposal> {
+    let account = client.get_account(proposal_id).await.ok()?;
+    let data: Vec<u8> = account.account.data.into();
+    borsh::from_slice(&data).ok()
+}
+
+/// Poll the proposal account until `done` holds for it (the FFI returns once
+/// the transaction is submitted, not once it is included).
+async fn wait_for_proposal(
+    client: &SequencerClient,
+    proposal_id: AccountId,
+    done: impl Fn(&Proposal) -> bool,
+) -> Proposal {
+    let max_wait = Duration::from_secs(BLOCK_WAIT_SECS * 3);
+    let start = std::time::Instant::now();
+    loop {
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        if let Some(proposal) = try_get_proposal(client, proposal_id).await {
+            if done(&proposal) {
+                return proposal;
+            }
+        }
+        if start.elapsed() > max_wait {
+            panic!("❌ Proposal {} did not reach the expected state after {:?}", proposal_id, max_wait);
+        }
+    }
+}
+
+fn deploy_program(bytecode: Vec<u8>) -> (ProgramDeploymentTransaction, nssa::ProgramId) {
+    let program = Program::new(bytecode.clone()).expect("Invalid program");
+    let program_id = program.id();
+    let msg = nssa::program_deployment_transaction::Message::new(bytecode);
+    (ProgramDeploymentTransaction::new(msg), program_id)
+}
+
+fn program_id_hex(program_id: &nssa::ProgramId) -> String {
+    program_id.iter().flat_map(|w| w.to_le_bytes()).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn wallet_home() -> String {
+    std::env::var("NSSA_WALLET_HOME_DIR").unwrap_or_else(|_| {
+        let dir = std::env::temp_dir().join("lez-multisig-e2e-wallet");
+        std::fs::create_dir_all(&dir).expect("Failed to create wallet dir");
+        dir.to_string_lossy().into_owned()
+    })
+}
+
+/// Create a public account in the wallet with the wallet CLI, so the FFI can
+/// sign for it. Parses the `Public/<base58>` account id it prints.
+fn new_wallet_account(wallet_home: &str) -> AccountId {
+    let wallet = std::env::var("WALLET").unwrap_or_else(|_| {
+        let home = std::env::var("HOME").expect("HOME env var not set");
+        format!("{}/lssa/target/release/wallet", home)
+    });
+    let mut child = Command::new(&wallet)
+        .args(["account", "new", "public", "--label", "e2e-ffi-member"])
+        .env("NSSA_WALLET_HOME_DIR", wallet_home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| panic!("Cannot run wallet CLI at '{}': {}", wallet, e));
+    // A wallet without storage asks for a password once.
+    child.stdin.take().unwrap().write_all(b"\n").unwrap();
+    let output = child.wait_with_output().expect("wallet CLI failed");
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    text.split_whitespace()
+        .find_map(|word| word.strip_prefix("Public/"))
+        .and_then(|b58| b58.parse().ok())
+        .unwrap_or_else(|| panic!("No account id in wallet CLI output:\n{}", text))
+}
+
+/// Call an FFI wrapper on its own thread: the wrappers run their own tokio
+/// runtime, which can't be started inside the test's.
+fn ffi_call(f: extern "C" fn(*const c_char) -> *mut c_char, args: serde_json::Value) -> serde_json::Value {
+    let body = std::thread::spawn(move || {
+        let args = CString::new(args.to_string()).unwrap();
+        let result = f(args.as_ptr());
+        let body = unsafe { CStr::from_ptr(result) }.to_string_lossy().into_owned();
+        lez_multisig_ffi::lez_multisig_free_string(result);
+        body
+    })
+    .join()
+    .expect("FFI call panicked");
+    println!("  FFI result: {}", body);
+    serde_json::from_str(&body).expect("FFI returned invalid JSON")
+}
+
+#[tokio::test]
+async fn test_ffi_propose_and_execute() {
+    let client = sequencer_client();
+
+    // ── Deploy programs ─────────────────────────────────────────────────
+    println!("📦 Deploying programs...");
+
+    let token_path = std::env::var("TOKEN_PROGRAM")
+        .unwrap_or_else(|_| {
+            let home = std::env::var("HOME").expect("HOME env var not set");
+            format!("{}/lssa/artifacts/program_methods/token.bin", home)
+        });
+    let token_bytecode = std::fs::read(&token_path)
+        .unwrap_or_else(|_| panic!("Cannot read token binary at '{}'", token_path));
+    let (token_deploy_tx, token_program_id) = deploy_program(token_bytecode);
+
+    let multisig_path = std::env::var("MULTISIG_PROGRAM")
+        .unwrap_or_else(|_| {
+            let manifest_dir = env!("CARGO_MANIFEST_DIR");
+            format!("{}/../target/riscv32im-risc0-zkvm-elf/docker/multisig.bin", manifest_dir)
+        });
+    let multisig_bytecode = std::fs::read(&multisig_path)
+        .unwrap_or_else(|_| panic!("Cannot read multisig binary at '{}'", multisig_path));
+    let (multisig_deploy_tx, multisig_program_id) = deploy_program(multisig_bytecode);
+
+    for (name, tx) in [("token", token_deploy_tx), ("multisig", multisig_deploy_tx)] {
+        match client.send_tx_program(tx).await {
+            Ok(r) => {
+                println!("  {} deployed: {}", name, r.tx_hash);
+                tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS)).await;
+            }
+            Err(e) => println!("  {} deploy skipped: {}", name, e),
+        }
+    }
+
+    // ── Create a 1-of-1 multisig with a wallet-held member ──────────────
+    println!("\n═══ STEP 1: Create 1-of-1 multisig with a wallet account ═══");
+    let wallet_home = wallet_home();
+    let member = new_wallet_account(&wallet_home);
+    println!("  Member (wallet account): {}", member);
+
+    let create_key: [u8; 32] = *AccountId::from(
+        &PublicKey::new_from_private_key(&PrivateKey::new_os_random())
+    ).value();
+    let multisig_state_id = compute_multisig_state_pda(&multisig_program_id, &create_key);
+    let vault_id = compute_vault_pda(&multisig_program_id, &create_key, 0);
+
+    let msg = Message::try_new(
+        multisig_program_id,
+        vec![multisig_state_id, member],
+        vec![],
+        Instruction::CreateMultisig {
+            create_key,
+            threshold: 1,
+            members: vec![*member.value()],
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
+        },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    println!("  ✅ Multisig created");
+
+    // ── Fund the vault ──────────────────────────────────────────────────
+    println!("\n═══ STEP 2: Fund vault 0 ═══");
+    let def_id = account_id_from_key(&PrivateKey::new_os_random());
+    let minter_holding_key = PrivateKey::new_os_random();
+    let minter_holding_id = account_id_from_key(&minter_holding_key);
+    let msg = Message::try_new(
+        token_program_id,
+        vec![def_id, minter_holding_id],
+        vec![],
+        TokenInstruction::NewFungibleDefinition { name: "FfiToken".to_string(), total_supply: 1_000 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    let nonce = get_nonce(&client, minter_holding_id).await;
+    let msg = Message::try_new(
+        token_program_id,
+        vec![minter_holding_id, vault_id],
+        vec![nonce],
+        TokenInstruction::Transfer { amount_to_transfer: 500 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[&minter_holding_key]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    assert_eq!(get_balance(&client, vault_id).await, Some(500), "Vault should have 500 tokens");
+    println!("  ✅ Vault funded with 500 tokens");
+
+    // ── Propose through the FFI ─────────────────────────────────────────
+    println!("\n═══ STEP 3: lez_multisig_propose ═══");
+    let recipient_id = account_id_from_key(&PrivateKey::new_os_random());
+    let target_instruction_data =
+        risc0_zkvm::serde::to_vec(&TokenInstruction::Transfer { amount_to_transfer: 200 }).unwrap();
+    let common_args = serde_json::json!({
+        "wallet_path": wallet_home,
+        "sequencer_url": sequencer_url(),
+        "program_id_hex": program_id_hex(&multisig_program_id),
+        "create_key": create_key,
+        "multisig_state": multisig_state_id.to_string(),
+    });
+
+    let mut args = common_args.clone();
+    args["proposer"] = serde_json::json!(member.to_string());
+    args["target_program_id"] = serde_json::json!(token_program_id);
+    args["target_instruction_data"] = serde_json::json!(target_instruction_data);
+    args["target_accounts"] = serde_json::json!([vault_id.value(), recipient_id.value()]);
+    args["pda_seeds"] = serde_json::json!([vault_pda_seed_bytes(&create_key, 0)]);
+    args["authorized_indices"] = serde_json::json!([0]);
+    args["expiry"] = serde_json::json!(0);
+    args["clock"] = serde_json::json!([]);
+    let result = ffi_call(lez_multisig_ffi::lez_multisig_propose, args);
+    assert_eq!(result["success"], true, "lez_multisig_propose failed: {}", result);
+
+    // The wrapper picked the next index (1); the proposal must be at the
+    // program's PDA for it.
+    let proposal_id = compute_proposal_pda(&multisig_program_id, &create_key, 1);
+    let proposal = wait_for_proposal(&client, proposal_id, |_| true).await;
+    assert_eq!(proposal.index, 1);
+    assert_eq!(proposal.approved, vec![*member.value()]);
+    println!("  ✅ Proposal #1 created at {}", proposal_id);
+
+    // ── Execute through the FFI ─────────────────────────────────────────
+    println!("\n═══ STEP 4: lez_multisig_execute ═══");
+    let mut args = common_args.clone();
+    args["executor"] = serde_json::json!(member.to_string());
+    args["proposal_index"] = serde_json::json!(1);
+    args["target_accounts"] = serde_json::json!([vault_id.to_string(), recipient_id.to_string()]);
+    let result = ffi_call(lez_multisig_ffi::lez_multisig_execute, args);
+    assert_eq!(result["success"], true, "lez_multisig_execute failed: {}", result);
+
+    let proposal = wait_for_proposal(&client, proposal_id, |p| p.status != ProposalStatus::Active).await;
+    assert_eq!(proposal.status, ProposalStatus::Executed);
+    assert_eq!(get_balance(&client, vault_id).await, Some(300), "Vault should have 300 tokens (500 - 200)");
+    assert_eq!(get_balance(&client, recipient_id).await, Some(200), "Recipient should have 200 tokens");
+
+    println!("\n🎉 FFI propose + execute e2e test PASSED!");
+}
@@ -214,6 +214,11 @@ async fn test_member_management() {
             create_key,
             threshold: 2,
             members: vec![*m1.value(), *m2.value(), *m3.value()],
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
         },
     ).unwrap();
     let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
@@ -135,6 +135,19 @@ fn deploy_program(bytecode: Vec<u8>) -> (ProgramDeploymentTransaction, nssa::Pro
     (ProgramDeploymentTransaction::new(msg), program_id)
 }
 
+/// Off-chain approvals map signing keys to members with nssa's public account
+/// derivation. Needs no sequencer.
+#[test]
+fn test_account_id_from_public_key_matches_nssa() {
+    for _ in 0..8 {
+        let public_key = PublicKey::new_from_private_key(&PrivateKey::new_os_random());
+        assert_eq!(
+            multisig_core::account_id_from_public_key(public_key.value()),
+            *AccountId::from(&public_key).value(),
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_multisig_token_transfer() {
     let client = sequencer_client();
@@ -213,7 +226,7 @@ async fn test_multisig_token_transfer() {
     ).value();
 
     let multisig_state_id = compute_multisig_state_pda(&multisig_program_id, &create_key);
-    let vault_id = compute_vault_pda(&multisig_program_id, &create_key);
+    let vault_id = compute_vault_pda(&multisig_program_id, &create_key, 0);
 
     println!("  Multisig state PDA: {}", multisig_state_id);
     println!("  Vault PDA: {}", vault_id);
@@ -222,6 +235,11 @@ async fn test_multisig_token_transfer() {
         create_key,
         threshold: 2,
         members: vec![*m1.value(), *m2.value(), *m3.value()],
+        time_lock: 0,
+        clock_account: [0u8; 32],
+        default_expiry: 0,
+        weights: vec![],
+        permissions: vec![],
     };
     let msg = Message::try_new(
         multisig_program_id,
@@ -269,7 +287,7 @@ async fn test_multisig_token_transfer() {
     };
     let target_instruction_data = risc0_zkvm::serde::to_vec(&token_transfer_instruction).unwrap();
 
-    let vault_seed = vault_pda_seed_bytes(&create_key);
+    let vault_seed = vault_pda_seed_bytes(&create_key, 0);
 
     // Compute proposal PDA
     let proposal_id = compute_proposal_pda(&multisig_program_id, &create_key, 1);
@@ -280,9 +298,10 @@ async fn test_multisig_token_transfer() {
     let propose_instruction = Instruction::Propose {
         target_program_id: token_program_id,
         target_instruction_data: target_instruction_data.clone(),
-        target_account_count: 2,  // vault_holding + recipient_holding
+        target_accounts: vec![*vault_id.value(), *recipient_id.value()],  // vault_holding + recipient_holding
         pda_seeds: vec![vault_seed],
         authorized_indices: vec![0], // vault (index 0) gets is_authorized=true
+        expiry: 0,
         create_key,
         proposal_index: 1,
     };
@@ -0,0 +1,309 @@
+//! End-to-end test for spending limits.
+//!
+//! Flow:
+//! 1. Deploy token program + multisig program, create a token
+//! 2. Create a 2-of-2 multisig, fund vault 0
+//! 3. ProposeAddSpendingLimit (300 from vault 0, member 2 may spend), approve, Execute → limit PDA created
+//! 4. UseSpendingLimit: member 2 sends 200 to a recipient without a proposal
+//! 5. UseSpendingLimit for 150 more → refused (only 100 left), balances unchanged
+//!
+//! The limit has no period (a one-off allowance), so the test needs no clock account.
+//!
+//! Prerequisites: as e2e_multisig (SEQUENCER_URL, MULTISIG_PROGRAM, TOKEN_PROGRAM).
+
+use std::time::Duration;
+
+use nssa::{
+    AccountId, PrivateKey, ProgramDeploymentTransaction, PublicKey, PublicTransaction,
+    program::Program,
+    public_transaction::{Message, WitnessSet},
+};
+use multisig_core::{Instruction, Proposal, ProposalStatus, SpendingLimit};
+use lez_multisig_ffi::{
+    compute_multisig_state_pda, compute_proposal_pda, compute_spending_limit_pda, compute_vault_pda,
+};
+use common::sequencer_client::SequencerClient;
+use token_core::{Instruction as TokenInstruction, TokenHolding};
+
+const BLOCK_WAIT_SECS: u64 = 15;
+
+fn account_id_from_key(key: &PrivateKey) -> AccountId {
+    let pk = PublicKey::new_from_private_key(key);
+    AccountId::from(&pk)
+}
+
+fn sequencer_client() -> SequencerClient {
+    let url = std::env::var("SEQUENCER_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3040".to_string());
+    SequencerClient::new(url.parse().unwrap()).expect("Failed to create sequencer client")
+}
+
+async fn submit_tx(client: &SequencerClient, tx: PublicTransaction) {
+    let response = client.send_tx_public(tx).await.expect("Failed to submit tx");
+    let tx_hash = response.tx_hash.clone();
+    println!("  tx_hash: {}", tx_hash);
+
+    let max_wait = Duration::from_secs(BLOCK_WAIT_SECS * 3);
+    let poll_interval = Duration::from_secs(3);
+    let start = std::time::Instant::now();
+
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        match client.get_transaction_by_hash(tx_hash.clone()).await {
+            Ok(resp) if resp.transaction.is_some() => {
+                println!("  ✅ tx included in block");
+                return;
+            }
+            _ => {
+                if start.elapsed() > max_wait {
+                    panic!("❌ Transaction {} not included after {:?}", tx_hash, max_wait);
+                }
+            }
+        }
+    }
+}
+
+/// Submit a tx that we expect to fail (not get included).
+/// Returns true if it was correctly rejected/not included.
+async fn submit_tx_expect_failure(client: &SequencerClient, tx: PublicTransaction) -> bool {
+    match client.send_tx_public(tx).await {
+        Err(_) => {
+            println!("  ✅ Transaction rejected at submission (expected)");
+            true
+        }
+        Ok(response) => {
+            let tx_hash = response.tx_hash.clone();
+            println!("  tx_hash: {} (expecting non-inclusion)", tx_hash);
+            tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS * 2)).await;
+            match client.get_transaction_by_hash(tx_hash.clone()).await {
+                Ok(resp) if resp.transaction.is_some() => {
+                    println!("  ❌ Transaction was unexpectedly included!");
+                    false
+                }
+                _ => {
+                    println!("  ✅ Transaction not included (expected failure)");
+                    true
+                }
+            }
+        }
+    }
+}
+
+async fn get_nonce(client: &SequencerClient, account_id: AccountId) -> u128 {
+    client.get_account(account_id).await
+        .map(|r| r.account.nonce)
+        .unwrap_or(0)
+}
+
+async fn get_balance(client: &SequencerClient, account_id: AccountId) -> Option<u128> {
+    let resp = client.get_account(account_id).await.ok()?;
+    let data: Vec<u8> = resp.account.data.into();
+    let holding: TokenHolding = borsh::from_slice(&data).ok()?;
+    match holding {
+        TokenHolding::Fungible { balance, .. } => Some(balance),
+        _ => None,
+    }
+}
+
+async fn get_proposal(client: &SequencerClient, proposal_id: AccountId) -> Option<Proposal> {
+    let account = client.get_account(proposal_id).await.ok()?;
+    let data: Vec<u8> = account.account.data.into();
+    borsh::from_slice(&data).ok()
+}
+
+fn deploy_program(bytecode: Vec<u8>) -> (ProgramDeploymentTransaction, nssa::ProgramId) {
+    let program = Program::new(bytecode.clone()).expect("Invalid program");
+    let program_id = program.id();
+    let msg = nssa::program_deployment_transaction::Message::new(bytecode);
+    (ProgramDeploymentTransaction::new(msg), program_id)
+}
+
+/// Build and sign a single-signer multisig transaction.
+fn signed_tx(
+    program_id: nssa::ProgramId,
+    accounts: Vec<AccountId>,
+    nonce: u128,
+    instruction: Instruction,
+    key: &PrivateKey,
+) -> PublicTransaction {
+    let msg = Message::try_new(program_id, accounts, vec![nonce], instruction).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[key]);
+    PublicTransaction::new(msg, ws)
+}
+
+async fn get_limit(client: &SequencerClient, limit_id: AccountId) -> Option<SpendingLimit> {
+    let account = client.get_account(limit_id).await.ok()?;
+    let data: Vec<u8> = account.account.data.into();
+    borsh::from_slice(&data).ok()
+}
+
+#[tokio::test]
+async fn test_spending_limit_transfers_up_to_its_allowance() {
+    let client = sequencer_client();
+
+    // ── Deploy programs ─────────────────────────────────────────────────
+    println!("📦 Deploying programs...");
+    let token_path = std::env::var("TOKEN_PROGRAM")
+        .unwrap_or_else(|_| {
+            let home = std::env::var("HOME").expect("HOME env var not set");
+            format!("{}/lssa/artifacts/program_methods/token.bin", home)
+        });
+    let token_bytecode = std::fs::read(&token_path)
+        .unwrap_or_else(|_| panic!("Cannot read token binary at '{}'", token_path));
+    let (token_deploy_tx, token_program_id) = deploy_program(token_bytecode);
+
+    let multisig_path = std::env::var("MULTISIG_PROGRAM")
+        .unwrap_or_else(|_| panic!("MULTISIG_PROGRAM env var not set"));
+    let multisig_bytecode = std::fs::read(&multisig_path)
+        .unwrap_or_else(|_| panic!("Cannot read multisig binary at '{}'", multisig_path));
+    let (multisig_deploy_tx, program_id) = deploy_program(multisig_bytecode);
+
+    for (name, tx) in [("token", token_deploy_tx), ("multisig", multisig_deploy_tx)] {
+        match client.send_tx_program(tx).await {
+            Ok(r) => {
+                println!("  {} deployed: {}", name, r.tx_hash);
+                tokio::time::sleep(Duration::from_secs(BLOCK_WAIT_SECS)).await;
+            }
+            Err(e) => println!("  {} deploy skipped: {}", name, e),
+        }
+    }
+
+    // ── STEP 1: Create token ────────────────────────────────────────────
+    println!("\n═══ STEP 1: Create fungible token ═══");
+    let def_id = account_id_from_key(&PrivateKey::new_os_random());
+    let minter_holding_key = PrivateKey::new_os_random();
+    let minter_holding_id = account_id_from_key(&minter_holding_key);
+
+    let msg = Message::try_new(
+        token_program_id,
+        vec![def_id, minter_holding_id],
+        vec![],
+        TokenInstruction::NewFungibleDefinition { name: "LimitToken".to_string(), total_supply: 1_000_000 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    assert_eq!(get_balance(&client, minter_holding_id).await, Some(1_000_000));
+
+    // ── STEP 2: Create 2-of-2 multisig, fund vault 0 ────────────────────
+    println!("\n═══ STEP 2: Create 2-of-2 multisig, fund vault 0 ═══");
+    let key1 = PrivateKey::new_os_random();
+    let key2 = PrivateKey::new_os_random();
+    let m1 = account_id_from_key(&key1);
+    let m2 = account_id_from_key(&key2);
+    let create_key: [u8; 32] = *account_id_from_key(&PrivateKey::new_os_random()).value();
+    let multisig_state_id = compute_multisig_state_pda(&program_id, &create_key);
+    let vault_0 = compute_vault_pda(&program_id, &create_key, 0);
+
+    let msg = Message::try_new(
+        program_id,
+        vec![multisig_state_id, m1, m2],
+        vec![],
+        Instruction::CreateMultisig {
+            create_key,
+            threshold: 2,
+            members: vec![*m1.value(), *m2.value()],
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
+        },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[] as &[&PrivateKey]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+
+    let msg = Message::try_new(
+        token_program_id,
+        vec![minter_holding_id, vault_0],
+        vec![get_nonce(&client, minter_holding_id).await],
+        TokenInstruction::Transfer { amount_to_transfer: 500 },
+    ).unwrap();
+    let ws = WitnessSet::for_message(&msg, &[&minter_holding_key]);
+    submit_tx(&client, PublicTransaction::new(msg, ws)).await;
+    assert_eq!(get_balance(&client, vault_0).await, Some(500));
+    println!("  ✅ Vault 0 holds 500");
+
+    // ── STEP 3: Create the limit through a config proposal ──────────────
+    println!("\n═══ STEP 3: ProposeAddSpendingLimit, approve, execute ═══");
+    let limit_key: [u8; 32] = *account_id_from_key(&PrivateKey::new_os_random()).value();
+    let limit_id = compute_spending_limit_pda(&program_id, &create_key, &limit_key);
+    let proposal_id = compute_proposal_pda(&program_id, &create_key, 1);
+
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_id],
+        get_nonce(&client, m1).await,
+        Instruction::ProposeAddSpendingLimit {
+            limit_key,
+            vault_index: 0,
+            token_program_id,
+            amount: 300,
+            period: 0,
+            members: vec![*m2.value()],
+            create_key,
+            proposal_index: 1,
+        },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m2, proposal_id],
+        get_nonce(&client, m2).await,
+        Instruction::Approve { create_key, proposal_index: 1 },
+        &key2,
+    );
+    submit_tx(&client, tx).await;
+
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m1, proposal_id, limit_id],
+        get_nonce(&client, m1).await,
+        Instruction::Execute { create_key, proposal_index: 1 },
+        &key1,
+    );
+    submit_tx(&client, tx).await;
+
+    let proposal = get_proposal(&client, proposal_id).await.expect("proposal stored");
+    assert_eq!(proposal.status, ProposalStatus::Executed);
+    let limit = get_limit(&client, limit_id).await.expect("limit PDA created");
+    assert_eq!(limit.remaining, 300);
+    assert_eq!(limit.members, vec![*m2.value()]);
+    println!("  ✅ Spending limit of 300 created");
+
+    // ── STEP 4: Spend under the limit without a proposal ────────────────
+    println!("\n═══ STEP 4: UseSpendingLimit for 200 ═══");
+    let recipient = account_id_from_key(&PrivateKey::new_os_random());
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m2, limit_id, vault_0, recipient],
+        get_nonce(&client, m2).await,
+        Instruction::UseSpendingLimit { amount: 200, limit_key, create_key },
+        &key2,
+    );
+    submit_tx(&client, tx).await;
+
+    assert_eq!(get_balance(&client, recipient).await, Some(200));
+    assert_eq!(get_balance(&client, vault_0).await, Some(300));
+    assert_eq!(get_limit(&client, limit_id).await.expect("limit").remaining, 100);
+    println!("  ✅ Recipient received 200; 100 left on the limit");
+
+    // ── STEP 5: Overspending is refused ─────────────────────────────────
+    println!("\n═══ STEP 5: UseSpendingLimit for 150 (only 100 left) ═══");
+    let tx = signed_tx(
+        program_id,
+        vec![multisig_state_id, m2, limit_id, vault_0, recipient],
+        get_nonce(&client, m2).await,
+        Instruction::UseSpendingLimit { amount: 150, limit_key, create_key },
+        &key2,
+    );
+    let failed = submit_tx_expect_failure(&client, tx).await;
+    assert!(failed, "Spending past the allowance should be refused");
+
+    assert_eq!(get_balance(&client, recipient).await, Some(200));
+    assert_eq!(get_balance(&client, vault_0).await, Some(300));
+    assert_eq!(get_limit(&client, limit_id).await.expect("limit").remaining, 100);
+
+    println!("\n🎉 Spending limit e2e test PASSED!");
+}
@@ -0,0 +1,26 @@
+// Appends the program's error list to an IDL read from stdin.
+//
+// The #[lez_program] IDL has no notion of program-specific errors, so the
+// stable MultisigError codes are merged in as a separate pass:
+//
+//   cargo run -p lez-multisig-idl-gen --bin generate_idl \
+//     | cargo run -p lez-multisig-idl-gen --bin add_idl_errors > multisig_idl.json
+
+use std::io::Read;
+
+use multisig_core::MultisigError;
+use serde_json::{Value, json};
+
+fn main() {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).expect("failed to read IDL from stdin");
+    let mut idl: Value = serde_json::from_str(&input).expect("stdin is not valid IDL JSON");
+
+    let errors: Vec<Value> = MultisigError::ALL
+        .iter()
+        .map(|e| json!({ "code": e.code(), "name": e.name(), "msg": e.message() }))
+        .collect();
+    idl["errors"] = Value::Array(errors);
+
+    println!("{}", serde_json::to_string_pretty(&idl).unwrap());
+}
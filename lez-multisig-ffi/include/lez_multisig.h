@@ -10,6 +10,10 @@
  * JSON error response format:
  *   { "success": false, "error": "<message>" }
  *
+ * When a transaction fails inside the program, the error also carries the
+ * stable program error (see "errors" in the IDL):
+ *   { "success": false, "error": "...", "error_code": 6010, "error_name": "ThresholdNotReached" }
+ *
  * JSON success response format varies by function (documented inline).
  */
 
@@ -33,8 +37,13 @@ extern "C" {
  *   "multisig_program_id": "hex64",
  *   "account":             "<signer AccountId>",
  *   "create_key":          "hex64  (unique key for PDA derivation)",
- *   "threshold":           2,
- *   "members":             ["hex64", "hex64", ...]
+ *   "threshold":           2  (required approval weight; = signatures when unweighted),
+ *   "members":             ["hex64", "hex64", ...],
+ *   "weights":             [2, 2, 1]  (vote weight per member, in members order; [] = one vote each),
+ *   "permissions":         [7, 7, 5]  (per member: Initiate = 1 | Vote = 2 | Execute = 4; [] = all),
+ *   "time_lock":           0  (delay between threshold and execute, in clock units; 0 = none),
+ *   "clock_account":       "hex64  (clock account read for the time-lock and expiry)",
+ *   "default_expiry":      0  (lifetime of proposals that don't set one, in clock units; 0 = never expire)
  * }
  *
  * Returns: {
@@ -49,6 +58,11 @@ char* lez_multisig_create(const char* args_json);
 /**
  * Create a new proposal in a multisig.
  *
+ * `proposal_index` must be the multisig's next index (transaction_index + 1).
+ * Omit it and it is fetched from the state account; when another proposal
+ * takes it first (ProposalIndexConflict), it is fetched again and the call
+ * resubmitted, up to 3 times. Every lez_multisig_propose_* function does this.
+ *
  * args_json: {
  *   "sequencer_url":           "http://...",
  *   "wallet_path":             "...",
@@ -57,9 +71,12 @@ char* lez_multisig_create(const char* args_json);
  *   "create_key":              "hex64",
  *   "target_program_id":       "hex64",
  *   "target_instruction_data": "hex (encoded bytes)",
- *   "target_account_count":    3,
- *   "pda_seeds":               ["hex64", ...],
- *   "authorized_indices":      [0, 1]
+ *   "target_accounts":         ["hex64", ...]  (exact accounts Execute must pass, in order),
+ *   "pda_seeds":               ["hex64", ...]  (vault_pda_seed_bytes(create_key, vault_index) selects the vault),
+ *   "authorized_indices":      [0, 1],
+ *   "expiry":                  0  (lifetime in clock units; 0 = the multisig's default_expiry),
+ *   "proposal_index":          1  (optional: omitted = next index),
+ *   "clock":                   ["<clock AccountId>"]  (proposal expires, or time-locked multisig with threshold 1; else [])
  * }
  *
  * Returns: {
@@ -71,6 +88,322 @@ char* lez_multisig_create(const char* args_json);
  */
 char* lez_multisig_propose(const char* args_json);
 
+/**
+ * Propose adding a member. Needs the config threshold to execute; Execute
+ * may take the new member's account as a target, to claim it.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "new_member":          [u8 x 32]  (must not already be a member),
+ *   "proposal_index":      2  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_add_member(const char* args_json);
+
+/**
+ * Propose removing a member. Needs the config threshold to execute, and the
+ * threshold must stay within the remaining members' total weight.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "member":              [u8 x 32],
+ *   "proposal_index":      2  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_remove_member(const char* args_json);
+
+/**
+ * Propose changing the threshold (M). Needs the config threshold to execute;
+ * the new threshold must be between 1 and the members' total weight.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "new_threshold":       2,
+ *   "proposal_index":      2  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_change_threshold(const char* args_json);
+
+/**
+ * Create a batch proposal: an ordered list of calls approved with one vote
+ * (at most 8 calls and 32 distinct target accounts).
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "calls": [
+ *     {
+ *       "target_program_id":       [u32 x 8],
+ *       "target_instruction_data": [u32, ...],
+ *       "target_accounts":         [[u8 x 32], ...],
+ *       "pda_seeds":               [[u8 x 32], ...],
+ *       "authorized_indices":      [0]  (indices into this call's target_accounts)
+ *     },
+ *     ...
+ *   ],
+ *   "expiry":              0  (lifetime in clock units; 0 = the multisig's default_expiry),
+ *   "proposal_index":      2  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (proposal expires, or time-locked multisig with threshold 1; else [])
+ * }
+ *
+ * Execute runs the calls in steps: each step takes the remaining calls up to
+ * the first one reusing an account (e.g. a second payment from the same
+ * vault), atomically, and the proposal stays Active until the last step.
+ * Each Execute takes the step's target accounts, each listed once, in order
+ * of first use; list_proposals reports them as "target_accounts".
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_batch(const char* args_json);
+
+/**
+ * Propose changing the execution time-lock: once executed, a proposal that
+ * reaches threshold can only be executed `time_lock` clock units later, as
+ * read from `clock_account`. Needs the config threshold to execute.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "time_lock":           3600  (clock units; 0 = no delay),
+ *   "clock_account":       [u8 x 32]  (account whose data is the current time, u64 LE),
+ *   "proposal_index":      3  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_set_time_lock(const char* args_json);
+
+/**
+ * Propose several config changes, applied together by one Execute (1 to 8
+ * actions, in order). Only the resulting config is validated, so e.g. a
+ * member rotation plus threshold change needs one round of approvals.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "actions": [
+ *     {"RemoveMember": {"member": [u8 x 32]}},
+ *     {"ChangeThreshold": {"new_threshold": 2}},
+ *     ...
+ *   ],
+ *   "proposal_index":      3  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_config_batch(const char* args_json);
+
+/**
+ * Propose rotating a member key: `old` is replaced by `new` in place, so the
+ * member count and threshold are unchanged. Needs M approvals to execute.
+ * Votes already cast by `old` stop counting once it executes.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "old":                 [u8 x 32]  (current member key),
+ *   "new":                 [u8 x 32]  (must not already be a member),
+ *   "proposal_index":      4  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_replace_member(const char* args_json);
+
+/**
+ * Propose changing one member's vote weight. An unweighted multisig first
+ * gives every member weight 1. Needs the config threshold to execute, and the
+ * threshold must stay within the members' total weight.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "member":              [u8 x 32],
+ *   "weight":              2  (at least 1),
+ *   "proposal_index":      4  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_set_weight(const char* args_json);
+
+/**
+ * Propose changing one member's permission flags: Initiate = 1 (propose),
+ * Vote = 2 (approve, reject, abstain), Execute = 4. Needs the config threshold
+ * to execute. At least one member must keep Initiate and one Execute, and the
+ * threshold must stay reachable by the members that can vote.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "member":              [u8 x 32],
+ *   "permissions":         5  (e.g. Initiate | Execute for a bot key),
+ *   "proposal_index":      4  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Fails with InvalidPermissions when the result leaves no member able to
+ * initiate or execute.
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_set_permissions(const char* args_json);
+
+/**
+ * Propose a spending limit: once executed, the listed members may transfer up
+ * to `amount` tokens from vault `vault_index` per `period` with use_spending_limit, no
+ * proposal needed. Needs M approvals to execute; Execute takes the spending
+ * limit PDA (["multisig_limit__", create_key, limit_key]) as its only target.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "limit_key":           [u8 x 32]  (unique per limit of this multisig),
+ *   "vault_index":         0  (vault the limit spends from; below MAX_VAULTS),
+ *   "token_program_id":    [u32 x 8],
+ *   "amount":              1000  (allowance per period),
+ *   "period":              86400  (clock units; 0 = one-off allowance, never refilled),
+ *   "members":             [[u8 x 32], ...]  (current members allowed to spend),
+ *   "proposal_index":      5  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_add_spending_limit(const char* args_json);
+
+/**
+ * Propose closing a spending limit. Needs M approvals to execute; Execute
+ * takes the spending limit PDA as its only target. A closed limit_key can't
+ * be reused.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "limit_key":           [u8 x 32],
+ *   "proposal_index":      6  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_remove_spending_limit(const char* args_json);
+
+/**
+ * Propose a separate threshold for one class of proposals. Needs the config
+ * threshold to execute, and must stay within the members' total weight.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "class":               "Config" | "Transfer" | {"Program": [u32 x 8]},
+ *   "threshold":           3  (0 = back to the default: threshold, or the transfer threshold for a program),
+ *   "proposal_index":      7  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_set_class_threshold(const char* args_json);
+
+/**
+ * Propose a target program policy. Once the allowlist is non-empty, Propose
+ * and Execute only accept calls to listed programs; denied programs are never
+ * callable. Needs the config threshold to execute.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<proposer AccountId>",
+ *   "create_key":          "hex64",
+ *   "target_program_id":   [u32 x 8],
+ *   "policy":              "Allowed" | "Denied" | "Default"  (Default = off both lists),
+ *   "proposal_index":      8  (optional: omitted = next index),
+ *   "clock":               ["<clock AccountId>"]  (multisig has a default_expiry, or is time-locked with threshold 1; else [])
+ * }
+ *
+ * Fails with ProgramNotAllowed on later proposals outside the policy.
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_propose_set_program_policy(const char* args_json);
+
+/**
+ * Transfer tokens from the vault under a spending limit, without a proposal.
+ * The signer must be a multisig member listed in the limit. The allowance is
+ * refilled at each multiple of the limit's period on the multisig's clock.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<spender AccountId>",
+ *   "create_key":          "hex64",
+ *   "limit_key":           [u8 x 32],
+ *   "amount":              250,
+ *   "transfer_accounts":   ["<clock AccountId>", "<vault AccountId>", "<recipient AccountId>"]
+ *                          (clock only for limits with a period)
+ * }
+ *
+ * Fails with SpendingLimitExceeded when amount is above the remaining allowance.
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_use_spending_limit(const char* args_json);
+
 /**
  * Approve an existing proposal.
  *
@@ -80,24 +413,66 @@ char* lez_multisig_propose(const char* args_json);
  *   "multisig_program_id": "hex64",
  *   "account":             "<approver AccountId>",
  *   "create_key":          "hex64",
- *   "proposal_index":      1
+ *   "proposal_index":      1,
+ *   "clock":               ["<clock AccountId>"]  (time-locked multisig or expiring proposal; else [])
  * }
  *
+ * Fails with ProposalExpired once the proposal's expires_at has passed.
+ *
  * Returns: { "success": true, "tx_hash": "0x...", "proposal_index": 1, "action": "approved" }
  */
 char* lez_multisig_approve(const char* args_json);
 
 /**
  * Reject an existing proposal.
+ * If the clock account is passed and the proposal has expired, it is marked
+ * Expired instead of recording the rejection.
  *
- * args_json: (same as approve)
+ * args_json: (same as approve; "clock" is optional here)
  *
  * Returns: { "success": true, "tx_hash": "0x...", "proposal_index": 1, "action": "rejected" }
  */
 char* lez_multisig_reject(const char* args_json);
 
+/**
+ * Withdraw the signer's approval, rejection or abstention on an Active
+ * proposal. A withdrawn approval can drop the proposal below threshold again
+ * (restarting the time-lock once it is reached).
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<voter AccountId>",
+ *   "create_key":          "hex64",
+ *   "proposal_index":      1
+ * }
+ *
+ * Fails with NoVoteToWithdraw if the signer has not voted.
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_withdraw_vote(const char* args_json);
+
+/**
+ * Abstain on an Active proposal, replacing an earlier approval or rejection.
+ * Abstentions never count toward threshold; like rejections, they can leave
+ * the proposal unable to pass, which marks it Rejected.
+ *
+ * args_json: (same as withdraw_vote)
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_abstain(const char* args_json);
+
 /**
  * Execute a fully-approved proposal.
+ * On a time-locked multisig, fails with TimeLockNotElapsed until time_lock
+ * has passed since the proposal reached threshold; the clock account must
+ * then be passed ahead of the target accounts. The same applies to a proposal
+ * with an expiry, which fails with ProposalExpired once expires_at has passed.
+ * A config proposal that adds members may be given their accounts, ahead of any
+ * spending limit PDAs, to claim them as CreateMultisig does.
  *
  * args_json: {
  *   "sequencer_url":       "http://...",
@@ -112,6 +487,138 @@ char* lez_multisig_reject(const char* args_json);
  */
 char* lez_multisig_execute(const char* args_json);
 
+/**
+ * Approve a proposal and, if this approval brings it to threshold, execute it
+ * in the same transaction. Takes execute's accounts; the targets are validated
+ * against the proposal only when it executes. On a time-locked multisig it
+ * only approves (the time-lock starts at threshold), so call execute later.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<approver AccountId>",
+ *   "create_key":          "hex64",
+ *   "proposal_index":      1,
+ *   "target_accounts":     ["<clock AccountId>", "<target AccountId>", ...]
+ *                          (clock only for time-locked multisigs or expiring proposals)
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_approve_and_execute(const char* args_json);
+
+/**
+ * Submit approvals members signed off-chain (see lez_multisig_sign_approval
+ * and lez_multisig_bundle_signatures). The relayer signs and pays for the
+ * transaction but need not be a member; each signature is verified in the
+ * program against the member account derived from its public key.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<relayer AccountId>",
+ *   "create_key":          "hex64",
+ *   "proposal_index":      1,
+ *   "signatures":          [{ "public_key": [...], "signature": [...] }, ...],
+ *   "clock":               ["<clock AccountId>"]  (only for time-locked multisigs or expiring proposals)
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_approve_with_signatures(const char* args_json);
+
+/**
+ * Create a member's voter record PDA, through which the member acts with their
+ * ordinary wallet key (lez_multisig_act_as_voter) instead of a claimed member
+ * account. Anyone may submit it; the record grants nothing on its own.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "multisig_state":      "<state PDA>",
+ *   "create_key":          "hex64",
+ *   "member":              "hex64"  (the member's account ID)
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_create_voter_record(const char* args_json);
+
+/**
+ * Run an instruction a member signed with lez_multisig_sign_voter_call. The
+ * voter record takes the instruction's signer slot; pass the rest of its
+ * accounts in their usual order. Needs no signer, so anyone can submit it.
+ *
+ * args_json: {
+ *   "sequencer_url":        "http://...",
+ *   "wallet_path":          "...",
+ *   "multisig_program_id":  "hex64",
+ *   "multisig_state":       "<state PDA>",
+ *   "voter_record":         "<voter record PDA>",
+ *   "create_key":           "hex64",
+ *   "voter_signature":      { "public_key": [...], "signature": [...] },
+ *   "instruction":          [u32, ...]  (from lez_multisig_sign_voter_call),
+ *   "instruction_accounts": ["<AccountId>", ...]  (the instruction's accounts after its signer)
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_act_as_voter(const char* args_json);
+
+/**
+ * Move the whole native balance of a claimed member account to recipient, once
+ * the account is no longer a member of the multisig (e.g. after RemoveMember)
+ * or the multisig is closed, and release the account. It stays owned by the
+ * multisig program (LEZ never changes owners), but another multisig can claim
+ * it again when it signs.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "multisig_state":      "<state PDA>",
+ *   "create_key":          "hex64",
+ *   "member_account":      "<AccountId>"  (signer: the removed member's account),
+ *   "recipient":           "<AccountId>"
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_release_member_account(const char* args_json);
+
+/**
+ * Close a finished (Executed, Rejected, Cancelled or Expired) proposal, wiping its
+ * account data. Active proposals cannot be closed. A closed proposal PDA
+ * stays owned by the program, so it can never be proposed into again.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "account":             "<closer AccountId>",
+ *   "create_key":          "hex64",
+ *   "proposal_index":      3,
+ *   "older_proposals":     ["<proposal PDA>", ...]  (finished proposals with index < proposal_index; may be empty)
+ * }
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_close_proposal(const char* args_json);
+
+/**
+ * Cancel an Active proposal (including an approved one not yet executed).
+ * The proposer cancels their own proposal immediately; any other member adds
+ * a cancel vote, and the proposal becomes Cancelled at threshold votes.
+ *
+ * args_json: (same as approve)
+ *
+ * Returns: { "success": true, "tx_hash": "0x..." }
+ */
+char* lez_multisig_cancel(const char* args_json);
+
 /**
  * List proposals for a multisig.
  *
@@ -129,10 +636,20 @@ char* lez_multisig_execute(const char* args_json);
  *       "index": 1,
  *       "proposer": "hex64",
  *       "target_program_id": "hex64",
- *       "target_account_count": 3,
+ *       "target_accounts": ["base58", ...]  (accounts the next Execute must pass, in order),
+ *       "call_count": 1  (ChainedCalls emitted on execute; > 1 for a batch),
+ *       "executed_calls": 0  (batch calls run by earlier Execute steps),
+ *       "config_action_count": 0  (config changes applied on execute instead of calls),
  *       "approved_count": 2,
+ *       "approval_weight": 2  (weight of approvals from current members; compared to threshold),
  *       "rejected_count": 0,
- *       "status": "Active|Approved|Rejected|Executed",
+ *       "abstained_count": 0,
+ *       "status": "Active|Executed|Rejected|Cancelled|Expired",
+ *       "stale": false  (created before the last config change; can't be approved/executed),
+ *       "threshold_reached_at": 1200  (clock time, time-locked multisigs only; else null),
+ *       "expires_at": 86400  (clock time the proposal expires; null = never),
+ *       "expired": false  (Expired, or Active and past expires_at per the clock account),
+ *       "vaults": [0]  (indices of the vaults the proposal's calls sign for),
  *       "proposal_pda": "..."
  *     },
  *     ...
@@ -157,15 +674,148 @@ char* lez_multisig_list_proposals(const char* args_json);
  *   "state": {
  *     "create_key": "hex64",
  *     "threshold": 2,
+ *     "config_threshold": 3  (applies to config proposals),
+ *     "transfer_threshold": 2  (applies to proposals that emit calls),
+ *     "program_thresholds": [{"program_id": [u32 x 8], "threshold": 1}, ...]  (override transfer_threshold),
+ *     "allowed_programs": [[u32 x 8], ...]  (empty = any program not denied),
+ *     "denied_programs": [[u32 x 8], ...],
  *     "member_count": 3,
  *     "members": ["hex64", ...],
- *     "transaction_index": 5
+ *     "weights": [1, 1, 1]  (one per member, also for unweighted multisigs),
+ *     "total_weight": 3  (members without Vote permission weigh 0),
+ *     "permissions": [7, 7, 7]  (Initiate = 1 | Vote = 2 | Execute = 4, one per member),
+ *     "transaction_index": 5,
+ *     "stale_transaction_index": 3,
+ *     "time_lock": 0,
+ *     "clock_account": "hex64",
+ *     "default_expiry": 0,
+ *     "last_clock": 0  (latest clock time the multisig read; an earlier one is refused),
+ *     "vaults": [1, 3]  (indexed vaults referenced by proposals or spending limits)
  *   },
  *   "multisig_state_pda": "..."
  * }
  */
 char* lez_multisig_get_state(const char* args_json);
 
+/**
+ * Get the index the multisig's next proposal must use, and its proposal PDA.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "create_key":          "hex64"
+ * }
+ * or, with the state account's owner as the program:
+ * args_json: { "sequencer_url": "...", "wallet_path": "...", "multisig_state": "<state PDA>" }
+ *
+ * Returns: { "success": true, "proposal_index": 6, "proposal_pda": "..." }
+ */
+char* lez_multisig_next_proposal_index(const char* args_json);
+
+/**
+ * List the vaults a multisig uses with their holdings: vault 0 plus every
+ * indexed vault a proposal or spending limit has referenced.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "create_key":          "hex64"
+ * }
+ *
+ * Returns: {
+ *   "success": true,
+ *   "vaults": [
+ *     {
+ *       "vault_index": 0,
+ *       "account_id": "base58",
+ *       "balance": "0"  (native balance, decimal string),
+ *       "data": "hex"  (account data, e.g. a token holding)
+ *     },
+ *     ...
+ *   ]
+ * }
+ */
+char* lez_multisig_get_vaults(const char* args_json);
+
+/**
+ * Sign the signer's approval digest for a proposal with their wallet key.
+ * Nothing is sent on-chain; hand the result to whoever relays the approvals.
+ * The digest includes the signer's vote nonce on the proposal: after they
+ * reject, abstain or withdraw, sign again.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "create_key":          "hex64",
+ *   "proposal_index":      1,
+ *   "signer":              "<member AccountId>"
+ * }
+ *
+ * Returns: {
+ *   "success": true,
+ *   "member": "<member AccountId>",
+ *   "proposal_index": 1,
+ *   "digest": "hex64",
+ *   "signature": { "public_key": [...], "signature": [...] }
+ * }
+ */
+char* lez_multisig_sign_approval(const char* args_json);
+
+/**
+ * Bundle collected approvals into the "signatures" argument of
+ * lez_multisig_approve_with_signatures. Offline: each member signs their own
+ * digest, so every signature is checked against the digest in its result; all
+ * results must be for the same proposal, and duplicates are dropped.
+ *
+ * args_json: {
+ *   "approvals": [<lez_multisig_sign_approval result>, ...]
+ * }
+ *
+ * Returns: { "success": true, "signatures": [{ "public_key": [...], "signature": [...] }, ...] }
+ */
+char* lez_multisig_bundle_signatures(const char* args_json);
+
+/**
+ * Sign an instruction for lez_multisig_act_as_voter with the member's wallet
+ * key, at their voter record's current nonce. Nothing is sent on-chain, and
+ * the signature is spent once the call runs.
+ *
+ * args_json: {
+ *   "sequencer_url":       "http://...",
+ *   "wallet_path":         "...",
+ *   "multisig_program_id": "hex64",
+ *   "create_key":          "hex64",
+ *   "signer":              "<member AccountId>",
+ *   "instruction":         { "Approve": { "proposal_index": 1, "create_key": [...] } }
+ * }
+ *
+ * Returns: {
+ *   "success": true,
+ *   "voter_record": "<voter record PDA>",
+ *   "nonce": 0,
+ *   "instruction": [u32, ...],
+ *   "voter_signature": { "public_key": [...], "signature": [...] }
+ * }
+ */
+char* lez_multisig_sign_voter_call(const char* args_json);
+
+/**
+ * Decode a program error code (or a failure message) into its named MultisigError.
+ *
+ * args_json: { "code": 6010 }  or  { "error": "<failure message>" }
+ *
+ * Returns: {
+ *   "success": true,
+ *   "code": 6010,
+ *   "name": "ThresholdNotReached",
+ *   "message": "Proposal does not have enough approvals"
+ * }
+ */
+char* lez_multisig_decode_error(const char* args_json);
+
 /* ── Memory Management ───────────────────────────────────────────────────── */
 
 /**
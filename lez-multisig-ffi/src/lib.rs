@@ -5,34 +5,21 @@
 //! generated extern "C" symbols under the canonical `lez_multisig_*` names
 //! and adds read-only query helpers not covered by the IDL.
 
+// scripts/patch-ffi-pda.py rewrites the generated compute_pda to derive
+// addresses through multisig_core::pda, the derivation the program itself uses.
+#[allow(dead_code)]
 mod multisig;
 
-// Re-export generated PDA compute helpers for use by tests and other crates.
-pub use multisig::{compute_multisig_state_pda, compute_proposal_pda};
-
-// Vault PDA helpers — derived from program_id + create_key
-// Seeds: SHA256(pad("multisig_vault___") || create_key)
-pub fn vault_pda_seed_bytes(create_key: &[u8; 32]) -> [u8; 32] {
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    let mut padded = [0u8; 32];
-    let src: &[u8] = b"multisig_vault___";
-    padded[..src.len()].copy_from_slice(src);
-    hasher.update(&padded);
-    hasher.update(create_key);
-    hasher.finalize().into()
-}
-
-pub fn compute_vault_pda(
-    program_id: &nssa_core::program::ProgramId,
-    create_key: &[u8; 32],
-) -> nssa_core::account::AccountId {
-    use nssa_core::program::PdaSeed;
-    use nssa_core::account::AccountId;
-    let seed = vault_pda_seed_bytes(create_key);
-    let pda_seed = PdaSeed::new(seed);
-    AccountId::from((program_id, &pda_seed))
-}
+// The generated wrappers' PDA derivation, exported so
+// tests/pda_conformance.rs can check it against docs/pda-test-vectors.json.
+#[doc(hidden)]
+pub use multisig::compute_pda;
+
+// PDA compute helpers for use by tests and other crates.
+pub use multisig_core::pda::{
+    compute_multisig_state_pda, compute_proposal_pda, compute_spending_limit_pda,
+    compute_vault_pda, compute_voter_record_pda, vault_pda_seed_bytes,
+};
 
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
@@ -52,31 +39,198 @@ fn error_str(msg: &str) -> *mut c_char {
     to_cstring(format!(r#"{{"success":false,"error":{}}}"#, serde_json::json!(msg)))
 }
 
+/// Annotate a failed generated call with `error_code` / `error_name` when the
+/// failure text carries a MultisigError (the guest aborts with its Display form).
+fn with_multisig_error(result: *mut c_char) -> *mut c_char {
+    if result.is_null() { return result; }
+    let body = unsafe { CString::from_raw(result) }.into_string().unwrap_or_default();
+    let mut v: serde_json::Value = match serde_json::from_str(&body) {
+        Ok(v) => v,
+        Err(_) => return to_cstring(body),
+    };
+    if let Some(err) = v["error"].as_str().and_then(multisig_core::MultisigError::from_error_text) {
+        v["error_code"] = serde_json::json!(err.code());
+        v["error_name"] = serde_json::json!(err.name());
+    }
+    to_cstring(v.to_string())
+}
+
+/// Times a propose wrapper submits when other proposals keep taking its index.
+const PROPOSE_ATTEMPTS: usize = 3;
+
+/// Submit a generated propose call at the multisig's next proposal index.
+/// Without a `proposal_index` argument the index is fetched from the state
+/// account, and fetched again and resubmitted while the program reports
+/// ProposalIndexConflict (another proposal took the index first).
+fn propose_at_next_index(args_json: *const c_char, submit: extern "C" fn(*const c_char) -> *mut c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    let mut v: serde_json::Value = match serde_json::from_str(args) {
+        Ok(v) => v, Err(e) => return error_str(&format!("invalid JSON: {}", e)),
+    };
+    if v["proposal_index"].is_u64() {
+        return with_multisig_error(submit(args_json));
+    }
+    let conflict = multisig_core::MultisigError::ProposalIndexConflict.code();
+    let mut attempt = 1;
+    loop {
+        match multisig_queries::fetch_next_proposal(&v) {
+            Ok((index, _)) => v["proposal_index"] = serde_json::json!(index),
+            Err(e) => return error_str(&e),
+        }
+        let request = match CString::new(v.to_string()) { Ok(s) => s, Err(_) => return error_str("null byte") };
+        let result = with_multisig_error(submit(request.as_ptr()));
+        let body = unsafe { CStr::from_ptr(result) }.to_string_lossy();
+        let retry = serde_json::from_str::<serde_json::Value>(&body)
+            .map(|r| r["error_code"] == conflict)
+            .unwrap_or(false);
+        if !retry || attempt == PROPOSE_ATTEMPTS {
+            return result;
+        }
+        drop(unsafe { CString::from_raw(result) });
+        attempt += 1;
+    }
+}
+
 // ── Generated instruction wrappers ───────────────────────────────────────────
 
 #[no_mangle]
 pub extern "C" fn lez_multisig_create(args_json: *const c_char) -> *mut c_char {
-    multisig::multisig_program_create_multisig(args_json)
+    with_multisig_error(multisig::multisig_program_create_multisig(args_json))
 }
 
 #[no_mangle]
 pub extern "C" fn lez_multisig_propose(args_json: *const c_char) -> *mut c_char {
-    multisig::multisig_program_propose(args_json)
+    propose_at_next_index(args_json, multisig::multisig_program_propose)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_add_member(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_add_member)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_remove_member(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_remove_member)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_change_threshold(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_change_threshold)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_batch(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_batch)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_set_time_lock(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_set_time_lock)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_config_batch(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_config_batch)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_replace_member(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_replace_member)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_set_weight(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_set_weight)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_set_permissions(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_set_permissions)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_add_spending_limit(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_add_spending_limit)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_remove_spending_limit(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_remove_spending_limit)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_set_class_threshold(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_set_class_threshold)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_propose_set_program_policy(args_json: *const c_char) -> *mut c_char {
+    propose_at_next_index(args_json, multisig::multisig_program_propose_set_program_policy)
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_use_spending_limit(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_use_spending_limit(args_json))
 }
 
 #[no_mangle]
 pub extern "C" fn lez_multisig_approve(args_json: *const c_char) -> *mut c_char {
-    multisig::multisig_program_approve(args_json)
+    with_multisig_error(multisig::multisig_program_approve(args_json))
 }
 
 #[no_mangle]
 pub extern "C" fn lez_multisig_reject(args_json: *const c_char) -> *mut c_char {
-    multisig::multisig_program_reject(args_json)
+    with_multisig_error(multisig::multisig_program_reject(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_approve_and_execute(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_approve_and_execute(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_approve_with_signatures(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_approve_with_signatures(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_create_voter_record(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_create_voter_record(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_act_as_voter(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_act_as_voter(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_release_member_account(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_release_member_account(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_withdraw_vote(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_withdraw_vote(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_abstain(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_abstain(args_json))
 }
 
 #[no_mangle]
 pub extern "C" fn lez_multisig_execute(args_json: *const c_char) -> *mut c_char {
-    multisig::multisig_program_execute(args_json)
+    with_multisig_error(multisig::multisig_program_execute(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_close_proposal(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_close_proposal(args_json))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_cancel(args_json: *const c_char) -> *mut c_char {
+    with_multisig_error(multisig::multisig_program_cancel(args_json))
 }
 
 #[no_mangle]
@@ -97,6 +251,28 @@ pub extern "C" fn lez_multisig_get_idl() -> *mut c_char {
 
 // ── Read-only helpers (not in IDL) ───────────────────────────────────────────
 
+#[no_mangle]
+pub extern "C" fn lez_multisig_decode_error(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    let v: serde_json::Value = match serde_json::from_str(args) {
+        Ok(v) => v, Err(e) => return error_str(&format!("invalid JSON: {}", e)),
+    };
+    let err = match (v["code"].as_u64(), v["error"].as_str()) {
+        (Some(code), _) => multisig_core::MultisigError::from_code(code as u32),
+        (None, Some(text)) => multisig_core::MultisigError::from_error_text(text),
+        (None, None) => return error_str("expected \"code\" or \"error\""),
+    };
+    match err {
+        Some(e) => to_cstring(serde_json::json!({
+            "success": true,
+            "code": e.code(),
+            "name": e.name(),
+            "message": e.message(),
+        }).to_string()),
+        None => error_str("not a MultisigError"),
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn lez_multisig_list_proposals(args_json: *const c_char) -> *mut c_char {
     let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
@@ -109,11 +285,47 @@ pub extern "C" fn lez_multisig_get_state(args_json: *const c_char) -> *mut c_cha
     to_cstring(multisig_queries::get_state(args))
 }
 
-mod multisig_queries {
+#[no_mangle]
+pub extern "C" fn lez_multisig_next_proposal_index(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    to_cstring(multisig_queries::next_proposal_index(args))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_get_vaults(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    to_cstring(multisig_queries::get_vaults(args))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_sign_approval(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    to_cstring(multisig_queries::sign_approval(args))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_bundle_signatures(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    to_cstring(multisig_queries::bundle_signatures(args))
+}
+
+#[no_mangle]
+pub extern "C" fn lez_multisig_sign_voter_call(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) { Ok(s) => s, Err(e) => return error_str(&e) };
+    to_cstring(multisig_queries::sign_voter_call(args))
+}
+
+pub mod multisig_queries {
     use wallet::WalletCore;
     use serde_json::{Value, json};
-    use multisig_core::{MultisigState, Proposal};
-    use crate::multisig::{compute_proposal_pda, compute_multisig_state_pda};
+    use multisig_core::{
+        account_id_from_public_key, approval_digest, verify_approval, voter_call_digest,
+        ActionClass, Instruction, MemberSignature, MultisigState, Proposal, ProposalStatus,
+        VoterRecord,
+    };
+    use crate::{
+        compute_multisig_state_pda, compute_proposal_pda, compute_vault_pda, compute_voter_record_pda,
+    };
     use nssa_core::account::AccountId;
 
     fn load_wallet(v: &Value) -> Result<WalletCore, String> {
@@ -148,6 +360,14 @@ mod multisig_queries {
         }
     }
 
+    /// Read the current time (u64 LE) from the multisig's clock account, if it has data.
+    async fn fetch_clock(wallet: &WalletCore, state: &MultisigState) -> Result<Option<u64>, String> {
+        let acc = wallet.get_account_public(AccountId::new(state.clock_account)).await
+            .map_err(|e| format!("get_account: {}", e))?;
+        let data: Vec<u8> = acc.data.into();
+        Ok(data.get(..8).map(|b| u64::from_le_bytes(b.try_into().unwrap())))
+    }
+
     fn parse_account(s: &str) -> Result<AccountId, String> {
         s.parse().map_err(|e| format!("invalid account: {:?}", e))
     }
@@ -166,18 +386,40 @@ mod multisig_queries {
                 Some(s) => s,
                 None => return Err("multisig_state not found".to_string()),
             };
+            // Current time from the multisig's clock account, for reporting expiry
+            let now = if state.clock_account != [0u8; 32] {
+                fetch_clock(&wallet, &state).await?
+            } else {
+                None
+            };
             let mut proposals = Vec::new();
             for i in 0..state.transaction_index {
                 let prop_id = compute_proposal_pda(&program_id, &state.create_key, i);
                 if let Some(prop) = fetch_borsh::<Proposal>(&wallet, prop_id).await? {
                     let proposer_b58 = bs58::encode(prop.proposer).into_string();
+                    let target_accounts: Vec<String> = prop.execute_accounts().iter()
+                        .map(|a| bs58::encode(a).into_string())
+                        .collect();
                     proposals.push(json!({
                         "index": prop.index,
                         "status": format!("{:?}", prop.status),
                         "proposer": proposer_b58,
+                        "target_accounts": target_accounts,
+                        "call_count": prop.calls().len(),
+                        "executed_calls": prop.executed_calls,
+                        "config_action_count": prop.config_actions().len(),
                         "approvals": prop.approved.len(),
+                        "approval_weight": prop.approval_weight(&state),
                         "rejections": prop.rejected.len(),
-                        "threshold": state.threshold,
+                        "abstentions": prop.abstained.len(),
+                        "cancellations": prop.cancelled.len(),
+                        "stale": state.is_stale(prop.index),
+                        "threshold_reached_at": prop.threshold_reached_at,
+                        "expires_at": prop.expires_at,
+                        "expired": prop.status == ProposalStatus::Expired
+                            || (prop.status == ProposalStatus::Active && matches!(now, Some(t) if prop.is_expired(t))),
+                        "vaults": prop.vault_indices(),
+                        "threshold": state.threshold_for(&prop),
                     }));
                 }
             }
@@ -185,6 +427,19 @@ mod multisig_queries {
         }).unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
     }
 
+    fn parse_create_key(v: &Value) -> Result<[u8; 32], String> {
+        // Generated instruction calls take it as a byte array
+        if let Ok(create_key) = serde_json::from_value::<[u8; 32]>(v["create_key"].clone()) {
+            return Ok(create_key);
+        }
+        let create_key_hex = v["create_key"].as_str().ok_or("missing create_key")?;
+        let create_key_bytes = hex::decode(create_key_hex.trim_start_matches("0x"))
+            .map_err(|e| format!("create_key hex: {}", e))?;
+        let mut create_key = [0u8; 32];
+        create_key.copy_from_slice(&create_key_bytes);
+        Ok(create_key)
+    }
+
     pub fn get_state(args: &str) -> String {
         let v: Value = match serde_json::from_str(args) {
             Ok(v) => v,
@@ -194,11 +449,7 @@ mod multisig_queries {
         rt.block_on(async move {
             let wallet = load_wallet(&v)?;
             let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
-            let create_key_hex = v["create_key"].as_str().ok_or("missing create_key")?;
-            let create_key_bytes = hex::decode(create_key_hex.trim_start_matches("0x"))
-                .map_err(|e| format!("create_key hex: {}", e))?;
-            let mut create_key = [0u8; 32];
-            create_key.copy_from_slice(&create_key_bytes);
+            let create_key = parse_create_key(&v)?;
             let ms_id = compute_multisig_state_pda(&program_id, &create_key);
             match fetch_borsh::<MultisigState>(&wallet, ms_id).await? {
                 Some(state) => {
@@ -208,9 +459,25 @@ mod multisig_queries {
                     Ok(json!({
                         "success": true,
                         "threshold": state.threshold,
+                        "config_threshold": state.class_threshold(&ActionClass::Config),
+                        "transfer_threshold": state.class_threshold(&ActionClass::Transfer),
+                        "program_thresholds": state.program_thresholds.iter()
+                            .map(|p| json!({"program_id": p.program_id, "threshold": p.threshold}))
+                            .collect::<Vec<_>>(),
+                        "allowed_programs": state.allowed_programs,
+                        "denied_programs": state.denied_programs,
                         "member_count": state.member_count,
                         "members": members,
+                        "weights": state.members.iter().map(|m| state.weight_of(m)).collect::<Vec<_>>(),
+                        "total_weight": state.total_weight(),
+                        "permissions": state.members.iter().map(|m| state.permissions_of(m)).collect::<Vec<_>>(),
                         "transaction_index": state.transaction_index,
+                        "stale_transaction_index": state.stale_transaction_index,
+                        "time_lock": state.time_lock,
+                        "clock_account": bs58::encode(state.clock_account).into_string(),
+                        "default_expiry": state.default_expiry,
+                        "last_clock": state.last_clock,
+                        "vaults": state.vaults,
                         "multisig_state_id": ms_id.to_string(),
                     }).to_string())
                 }
@@ -218,4 +485,205 @@ mod multisig_queries {
             }
         }).unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
     }
+
+    /// The index the multisig's next proposal must use (`transaction_index + 1`)
+    /// and its proposal PDA. The multisig is `multisig_state` when given (its
+    /// owner is the program), else the state PDA of program_id_hex and create_key.
+    pub(crate) fn fetch_next_proposal(v: &Value) -> Result<(u64, AccountId), String> {
+        let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+        rt.block_on(async move {
+            let wallet = load_wallet(v)?;
+            let (ms_id, program_id) = match v["multisig_state"].as_str() {
+                Some(id) => {
+                    let ms_id = parse_account(id)?;
+                    let acc = wallet.get_account_public(ms_id).await
+                        .map_err(|e| format!("get_account: {}", e))?;
+                    (ms_id, acc.program_owner)
+                }
+                None => {
+                    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+                    (compute_multisig_state_pda(&program_id, &parse_create_key(v)?), program_id)
+                }
+            };
+            match fetch_borsh::<MultisigState>(&wallet, ms_id).await? {
+                Some(state) => {
+                    let proposal_index = state.transaction_index + 1;
+                    Ok((proposal_index, compute_proposal_pda(&program_id, &state.create_key, proposal_index)))
+                }
+                None => Err("multisig_state not found".to_string()),
+            }
+        })
+    }
+
+    /// The next proposal index and its proposal PDA, for building a propose call.
+    pub fn next_proposal_index(args: &str) -> String {
+        let v: Value = match serde_json::from_str(args) {
+            Ok(v) => v,
+            Err(e) => return json!({"success": false, "error": format!("{}", e)}).to_string(),
+        };
+        fetch_next_proposal(&v)
+            .map(|(proposal_index, prop_id)| json!({
+                "success": true,
+                "proposal_index": proposal_index,
+                "proposal_pda": prop_id.to_string(),
+            }).to_string())
+            .unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
+    }
+
+    /// Every vault the multisig uses (vault 0 plus any indexed vault a proposal
+    /// or spending limit referenced), with its current holdings.
+    pub fn get_vaults(args: &str) -> String {
+        let v: Value = match serde_json::from_str(args) {
+            Ok(v) => v,
+            Err(e) => return json!({"success": false, "error": format!("{}", e)}).to_string(),
+        };
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let wallet = load_wallet(&v)?;
+            let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+            let create_key = parse_create_key(&v)?;
+            let ms_id = compute_multisig_state_pda(&program_id, &create_key);
+            let state: MultisigState = match fetch_borsh(&wallet, ms_id).await? {
+                Some(s) => s,
+                None => return Err("multisig_state not found".to_string()),
+            };
+            let mut indices = vec![0u8];
+            indices.extend(state.vaults.iter().filter(|i| **i != 0));
+            let mut vaults = Vec::new();
+            for vault_index in indices {
+                let vault_id = compute_vault_pda(&program_id, &create_key, vault_index);
+                let acc = wallet.get_account_public(vault_id).await
+                    .map_err(|e| format!("get_account: {}", e))?;
+                let data: Vec<u8> = acc.data.into();
+                vaults.push(json!({
+                    "vault_index": vault_index,
+                    "account_id": vault_id.to_string(),
+                    "balance": acc.balance.to_string(),
+                    "data": hex::encode(data),
+                }));
+            }
+            Ok::<String, String>(json!({"success": true, "vaults": vaults}).to_string())
+        }).unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
+    }
+
+    /// Sign the signer's approval digest for a proposal with their wallet key,
+    /// for a relayer to submit with ApproveWithSignatures. The digest is tied to
+    /// the signer's current vote nonce on the proposal, so the signature stops
+    /// verifying once they reject, abstain or withdraw. Nothing is sent on-chain.
+    pub fn sign_approval(args: &str) -> String {
+        let v: Value = match serde_json::from_str(args) {
+            Ok(v) => v,
+            Err(e) => return json!({"success": false, "error": format!("{}", e)}).to_string(),
+        };
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let wallet = load_wallet(&v)?;
+            let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+            let create_key = parse_create_key(&v)?;
+            let proposal_index = v["proposal_index"].as_u64().ok_or("missing proposal_index")?;
+            let signer = parse_account(v["signer"].as_str().ok_or("missing signer")?)?;
+            let prop_id = compute_proposal_pda(&program_id, &create_key, proposal_index);
+            let proposal: Proposal = match fetch_borsh(&wallet, prop_id).await? {
+                Some(p) => p,
+                None => return Err("proposal not found".to_string()),
+            };
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(signer)
+                .ok_or_else(|| format!("signing key not found for {}", signer))?;
+            let public_key = *nssa::PublicKey::new_from_private_key(key).value();
+            if account_id_from_public_key(&public_key) != *signer.value() {
+                return Err(format!("signing key does not derive account {}", signer));
+            }
+            let digest = approval_digest(&program_id, &proposal, signer.value());
+            let signature = nssa::Signature::new(key, &digest);
+            let member_signature = MemberSignature { public_key, signature: signature.value.to_vec() };
+            Ok::<String, String>(json!({
+                "success": true,
+                "member": signer.to_string(),
+                "proposal_index": proposal_index,
+                "digest": hex::encode(digest),
+                "signature": member_signature,
+            }).to_string())
+        }).unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
+    }
+
+    /// Collect `sign_approval` results into the `signatures` argument of
+    /// ApproveWithSignatures. Each member signs their own digest, so every
+    /// result is checked against the digest it carries; fails on a signature
+    /// that doesn't verify, or on results for different proposals, so a bad
+    /// one can't sink the transaction. Drops duplicates.
+    pub fn bundle_signatures(args: &str) -> String {
+        let v: Value = match serde_json::from_str(args) {
+            Ok(v) => v,
+            Err(e) => return json!({"success": false, "error": format!("{}", e)}).to_string(),
+        };
+        (|| {
+            let mut proposal_index = None;
+            let mut bundle: Vec<MemberSignature> = Vec::new();
+            for item in v["approvals"].as_array().ok_or("missing approvals")? {
+                let index = item["proposal_index"].as_u64().ok_or("approval missing proposal_index")?;
+                if *proposal_index.get_or_insert(index) != index {
+                    return Err(format!("approvals for proposals {} and {}", proposal_index.unwrap(), index));
+                }
+                let digest_bytes = hex::decode(item["digest"].as_str().ok_or("approval missing digest")?.trim_start_matches("0x"))
+                    .map_err(|e| format!("digest hex: {}", e))?;
+                let digest: [u8; 32] = digest_bytes.try_into().map_err(|_| "digest must be 32 bytes".to_string())?;
+                let sig: MemberSignature = serde_json::from_value(item["signature"].clone())
+                    .map_err(|e| format!("signature: {}", e))?;
+                if !verify_approval(&sig.public_key, &sig.signature, &digest) {
+                    let member = AccountId::new(account_id_from_public_key(&sig.public_key));
+                    return Err(format!("signature from {} does not match digest", member));
+                }
+                if !bundle.iter().any(|b| b.public_key == sig.public_key) {
+                    bundle.push(sig);
+                }
+            }
+            if bundle.is_empty() {
+                return Err("no signatures to bundle".to_string());
+            }
+            Ok::<String, String>(json!({"success": true, "signatures": bundle}).to_string())
+        })().unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
+    }
+
+    /// Sign an instruction with the member's wallet key for ActAsVoter, at their
+    /// voter record's current nonce. Nothing is sent on-chain; anyone can submit
+    /// the result, and it runs at most once.
+    pub fn sign_voter_call(args: &str) -> String {
+        let v: Value = match serde_json::from_str(args) {
+            Ok(v) => v,
+            Err(e) => return json!({"success": false, "error": format!("{}", e)}).to_string(),
+        };
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let wallet = load_wallet(&v)?;
+            let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+            let create_key = parse_create_key(&v)?;
+            let signer = parse_account(v["signer"].as_str().ok_or("missing signer")?)?;
+            let instruction: Instruction = serde_json::from_value(v["instruction"].clone())
+                .map_err(|e| format!("instruction: {}", e))?;
+            let record_id = compute_voter_record_pda(&program_id, &create_key, signer.value());
+            let record: VoterRecord = match fetch_borsh(&wallet, record_id).await? {
+                Some(r) => r,
+                None => return Err("voter record not found (create it with create_voter_record)".to_string()),
+            };
+            let words = nssa::program::Program::serialize_instruction(instruction)
+                .map_err(|e| format!("serialize: {:?}", e))?;
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(signer)
+                .ok_or_else(|| format!("signing key not found for {}", signer))?;
+            let public_key = *nssa::PublicKey::new_from_private_key(key).value();
+            if account_id_from_public_key(&public_key) != *signer.value() {
+                return Err(format!("signing key does not derive account {}", signer));
+            }
+            let digest = voter_call_digest(&program_id, &create_key, signer.value(), record.nonce, &words);
+            let signature = nssa::Signature::new(key, &digest);
+            Ok::<String, String>(json!({
+                "success": true,
+                "voter_record": record_id.to_string(),
+                "nonce": record.nonce,
+                "instruction": words,
+                "voter_signature": MemberSignature { public_key, signature: signature.value.to_vec() },
+            }).to_string())
+        }).unwrap_or_else(|e| json!({"success": false, "error": e}).to_string())
+    }
 }
@@ -10,7 +10,6 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use serde_json::{Value, json};
-use sha2::{Sha256, Digest};
 use nssa::{AccountId, ProgramId, PublicTransaction};
 use nssa::public_transaction::{Message, WitnessSet};
 use wallet::WalletCore;
@@ -33,16 +32,9 @@ fn error_json(msg: &str) -> *mut c_char {
     to_cstring(body)
 }
 
-fn compute_pda(seeds: &[&[u8]]) -> AccountId {
-    let mut hasher = Sha256::new();
-    for seed in seeds {
-        let mut padded = [0u8; 32];
-        let len = seed.len().min(32);
-        padded[..len].copy_from_slice(&seed[..len]);
-        hasher.update(&padded);
-    }
-    let hash: [u8; 32] = hasher.finalize().into();
-    AccountId::new(hash)
+/// PDA address for raw IDL seed segments, derived by multisig_core::pda.
+pub fn compute_pda(program_id: &ProgramId, seeds: &[&[u8]]) -> AccountId {
+    multisig_core::pda::compute_pda(program_id, seeds)
 }
 
 fn parse_program_id_hex(s: &str) -> Result<ProgramId, String> {
@@ -97,8 +89,14 @@ fn multisig_program_create_multisig_impl(args: &str) -> Result<String, String> {
     let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
     let threshold = v["threshold"].as_u64().ok_or("expected number")? as u8;
     let members = v["members"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
-
-    let multisig_state = compute_pda(&[
+    let time_lock = v["time_lock"].as_u64().ok_or("expected number")? as u64;
+    let clock_account = serde_json::from_value(v["clock_account"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let default_expiry = v["default_expiry"].as_u64().ok_or("expected number")? as u64;
+    let weights = v["weights"].as_array().ok_or("expected array")?.iter().map(|item| Ok(item.as_u64().ok_or("expected number")? as u8)).collect::<Result<Vec<_>, String>>()?;
+    let permissions = v["permissions"].as_array().ok_or("expected array")?.iter().map(|item| Ok(item.as_u64().ok_or("expected number")? as u8)).collect::<Result<Vec<_>, String>>()?;
+
+    let multisig_state = compute_pda(&program_id, &[
+        b"multisig_state__",
         &create_key as &[u8],
     ]);
     let member_accounts: Vec<AccountId> = v["member_accounts"].as_array()
@@ -116,6 +114,11 @@ fn multisig_program_create_multisig_impl(args: &str) -> Result<String, String> {
         create_key,
         threshold,
         members,
+        time_lock,
+        clock_account,
+        default_expiry,
+        weights,
+        permissions,
     };
 
     let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
@@ -159,25 +162,30 @@ fn multisig_program_propose_impl(args: &str) -> Result<String, String> {
 
     let target_program_id = serde_json::from_value(v["target_program_id"].clone()).map_err(|e| format!("parse error: {}", e))?;
     let target_instruction_data = serde_json::from_value(v["target_instruction_data"].clone()).map_err(|e| format!("parse error: {}", e))?;
-    let target_account_count = v["target_account_count"].as_u64().ok_or("expected number")? as u8;
+    let target_accounts = v["target_accounts"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
     let pda_seeds = v["pda_seeds"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
     let authorized_indices = v["authorized_indices"].as_array().ok_or("expected array")?.iter().map(|item| Ok(item.as_u64().ok_or("expected number")? as u8)).collect::<Result<Vec<_>, String>>()?;
+    let expiry = v["expiry"].as_u64().ok_or("expected number")? as u64;
     let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
     let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
     ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
 
     let mut account_ids: Vec<AccountId> = vec![
         multisig_state,
         proposer,
         proposal,
     ];
+    account_ids.extend(clock);
     let signer_ids: Vec<AccountId> = vec![
         proposer,
     ];
@@ -185,9 +193,10 @@ fn multisig_program_propose_impl(args: &str) -> Result<String, String> {
     let instruction = ProgramInstruction::Propose {
         target_program_id,
         target_instruction_data,
-        target_account_count,
+        target_accounts,
         pda_seeds,
         authorized_indices,
+        expiry,
         create_key,
         proposal_index,
     };
@@ -236,17 +245,21 @@ fn multisig_program_approve_impl(args: &str) -> Result<String, String> {
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let approver = parse_account_id(v["approver"].as_str().ok_or("missing approver")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
     ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
 
     let mut account_ids: Vec<AccountId> = vec![
         multisig_state,
         approver,
         proposal,
     ];
+    account_ids.extend(clock);
     let signer_ids: Vec<AccountId> = vec![
         approver,
     ];
@@ -300,17 +313,21 @@ fn multisig_program_reject_impl(args: &str) -> Result<String, String> {
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let rejector = parse_account_id(v["rejector"].as_str().ok_or("missing rejector")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
     ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
 
     let mut account_ids: Vec<AccountId> = vec![
         multisig_state,
         rejector,
         proposal,
     ];
+    account_ids.extend(clock);
     let signer_ids: Vec<AccountId> = vec![
         rejector,
     ];
@@ -364,7 +381,7 @@ fn multisig_program_execute_impl(args: &str) -> Result<String, String> {
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let executor = parse_account_id(v["executor"].as_str().ok_or("missing executor")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
@@ -411,6 +428,138 @@ fn multisig_program_execute_impl(args: &str) -> Result<String, String> {
     Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
 }
 
+/// FFI: close_proposal instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_close_proposal(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_close_proposal_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_close_proposal_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let closer = parse_account_id(v["closer"].as_str().ok_or("missing closer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let older_proposals: Vec<AccountId> = v["older_proposals"].as_array()
+        .ok_or("missing older_proposals")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        closer,
+        proposal,
+    ];
+    account_ids.extend(older_proposals);
+    let signer_ids: Vec<AccountId> = vec![
+        closer,
+    ];
+
+    let instruction = ProgramInstruction::CloseProposal {
+        proposal_index,
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: cancel instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_cancel(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_cancel_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_cancel_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let canceller = parse_account_id(v["canceller"].as_str().ok_or("missing canceller")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        canceller,
+        proposal,
+    ];
+    let signer_ids: Vec<AccountId> = vec![
+        canceller,
+    ];
+
+    let instruction = ProgramInstruction::Cancel {
+        proposal_index,
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
 /// FFI: propose_add_member instruction.
 #[no_mangle]
 pub extern "C" fn multisig_program_propose_add_member(args_json: *const c_char) -> *mut c_char {
@@ -433,17 +582,21 @@ fn multisig_program_propose_add_member_impl(args: &str) -> Result<String, String
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
     ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
 
     let mut account_ids: Vec<AccountId> = vec![
         multisig_state,
         proposer,
         proposal,
     ];
+    account_ids.extend(clock);
     let signer_ids: Vec<AccountId> = vec![
         proposer,
     ];
@@ -499,17 +652,21 @@ fn multisig_program_propose_remove_member_impl(args: &str) -> Result<String, Str
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
     ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
 
     let mut account_ids: Vec<AccountId> = vec![
         multisig_state,
         proposer,
         proposal,
     ];
+    account_ids.extend(clock);
     let signer_ids: Vec<AccountId> = vec![
         proposer,
     ];
@@ -565,17 +722,21 @@ fn multisig_program_propose_change_threshold_impl(args: &str) -> Result<String,
 
     let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
     let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
-    let proposal = compute_pda(&[
+    let proposal = compute_pda(&program_id, &[
         b"multisig_prop___",
         &create_key as &[u8],
         &proposal_index.to_le_bytes(),
     ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
 
     let mut account_ids: Vec<AccountId> = vec![
         multisig_state,
         proposer,
         proposal,
     ];
+    account_ids.extend(clock);
     let signer_ids: Vec<AccountId> = vec![
         proposer,
     ];
@@ -609,38 +770,1254 @@ fn multisig_program_propose_change_threshold_impl(args: &str) -> Result<String,
     Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
 }
 
+/// FFI: propose_set_time_lock instruction.
 #[no_mangle]
-pub extern "C" fn multisig_program_free_string(s: *mut c_char) {
-    if !s.is_null() { unsafe { drop(CString::from_raw(s)) }; }
+pub extern "C" fn multisig_program_propose_set_time_lock(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_set_time_lock_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_set_time_lock_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let time_lock = v["time_lock"].as_u64().ok_or("expected number")? as u64;
+    let clock_account = serde_json::from_value(v["clock_account"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeSetTimeLock {
+        time_lock,
+        clock_account,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
 }
 
+/// FFI: propose_batch instruction.
 #[no_mangle]
-pub extern "C" fn multisig_program_version() -> *mut c_char {
-    to_cstring("0.1.0".to_string())
+pub extern "C" fn multisig_program_propose_batch(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_batch_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_batch_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let calls = v["calls"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
+    let expiry = v["expiry"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeBatch {
+        calls,
+        expiry,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
 }
 
-/// Compute PDA for `multisig_state` account.
-/// Seeds: [arg(create_key)]
-pub fn compute_multisig_state_pda(program_id: &ProgramId, create_key: &[u8; 32]) -> AccountId {
-    let seed_bytes: [u8; 32] = *create_key;
-    let pda_seed = nssa_core::program::PdaSeed::new(seed_bytes);
-    AccountId::from((program_id, &pda_seed))
-}
-
-/// Compute PDA for `proposal` account.
-/// Seeds: [const("multisig_prop___"), arg(create_key), arg(proposal_index)]
-pub fn compute_proposal_pda(program_id: &ProgramId, create_key: &[u8; 32], proposal_index: u64) -> AccountId {
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    {
-        let mut padded = [0u8; 32];
-        let src = b"multisig_prop___";
-        padded[..src.len()].copy_from_slice(src);
-        hasher.update(&padded);
+/// FFI: propose_config_batch instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_config_batch(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_config_batch_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
     }
-    hasher.update(create_key as &[u8]);
-    hasher.update(&proposal_index.to_le_bytes());
-    let combined: [u8; 32] = hasher.finalize().into();
-    let pda_seed = nssa_core::program::PdaSeed::new(combined);
-    AccountId::from((program_id, &pda_seed))
+}
+
+fn multisig_program_propose_config_batch_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let actions = v["actions"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeConfigBatch {
+        actions,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_replace_member instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_replace_member(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_replace_member_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_replace_member_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let old = serde_json::from_value(v["old"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let new = serde_json::from_value(v["new"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeReplaceMember {
+        old,
+        new,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_set_weight instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_set_weight(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_set_weight_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_set_weight_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let member = serde_json::from_value(v["member"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let weight = v["weight"].as_u64().ok_or("expected number")? as u8;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeSetWeight {
+        member,
+        weight,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_set_permissions instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_set_permissions(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_set_permissions_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_set_permissions_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let member = serde_json::from_value(v["member"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let permissions = v["permissions"].as_u64().ok_or("expected number")? as u8;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeSetPermissions {
+        member,
+        permissions,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_add_spending_limit instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_add_spending_limit(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_add_spending_limit_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_add_spending_limit_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let limit_key = serde_json::from_value(v["limit_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let vault_index = v["vault_index"].as_u64().ok_or("expected number")? as u8;
+    let token_program_id = serde_json::from_value(v["token_program_id"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let amount = serde_json::from_value(v["amount"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let period = v["period"].as_u64().ok_or("expected number")? as u64;
+    let members = v["members"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeAddSpendingLimit {
+        limit_key,
+        vault_index,
+        token_program_id,
+        amount,
+        period,
+        members,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_remove_spending_limit instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_remove_spending_limit(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_remove_spending_limit_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_remove_spending_limit_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let limit_key = serde_json::from_value(v["limit_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeRemoveSpendingLimit {
+        limit_key,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: use_spending_limit instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_use_spending_limit(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_use_spending_limit_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_use_spending_limit_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let amount = serde_json::from_value(v["amount"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let limit_key = serde_json::from_value(v["limit_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let spender = parse_account_id(v["spender"].as_str().ok_or("missing spender")?)?;
+    let spending_limit = compute_pda(&program_id, &[
+        b"multisig_limit__",
+        &create_key as &[u8],
+        &limit_key as &[u8],
+    ]);
+    let transfer_accounts: Vec<AccountId> = v["transfer_accounts"].as_array()
+        .ok_or("missing transfer_accounts")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        spender,
+        spending_limit,
+    ];
+    account_ids.extend(transfer_accounts);
+    let signer_ids: Vec<AccountId> = vec![
+        spender,
+    ];
+
+    let instruction = ProgramInstruction::UseSpendingLimit {
+        amount,
+        limit_key,
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_set_class_threshold instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_set_class_threshold(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_set_class_threshold_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_set_class_threshold_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let class = serde_json::from_value(v["class"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let threshold = v["threshold"].as_u64().ok_or("expected number")? as u8;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeSetClassThreshold {
+        class,
+        threshold,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: propose_set_program_policy instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_propose_set_program_policy(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_propose_set_program_policy_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_propose_set_program_policy_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let target_program_id = serde_json::from_value(v["target_program_id"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let policy = serde_json::from_value(v["policy"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let proposer = parse_account_id(v["proposer"].as_str().ok_or("missing proposer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        proposer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        proposer,
+    ];
+
+    let instruction = ProgramInstruction::ProposeSetProgramPolicy {
+        target_program_id,
+        policy,
+        create_key,
+        proposal_index,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: withdraw_vote instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_withdraw_vote(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_withdraw_vote_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_withdraw_vote_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let voter = parse_account_id(v["voter"].as_str().ok_or("missing voter")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        voter,
+        proposal,
+    ];
+    let signer_ids: Vec<AccountId> = vec![
+        voter,
+    ];
+
+    let instruction = ProgramInstruction::WithdrawVote {
+        proposal_index,
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: abstain instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_abstain(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_abstain_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_abstain_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let voter = parse_account_id(v["voter"].as_str().ok_or("missing voter")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        voter,
+        proposal,
+    ];
+    let signer_ids: Vec<AccountId> = vec![
+        voter,
+    ];
+
+    let instruction = ProgramInstruction::Abstain {
+        proposal_index,
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: approve_and_execute instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_approve_and_execute(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_approve_and_execute_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_approve_and_execute_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let approver = parse_account_id(v["approver"].as_str().ok_or("missing approver")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let target_accounts: Vec<AccountId> = v["target_accounts"].as_array()
+        .ok_or("missing target_accounts")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        approver,
+        proposal,
+    ];
+    account_ids.extend(target_accounts);
+    let signer_ids: Vec<AccountId> = vec![
+        approver,
+    ];
+
+    let instruction = ProgramInstruction::ApproveAndExecute {
+        proposal_index,
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: approve_with_signatures instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_approve_with_signatures(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_approve_with_signatures_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_approve_with_signatures_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let proposal_index = v["proposal_index"].as_u64().ok_or("expected number")? as u64;
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let signatures = v["signatures"].as_array().ok_or("expected array")?.iter().map(|item| Ok(serde_json::from_value(item.clone()).map_err(|e| format!("parse error: {}", e))?)).collect::<Result<Vec<_>, String>>()?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let relayer = parse_account_id(v["relayer"].as_str().ok_or("missing relayer")?)?;
+    let proposal = compute_pda(&program_id, &[
+        b"multisig_prop___",
+        &create_key as &[u8],
+        &proposal_index.to_le_bytes(),
+    ]);
+    let clock: Vec<AccountId> = v["clock"].as_array()
+        .ok_or("missing clock")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        relayer,
+        proposal,
+    ];
+    account_ids.extend(clock);
+    let signer_ids: Vec<AccountId> = vec![
+        relayer,
+    ];
+
+    let instruction = ProgramInstruction::ApproveWithSignatures {
+        proposal_index,
+        create_key,
+        signatures,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: create_voter_record instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_create_voter_record(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_create_voter_record_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_create_voter_record_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let member = serde_json::from_value(v["member"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let voter_record = compute_pda(&program_id, &[
+        b"multisig_voter__",
+        &create_key as &[u8],
+        &member as &[u8],
+    ]);
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        voter_record,
+    ];
+    let signer_ids: Vec<AccountId> = vec![
+    ];
+
+    let instruction = ProgramInstruction::CreateVoterRecord {
+        create_key,
+        member,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: act_as_voter instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_act_as_voter(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_act_as_voter_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_act_as_voter_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let voter_signature = serde_json::from_value(v["voter_signature"].clone()).map_err(|e| format!("parse error: {}", e))?;
+    let instruction = serde_json::from_value(v["instruction"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let voter_record = parse_account_id(v["voter_record"].as_str().ok_or("missing voter_record")?)?;
+    let instruction_accounts: Vec<AccountId> = v["instruction_accounts"].as_array()
+        .ok_or("missing instruction_accounts")?
+        .iter().map(|a| parse_account_id(a.as_str().ok_or("expected string")?)).collect::<Result<Vec<_>,_>>()?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        voter_record,
+    ];
+    account_ids.extend(instruction_accounts);
+    let signer_ids: Vec<AccountId> = vec![
+    ];
+
+    let instruction = ProgramInstruction::ActAsVoter {
+        create_key,
+        voter_signature,
+        instruction,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+/// FFI: release_member_account instruction.
+#[no_mangle]
+pub extern "C" fn multisig_program_release_member_account(args_json: *const c_char) -> *mut c_char {
+    let args = match cstr_to_str(args_json) {
+        Ok(s) => s, Err(e) => return error_json(&e),
+    };
+    match multisig_program_release_member_account_impl(args) {
+        Ok(r) => to_cstring(r), Err(e) => error_json(&e),
+    }
+}
+
+fn multisig_program_release_member_account_impl(args: &str) -> Result<String, String> {
+    let v: Value = serde_json::from_str(args).map_err(|e| format!("invalid JSON: {}", e))?;
+    let program_id = parse_program_id_hex(v["program_id_hex"].as_str().ok_or("missing program_id_hex")?)?;
+    let wallet = init_wallet(&v)?;
+
+    let create_key = serde_json::from_value(v["create_key"].clone()).map_err(|e| format!("parse error: {}", e))?;
+
+    let multisig_state = parse_account_id(v["multisig_state"].as_str().ok_or("missing multisig_state")?)?;
+    let member_account = parse_account_id(v["member_account"].as_str().ok_or("missing member_account")?)?;
+    let recipient = parse_account_id(v["recipient"].as_str().ok_or("missing recipient")?)?;
+
+    let mut account_ids: Vec<AccountId> = vec![
+        multisig_state,
+        member_account,
+        recipient,
+    ];
+    let signer_ids: Vec<AccountId> = vec![
+        member_account,
+    ];
+
+    let instruction = ProgramInstruction::ReleaseMemberAccount {
+        create_key,
+    };
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("tokio: {}", e))?;
+    let tx_hash = rt.block_on(async {
+        let nonces = wallet.get_accounts_nonces(signer_ids.clone()).await
+            .map_err(|e| format!("nonces: {}", e))?;
+        let mut signing_keys = Vec::new();
+        for sid in &signer_ids {
+            let key = wallet.storage().user_data
+                .get_pub_account_signing_key(*sid)
+                .ok_or_else(|| format!("signing key not found for {}", sid))?;
+            signing_keys.push(key);
+        }
+        let message = Message::try_new(program_id, account_ids, nonces, instruction)
+            .map_err(|e| format!("message: {:?}", e))?;
+        let witness_set = WitnessSet::for_message(&message, &signing_keys);
+        let tx = PublicTransaction::new(message, witness_set);
+        wallet.sequencer_client.send_tx_public(tx).await
+            .map_err(|e| format!("submit: {}", e))
+            .map(|r| r.tx_hash.to_string())
+    })?;
+
+    Ok(json!({"success": true, "tx_hash": tx_hash}).to_string())
+}
+
+#[no_mangle]
+pub extern "C" fn multisig_program_free_string(s: *mut c_char) {
+    if !s.is_null() { unsafe { drop(CString::from_raw(s)) }; }
+}
+
+#[no_mangle]
+pub extern "C" fn multisig_program_version() -> *mut c_char {
+    to_cstring("0.1.0".to_string())
 }
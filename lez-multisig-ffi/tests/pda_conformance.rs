@@ -0,0 +1,86 @@
+//! PDA conformance — the generated FFI wrappers must derive the same addresses
+//! as the program (multisig_core::pda).
+//!
+//! Every PDA account in the IDL is derived the way the generated wrappers
+//! derive it — its IDL seeds fed to the generated `compute_pda` — and checked
+//! against docs/pda-test-vectors.json. Fails if the generator, the patch in
+//! scripts/patch-ffi-pda.py or the IDL seeds drift from the published vectors.
+
+use nssa_core::program::ProgramId;
+use serde_json::Value;
+
+const VECTORS: &str = include_str!("../../docs/pda-test-vectors.json");
+const IDL: &str = include_str!("../src/multisig_idl.json");
+
+fn hex32(s: &str) -> [u8; 32] {
+    hex::decode(s).unwrap().try_into().unwrap()
+}
+
+fn vectors() -> Vec<Value> {
+    let doc: Value = serde_json::from_str(VECTORS).unwrap();
+    doc["vectors"].as_array().unwrap().clone()
+}
+
+/// Bytes of one IDL seed segment, with instruction arguments taken from `vector`.
+/// Integer arguments are u64 little-endian, as the generated wrappers encode them.
+fn seed_bytes(seed: &Value, vector: &Value) -> Vec<u8> {
+    let kind = seed["kind"].as_str().unwrap_or_default().to_lowercase();
+    if !kind.contains("arg") {
+        return match &seed["value"] {
+            Value::String(s) => s.as_bytes().to_vec(),
+            Value::Array(bytes) => bytes.iter().map(|b| b.as_u64().unwrap() as u8).collect(),
+            other => panic!("IDL const seed has no value: {}", other),
+        };
+    }
+    let name = seed["path"].as_str().or(seed["name"].as_str()).unwrap_or_default();
+    match name {
+        "proposal_index" => vector["proposal_index"].as_u64().unwrap().to_le_bytes().to_vec(),
+        "create_key" | "limit_key" | "member" => hex32(vector[name].as_str().unwrap()).to_vec(),
+        other => panic!("IDL seed arg `{}` has no published vector", other),
+    }
+}
+
+#[test]
+fn generated_pdas_match_vectors() {
+    let idl: Value = serde_json::from_str(IDL).unwrap();
+
+    let mut checked = 0;
+    for v in vectors() {
+        let program_id: ProgramId = serde_json::from_value(v["program_id"].clone()).unwrap();
+        for ix in idl["instructions"].as_array().unwrap() {
+            for account in ix["accounts"].as_array().unwrap() {
+                let Some(seeds) = account["pda"]["seeds"].as_array() else { continue };
+                let name = account["name"].as_str().unwrap();
+                let expected = match name {
+                    "multisig_state" => "multisig_state_account_id",
+                    "proposal" => "proposal_account_id",
+                    "spending_limit" => "spending_limit_account_id",
+                    "voter_record" => "voter_record_account_id",
+                    other => panic!("IDL PDA account `{}` has no canonical derivation in multisig_core::pda", other),
+                };
+
+                let segments: Vec<Vec<u8>> = seeds.iter().map(|s| seed_bytes(s, &v)).collect();
+                let segments: Vec<&[u8]> = segments.iter().map(Vec::as_slice).collect();
+                assert_eq!(
+                    *lez_multisig_ffi::compute_pda(&program_id, &segments).value(),
+                    hex32(v[expected].as_str().unwrap()),
+                    "generated {} PDA drifted in {}",
+                    name,
+                    ix["name"]
+                );
+                checked += 1;
+            }
+        }
+    }
+    assert!(checked > 0, "IDL has no PDA annotations");
+}
+
+#[test]
+fn generated_pda_uses_program_id() {
+    let create_key = [9u8; 32];
+    let segments: [&[u8]; 2] = [b"multisig_state__", &create_key];
+    let a = lez_multisig_ffi::compute_pda(&[1u32; 8], &segments);
+    let b = lez_multisig_ffi::compute_pda(&[2u32; 8], &segments);
+    assert_ne!(a, b);
+    assert_eq!(a, lez_multisig_ffi::compute_multisig_state_pda(&[1u32; 8], &create_key));
+}
@@ -11,7 +11,8 @@ fn main() {
 
     let pre_states_clone = pre_states.clone();
 
-    let (post_states, chained_calls) = multisig_program::process(&pre_states, &instruction);
+    let (post_states, chained_calls) = multisig_program::process(&pre_states, &instruction)
+        .unwrap_or_else(|e| panic!("{}", e));
 
     write_nssa_outputs_with_chained_call(
         instruction_words,
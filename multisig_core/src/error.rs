@@ -0,0 +1,171 @@
+// MultisigError — every way a multisig instruction can fail.
+//
+// Codes are stable: they are exported in the IDL (`errors`), surfaced by the
+// FFI (`error_code` / `error_name`) and decoded by the CLI. Never renumber or
+// reuse a code; append new variants at the end.
+//
+// The guest aborts with the Display form, which always contains
+// "MultisigError <code>" so clients can map a failed transaction back to the
+// named error with `MultisigError::from_error_text`.
+
+use core::fmt;
+
+macro_rules! multisig_errors {
+    ($($name:ident = $code:literal => $msg:literal,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(u32)]
+        pub enum MultisigError {
+            $(
+                #[doc = $msg]
+                $name = $code,
+            )*
+        }
+
+        impl MultisigError {
+            /// Every error, in code order.
+            pub const ALL: &'static [MultisigError] = &[$(MultisigError::$name,)*];
+
+            /// Variant name, as exported in the IDL.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(MultisigError::$name => stringify!($name),)*
+                }
+            }
+
+            /// Human-readable description.
+            pub fn message(self) -> &'static str {
+                match self {
+                    $(MultisigError::$name => $msg,)*
+                }
+            }
+        }
+    };
+}
+
+multisig_errors! {
+    NotEnoughAccounts = 6000 => "Not enough accounts for this instruction",
+    MissingSignature = 6001 => "Required signer did not sign the transaction",
+    NotAMember = 6002 => "Signer is not a multisig member",
+    InvalidMultisigState = 6003 => "Multisig state account could not be deserialized",
+    InvalidProposal = 6004 => "Proposal account could not be deserialized",
+    AccountAlreadyInitialized = 6005 => "Account must be uninitialized",
+    ProposalMultisigMismatch = 6006 => "Proposal does not belong to this multisig",
+    ProposalNotActive = 6007 => "Proposal is not active",
+    AlreadyApproved = 6008 => "Member has already approved this proposal",
+    AlreadyRejected = 6009 => "Member has already rejected this proposal",
+    ThresholdNotReached = 6010 => "Proposal does not have enough approvals",
+    TargetAccountCountMismatch = 6011 => "Number of target accounts does not match the proposal",
+    TargetAccountMismatch = 6012 => "Target account does not match the proposal",
+    AuthorizedIndexOutOfRange = 6013 => "Authorized index is out of range for the target accounts",
+    ForeignPdaSeed = 6014 => "PDA seed is not derived from this multisig's create_key",
+    NoMembers = 6015 => "Multisig must have at least one member",
+    InvalidThreshold = 6016 => "Threshold must be at least 1",
+    ThresholdExceedsMemberCount = 6017 => "Threshold cannot exceed member count",
+    TooManyMembers = 6018 => "Maximum 10 members",
+    MemberAccountMismatch = 6019 => "Member account ID does not match member list",
+    AlreadyMember = 6020 => "Account is already a member",
+    MemberNotFound = 6021 => "Account is not a member",
+    RemovalBreaksThreshold = 6022 => "Cannot remove member: member count would drop below threshold",
+    UnexpectedTargetAccounts = 6023 => "Config change proposals take no target accounts",
+    CannotCloseActiveProposal = 6024 => "Active proposals cannot be closed",
+    ProposalIndexMismatch = 6025 => "Proposal account index does not match the instruction",
+    AlreadyCancelled = 6026 => "Member has already voted to cancel this proposal",
+    StaleProposal = 6027 => "Proposal was created before the last config change",
+    MissingClockAccount = 6028 => "Time-locked multisig requires the clock account",
+    ClockAccountMismatch = 6029 => "Clock account does not match the multisig's clock",
+    InvalidClockAccount = 6030 => "Clock account data could not be read",
+    TimeLockNotElapsed = 6031 => "Time-lock has not elapsed since the proposal reached threshold",
+    ProposalExpired = 6032 => "Proposal has expired",
+    EmptyBatch = 6033 => "Batch proposal is empty",
+    BatchTooLarge = 6034 => "Batch proposal exceeds its size limit",
+    InvalidWeights = 6035 => "Member weights must be non-zero, one per member",
+    MissingPermission = 6036 => "Member does not have the permission for this action",
+    InvalidPermissions = 6037 => "Member permissions are invalid or leave no member able to propose or execute",
+    InvalidSpendingLimit = 6038 => "Spending limit account could not be deserialized",
+    InvalidSpendingLimitParams = 6039 => "Spending limit needs a non-zero amount, at least one member and a unique limit_key",
+    SpendingLimitMismatch = 6040 => "Account is not this multisig's spending limit PDA",
+    SpendingLimitExceeded = 6041 => "Amount exceeds the spending limit's remaining allowance",
+    VaultMismatch = 6042 => "Account is not this multisig's vault PDA",
+    InvalidVaultIndex = 6043 => "Vault index must be below MAX_VAULTS",
+    ProgramNotAllowed = 6044 => "Target program is denied, or not on the multisig's allowlist",
+    TooManyPolicyPrograms = 6045 => "Program allowlist and denylist exceed MAX_POLICY_PROGRAMS entries",
+    NoVoteToWithdraw = 6046 => "Member has not voted on this proposal",
+    AlreadyAbstained = 6047 => "Member has already abstained on this proposal",
+    EmptySignatureBundle = 6048 => "ApproveWithSignatures needs at least one signature",
+    InvalidApprovalSignature = 6049 => "Signature does not verify against the proposal's approval digest",
+    VoterRecordMismatch = 6050 => "Account is not the member's voter record PDA",
+    InvalidVoterRecord = 6051 => "Voter record account could not be deserialized",
+    InvalidVoterSignature = 6052 => "Signature does not verify against the voter call digest",
+    NotVoterCallable = 6053 => "Instruction is malformed or cannot be run through a voter record",
+    StillAMember = 6054 => "Account is still a member of this multisig",
+    NotAMemberAccount = 6055 => "Account is not a member account claimed by this multisig",
+    MultisigStateMismatch = 6056 => "Account is not the multisig state PDA for the instruction's create_key",
+    ProposalAccountMismatch = 6057 => "Account is not the multisig's proposal PDA for this proposal index",
+    ProposalIndexConflict = 6058 => "Proposal index is not the multisig's next index (another proposal took it); fetch the next index and retry",
+    ProposalPredatesTargetAccounts = 6059 => "Proposal was created before target accounts were recorded and cannot be executed; re-propose it",
+    ClockAccountNotSet = 6060 => "A time-lock or default expiry needs a clock account",
+    InvalidClockConfig = 6061 => "Clock account can't be the multisig's state or a member account",
+    ClockWentBackwards = 6062 => "Clock account reads earlier than a time the multisig already saw",
+    DuplicateMember = 6063 => "Member list names the same account twice",
+    TooManyTargetAccounts = 6064 => "Proposal names more than MAX_BATCH_ACCOUNTS target accounts",
+    DuplicateTargetAccount = 6065 => "Call names the same target account twice",
+}
+
+impl MultisigError {
+    /// Stable numeric code.
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// Look up an error by its numeric code.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| e.code() == code)
+    }
+
+    /// Find the error in a failure message (e.g. a rejected transaction's
+    /// error text), by the "MultisigError <code>" marker the guest emits.
+    pub fn from_error_text(text: &str) -> Option<Self> {
+        const MARKER: &str = "MultisigError ";
+        text.match_indices(MARKER).find_map(|(i, _)| {
+            let rest = &text[i + MARKER.len()..];
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            rest[..end].parse().ok().and_then(Self::from_code)
+        })
+    }
+}
+
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (MultisigError {}): {}", self.name(), self.code(), self.message())
+    }
+}
+
+impl core::error::Error for MultisigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique_and_sequential() {
+        for (i, e) in MultisigError::ALL.iter().enumerate() {
+            assert_eq!(e.code(), 6000 + i as u32, "{} is out of order", e.name());
+        }
+    }
+
+    #[test]
+    fn test_code_roundtrip() {
+        for e in MultisigError::ALL {
+            assert_eq!(MultisigError::from_code(e.code()), Some(*e));
+        }
+        assert_eq!(MultisigError::from_code(5999), None);
+    }
+
+    #[test]
+    fn test_from_error_text() {
+        let text = format!("submit: guest panicked: {}", MultisigError::ThresholdNotReached);
+        assert_eq!(MultisigError::from_error_text(&text), Some(MultisigError::ThresholdNotReached));
+        assert_eq!(MultisigError::from_error_text("MultisigError 9999"), None);
+        assert_eq!(MultisigError::from_error_text("connection refused"), None);
+    }
+}
@@ -1,5 +1,6 @@
 // multisig_core — shared types for the Multisig program.
-// PDA compute helpers have been moved to lez-multisig-ffi/src/multisig.rs (generated by lez-client-gen).
+// PDA derivation lives in `pda` — the one scheme shared by program, FFI and CLI.
+// Off-chain approval digests and signature checks live in `signing`.
 //
 // A multisig is a governance wrapper: it collects M-of-N approvals and then
 // executes a ChainedCall to a target program. The multisig itself never
@@ -12,11 +13,27 @@
 // Inspired by Squads Protocol v4 (Solana).
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use nssa_core::program::{InstructionData, ProgramId, PdaSeed};
-use nssa_core::account::AccountId;
+use nssa_core::program::ProgramId;
 
 use serde::{Deserialize, Serialize};
 
+pub mod error;
+pub mod pda;
+pub mod signing;
+
+pub use error::MultisigError;
+
+pub use pda::{
+    compute_multisig_state_pda, compute_proposal_pda, compute_spending_limit_pda, compute_vault_pda,
+    compute_voter_record_pda, is_multisig_pda_seed, multisig_state_pda_seed, proposal_pda_seed,
+    spending_limit_pda_seed, vault_index_of_seed, vault_pda_seed, vault_pda_seed_bytes,
+    voter_record_pda_seed, MAX_VAULTS,
+};
+
+pub use signing::{
+    account_id_from_public_key, approval_digest, verify_approval, voter_call_digest, MemberSignature,
+};
+
 // ---------------------------------------------------------------------------
 // Instructions
 // ---------------------------------------------------------------------------
@@ -28,20 +45,36 @@ use serde::{Deserialize, Serialize};
 /// 2. Other members call `Approve { proposal_index }` — adds their approval
 /// 3. Once M approvals collected, anyone calls `Execute { proposal_index }`
 ///    → multisig emits a ChainedCall to the target program
-/// 4. Members can also `Reject` proposals
+/// 4. Members can also `Reject` or `Abstain`, and `WithdrawVote` before execution
+///
+/// Members may be limited by permission flags: proposing needs
+/// PERMISSION_INITIATE, approving/rejecting PERMISSION_VOTE, and executing
+/// PERMISSION_EXECUTE. By default every member has all three.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Instruction {
     /// Create a new multisig with M-of-N threshold.
     CreateMultisig {
         /// Unique key for PDA derivation — allows multiple multisigs per program
         create_key: [u8; 32],
-        /// Required signatures for execution (M)
+        /// Required approval weight for execution (M); with no weights, M signatures
         threshold: u8,
         /// List of member account IDs (32 bytes each, derived from public keys)
         members: Vec<[u8; 32]>,
+        /// Delay (in clock units) between reaching threshold and execute; 0 = none
+        time_lock: u64,
+        /// Clock account read for the time-lock and proposal expiry
+        /// (ignored while both time_lock and default_expiry are 0)
+        clock_account: [u8; 32],
+        /// Lifetime (in clock units) of proposals that don't set their own; 0 = never expire
+        default_expiry: u64,
+        /// Vote weight of each member, in `members` order; empty = one vote each
+        weights: Vec<u8>,
+        /// Permission bitflags (PERMISSION_*) of each member, in `members` order;
+        /// empty = every member may propose, vote and execute
+        permissions: Vec<u8>,
     },
 
-    /// Create a new proposal (any member can propose).
+    /// Create a new proposal (any member with Initiate permission can propose).
     /// Creates a separate PDA account for the proposal.
     /// proposal PDA is derived from (create_key, proposal_index).
     Propose {
@@ -49,19 +82,22 @@ pub enum Instruction {
         target_program_id: ProgramId,
         /// Serialized instruction data for the target program
         target_instruction_data: Vec<u32>,
-        /// Number of target accounts that will be passed at execute time.
-        target_account_count: u8,
+        /// Exact target account IDs (in order) that must be passed at execute time.
+        /// Members approve these accounts — Execute rejects any substitution.
+        target_accounts: Vec<[u8; 32]>,
         /// PDA seeds for authorization in the chained call
         pda_seeds: Vec<[u8; 32]>,
         /// Which target account indices (0-based) get `is_authorized = true`
         authorized_indices: Vec<u8>,
+        /// Lifetime (in clock units) from creation; 0 = the multisig's default_expiry
+        expiry: u64,
         /// Unique key of the parent multisig (for proposal PDA derivation)
         create_key: [u8; 32],
         /// Index of this proposal (used for PDA derivation)
         proposal_index: u64,
     },
 
-    /// Approve an existing proposal (any member, one approval per member)
+    /// Approve an existing proposal (any member with Vote permission, one approval per member)
     Approve {
         proposal_index: u64,
         /// Unique key of the parent multisig (for proposal PDA derivation)
@@ -111,6 +147,265 @@ pub enum Instruction {
         /// Index of this proposal (used for PDA derivation)
         proposal_index: u64,
     },
+
+    /// Close a finished (non-Active) proposal, clearing its account data.
+    /// Any further proposals passed after [multisig_state, closer, proposal] are
+    /// closed in the same call; each must have an index below `proposal_index`.
+    CloseProposal {
+        proposal_index: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+    },
+
+    /// Cancel an Active proposal (including an approved one not yet executed).
+    /// The proposer cancels their own proposal immediately; any other member
+    /// casts a cancel vote, and the proposal is cancelled once `threshold`
+    /// members have voted to cancel.
+    Cancel {
+        proposal_index: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+    },
+
+    /// Propose changing the execution time-lock (requires M approvals to execute).
+    ProposeSetTimeLock {
+        time_lock: u64,
+        clock_account: [u8; 32],
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose an ordered batch of calls, executed in order by Execute.
+    /// At most MAX_BATCH_CALLS calls and MAX_BATCH_ACCOUNTS distinct target accounts.
+    /// Calls on disjoint accounts run atomically in one Execute; a call reusing
+    /// an account an earlier call writes runs in a later Execute (see `batch_step_len`).
+    ProposeBatch {
+        calls: Vec<ProposalCall>,
+        /// Lifetime (in clock units) from creation; 0 = the multisig's default_expiry
+        expiry: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose several config changes applied together by one Execute
+    /// (requires M approvals). Only the resulting config is validated, so
+    /// e.g. a member can be removed before the add that keeps N >= M.
+    ProposeConfigBatch {
+        actions: Vec<ConfigAction>,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose replacing member `old` with `new` in place (requires M approvals
+    /// to execute). Rotates a key without changing N, so it works at 10 members.
+    ProposeReplaceMember {
+        old: [u8; 32],
+        new: [u8; 32],
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose changing a member's vote weight (requires M approvals to execute).
+    /// Must keep threshold <= total weight (checked on execute).
+    ProposeSetWeight {
+        member: [u8; 32],
+        weight: u8,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose changing a member's permission bitflags (requires M approvals to execute).
+    /// Some member must keep Initiate and some Execute, and threshold must stay
+    /// reachable by the members with Vote (checked on execute).
+    ProposeSetPermissions {
+        member: [u8; 32],
+        permissions: u8,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose creating a spending limit (requires M approvals to execute).
+    /// Execute takes the uninitialized spending limit PDA as its only target account.
+    ProposeAddSpendingLimit {
+        /// Key of the new limit (for spending limit PDA derivation)
+        limit_key: [u8; 32],
+        /// Vault the limit spends from
+        vault_index: u8,
+        /// Token program the limit's transfers are sent to
+        token_program_id: ProgramId,
+        /// Allowance per period
+        amount: u128,
+        /// Period length in clock units; 0 = a one-off allowance
+        period: u64,
+        /// Members allowed to spend under the limit
+        members: Vec<[u8; 32]>,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose closing a spending limit (requires M approvals to execute).
+    /// Execute takes the spending limit PDA as its only target account.
+    ProposeRemoveSpendingLimit {
+        limit_key: [u8; 32],
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Transfer `amount` from the vault to a recipient under a spending limit,
+    /// without a proposal. The transaction must include the clock account (for
+    /// limits with a period), then [vault, recipient] after
+    /// [multisig_state, spender, spending_limit].
+    UseSpendingLimit {
+        amount: u128,
+        /// Key of the limit (for spending limit PDA derivation)
+        limit_key: [u8; 32],
+        /// Unique key of the parent multisig (for spending limit PDA derivation)
+        create_key: [u8; 32],
+    },
+
+    /// Propose a separate threshold for config changes, transfers or one target
+    /// program (requires the config threshold to execute). 0 restores the default.
+    /// Must stay <= total weight (checked on execute).
+    ProposeSetClassThreshold {
+        class: ActionClass,
+        threshold: u8,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Propose putting a target program on the allowlist or denylist, or taking
+    /// it off both (requires the config threshold to execute). Once the allowlist
+    /// is non-empty, Propose and Execute only accept calls to listed programs.
+    ProposeSetProgramPolicy {
+        /// Program the policy applies to
+        target_program_id: ProgramId,
+        policy: ProgramPolicy,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        /// Index of this proposal (used for PDA derivation)
+        proposal_index: u64,
+    },
+
+    /// Withdraw the signer's approval, rejection or abstention, and its cancel
+    /// vote, on an Active proposal (e.g. after discovering a problem before
+    /// execution)
+    WithdrawVote {
+        proposal_index: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+    },
+
+    /// Abstain on a proposal (any member with Vote permission). Replaces an
+    /// earlier approval or rejection; never counts toward threshold.
+    Abstain {
+        proposal_index: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+    },
+
+    /// Approve a proposal and, when this approval reaches threshold, execute it
+    /// in the same transaction. Takes Execute's accounts: [multisig_state,
+    /// approver, proposal, (clock), ...targets]. On a time-locked multisig it
+    /// executes only once the lock has elapsed; until then it only approves.
+    ApproveAndExecute {
+        proposal_index: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+    },
+
+    /// Record approvals members signed off-chain over the proposal's
+    /// `approval_digest`, submitted by a relayer (any signer). Each signature is
+    /// verified against the member account derived from its public key.
+    ApproveWithSignatures {
+        proposal_index: u64,
+        /// Unique key of the parent multisig (for proposal PDA derivation)
+        create_key: [u8; 32],
+        signatures: Vec<MemberSignature>,
+    },
+
+    /// Create a member's voter record PDA, through which the member acts with
+    /// their ordinary wallet key instead of a claimed member account. Anyone may
+    /// create it; it grants nothing without the member's signature.
+    CreateVoterRecord {
+        /// Unique key of the parent multisig (for voter record PDA derivation)
+        create_key: [u8; 32],
+        member: [u8; 32],
+    },
+
+    /// Run `instruction` (risc0-serialized) as the member who signed it. The
+    /// member's voter record takes the signer slot (accounts[1]); the signature is
+    /// over `voter_call_digest` and is only valid for the record's current nonce.
+    ActAsVoter {
+        /// Unique key of the parent multisig (for voter record PDA derivation)
+        create_key: [u8; 32],
+        voter_signature: MemberSignature,
+        instruction: Vec<u32>,
+    },
+
+    /// Move the whole native balance of a member account claimed by this
+    /// multisig, once no longer a member of it or once the multisig is closed,
+    /// to `recipient`, and mark the account released. LEZ never changes an
+    /// account's owner, so this is how a removed member gets its funds back;
+    /// the account stays with the program, free to be claimed by another multisig.
+    /// Accounts: [multisig_state, member_account (signer), recipient].
+    ReleaseMemberAccount {
+        /// Unique key of the multisig the member was removed from
+        create_key: [u8; 32],
+    },
+}
+
+impl Instruction {
+    /// The create_key of the multisig the instruction acts on.
+    pub fn create_key(&self) -> &[u8; 32] {
+        match self {
+            Instruction::CreateMultisig { create_key, .. }
+            | Instruction::Propose { create_key, .. }
+            | Instruction::Approve { create_key, .. }
+            | Instruction::Reject { create_key, .. }
+            | Instruction::Execute { create_key, .. }
+            | Instruction::ProposeAddMember { create_key, .. }
+            | Instruction::ProposeRemoveMember { create_key, .. }
+            | Instruction::ProposeChangeThreshold { create_key, .. }
+            | Instruction::CloseProposal { create_key, .. }
+            | Instruction::Cancel { create_key, .. }
+            | Instruction::ProposeSetTimeLock { create_key, .. }
+            | Instruction::ProposeBatch { create_key, .. }
+            | Instruction::ProposeConfigBatch { create_key, .. }
+            | Instruction::ProposeReplaceMember { create_key, .. }
+            | Instruction::ProposeSetWeight { create_key, .. }
+            | Instruction::ProposeSetPermissions { create_key, .. }
+            | Instruction::ProposeAddSpendingLimit { create_key, .. }
+            | Instruction::ProposeRemoveSpendingLimit { create_key, .. }
+            | Instruction::UseSpendingLimit { create_key, .. }
+            | Instruction::ProposeSetClassThreshold { create_key, .. }
+            | Instruction::ProposeSetProgramPolicy { create_key, .. }
+            | Instruction::WithdrawVote { create_key, .. }
+            | Instruction::Abstain { create_key, .. }
+            | Instruction::ApproveAndExecute { create_key, .. }
+            | Instruction::ApproveWithSignatures { create_key, .. }
+            | Instruction::CreateVoterRecord { create_key, .. }
+            | Instruction::ActAsVoter { create_key, .. }
+            | Instruction::ReleaseMemberAccount { create_key, .. } => create_key,
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -118,9 +413,9 @@ pub enum Instruction {
 // ---------------------------------------------------------------------------
 
 /// Configuration change action embedded in a proposal.
-/// When a proposal has a `config_action`, execute modifies MultisigState
-/// directly instead of emitting a ChainedCall.
-#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// When a proposal has a `config_action` (or a `config_batch`), execute
+/// modifies MultisigState directly instead of emitting a ChainedCall.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub enum ConfigAction {
     /// Add a new member to the multisig
     AddMember { new_member: [u8; 32] },
@@ -128,6 +423,126 @@ pub enum ConfigAction {
     RemoveMember { member: [u8; 32] },
     /// Change the approval threshold
     ChangeThreshold { new_threshold: u8 },
+    /// Change the execution time-lock and the clock account it reads
+    SetTimeLock { time_lock: u64, clock_account: [u8; 32] },
+    /// Swap a member's key for a new one in place (key rotation; N is unchanged)
+    ReplaceMember { old: [u8; 32], new: [u8; 32] },
+    /// Change a member's vote weight (must be at least 1)
+    SetWeight { member: [u8; 32], weight: u8 },
+    /// Change a member's permission bitflags (PERMISSION_*)
+    SetPermissions { member: [u8; 32], permissions: u8 },
+    /// Create the spending limit PDA for `limit_key`, letting `members` transfer
+    /// up to `amount` of `token_program_id` tokens from vault `vault_index` per `period`
+    AddSpendingLimit {
+        limit_key: [u8; 32],
+        vault_index: u8,
+        token_program_id: ProgramId,
+        amount: u128,
+        period: u64,
+        members: Vec<[u8; 32]>,
+    },
+    /// Close the spending limit PDA for `limit_key`
+    RemoveSpendingLimit { limit_key: [u8; 32] },
+    /// Set the threshold for one class of proposals; 0 falls back to the
+    /// class's default (`threshold`, or the transfer threshold for a program)
+    SetClassThreshold { class: ActionClass, threshold: u8 },
+    /// Put `target_program_id` on the allowlist or denylist, or take it off both
+    SetProgramPolicy { target_program_id: ProgramId, policy: ProgramPolicy },
+    /// Close the multisig: Execute wipes its state account, after which no
+    /// instruction but ReleaseMemberAccount accepts it
+    CloseMultisig,
+}
+
+/// Whether proposals may call a target program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ProgramPolicy {
+    /// On neither list: callable while the allowlist is empty
+    Default,
+    /// On the allowlist; once it is non-empty, only listed programs are callable
+    Allowed,
+    /// On the denylist: never callable
+    Denied,
+}
+
+/// Class of proposals that can have its own threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ActionClass {
+    /// Config proposals (membership, thresholds, time-lock, spending limits)
+    Config,
+    /// Proposals that emit ChainedCalls, e.g. vault transfers
+    Transfer,
+    /// Proposals calling one target program (overrides `Transfer`)
+    Program(ProgramId),
+}
+
+/// Threshold override for proposals calling `program_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ProgramThreshold {
+    pub program_id: ProgramId,
+    pub threshold: u8,
+}
+
+impl ConfigAction {
+    /// The spending limit this action creates or closes, if any. Execute takes
+    /// that limit's PDA as a target account.
+    pub fn spending_limit_key(&self) -> Option<[u8; 32]> {
+        match self {
+            ConfigAction::AddSpendingLimit { limit_key, .. }
+            | ConfigAction::RemoveSpendingLimit { limit_key } => Some(*limit_key),
+            _ => None,
+        }
+    }
+
+    /// The member this action brings in, if any. Execute may take that
+    /// member's account as a target account and claim it, as CreateMultisig does.
+    pub fn new_member(&self) -> Option<[u8; 32]> {
+        match self {
+            ConfigAction::AddMember { new_member } => Some(*new_member),
+            ConfigAction::ReplaceMember { new, .. } => Some(*new),
+            _ => None,
+        }
+    }
+
+    /// Whether this action closes the multisig.
+    pub fn closes_multisig(&self) -> bool {
+        matches!(self, ConfigAction::CloseMultisig)
+    }
+}
+
+/// Permission to create proposals
+pub const PERMISSION_INITIATE: u8 = 1 << 0;
+/// Permission to approve and reject; members without it carry no vote weight
+pub const PERMISSION_VOTE: u8 = 1 << 1;
+/// Permission to execute approved proposals
+pub const PERMISSION_EXECUTE: u8 = 1 << 2;
+/// Every permission (the default for members)
+pub const PERMISSION_ALL: u8 = PERMISSION_INITIATE | PERMISSION_VOTE | PERMISSION_EXECUTE;
+
+/// Maximum number of calls in a batch proposal
+pub const MAX_BATCH_CALLS: usize = 8;
+/// Maximum number of distinct target accounts across a proposal (the single
+/// call's, or a batch's)
+pub const MAX_BATCH_ACCOUNTS: usize = 32;
+/// Maximum number of actions in a config batch proposal
+pub const MAX_CONFIG_BATCH_ACTIONS: usize = 8;
+/// Maximum number of per-program threshold overrides
+pub const MAX_PROGRAM_THRESHOLDS: usize = 8;
+/// Maximum number of programs on the allowlist and denylist together
+pub const MAX_POLICY_PROGRAMS: usize = 16;
+
+/// One ChainedCall of a batch proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ProposalCall {
+    /// Target program to call
+    pub target_program_id: ProgramId,
+    /// Serialized instruction data for target program
+    pub target_instruction_data: Vec<u32>,
+    /// Exact target account IDs (in order) this call receives
+    pub target_accounts: Vec<[u8; 32]>,
+    /// PDA seeds for the chained call (multisig proves ownership)
+    pub pda_seeds: Vec<[u8; 32]>,
+    /// Which of this call's target account indices (0-based) get `is_authorized = true`
+    pub authorized_indices: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
@@ -140,11 +555,18 @@ pub enum ProposalStatus {
     Rejected,
     /// Proposal was cancelled
     Cancelled,
+    /// Proposal passed its expiry without being executed
+    Expired,
 }
 
 /// A proposal stored in its own PDA account.
 /// PDA derived from: proposal_pda_seed(create_key, proposal_index)
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+///
+/// Borsh layout: the original twelve fields (through `config_action`), then
+/// every later field appended in declaration order. Like `MultisigState`,
+/// decoding gives fields missing from the end of an older account their
+/// defaults, so proposals written before they existed still decode.
+#[derive(Debug, Clone, BorshSerialize)]
 pub struct Proposal {
     /// Unique index (matches MultisigState.transaction_index at creation time)
     pub index: u64,
@@ -158,7 +580,8 @@ pub struct Proposal {
     pub target_program_id: ProgramId,
     /// Serialized instruction data for target program
     pub target_instruction_data: Vec<u32>,
-    /// Expected number of target accounts at execute time
+    /// Number of target accounts (`target_accounts.len()`); the only record of
+    /// the targets in proposals created before `target_accounts`
     pub target_account_count: u8,
     /// PDA seeds for the chained call (multisig proves ownership)
     pub pda_seeds: Vec<[u8; 32]>,
@@ -174,6 +597,53 @@ pub struct Proposal {
     pub status: ProposalStatus,
     /// Optional config change action (if set, execute modifies MultisigState instead of ChainedCall)
     pub config_action: Option<ConfigAction>,
+    /// Clock time at which approvals first reached threshold (only tracked with a time-lock)
+    pub threshold_reached_at: Option<u64>,
+    /// Clock time from which the proposal can no longer be approved or executed
+    pub expires_at: Option<u64>,
+    /// Batch proposals: the ordered calls to execute (the single-call fields above are unused)
+    pub calls: Vec<ProposalCall>,
+    /// Config batch proposals: actions applied together on execute (`config_action` is None)
+    pub config_batch: Vec<ConfigAction>,
+    /// Account IDs that have abstained (counted as cast, never as approval)
+    pub abstained: Vec<[u8; 32]>,
+    /// Exact target account IDs (in order) expected at execute time
+    pub target_accounts: Vec<[u8; 32]>,
+    /// Account IDs that have voted to cancel
+    pub cancelled: Vec<[u8; 32]>,
+    /// Per member, how often they rejected, abstained or withdrew; part of
+    /// their approval digest, so those votes void earlier signed approvals
+    pub vote_nonces: Vec<([u8; 32], u32)>,
+    /// Batch proposals: how many of `calls` earlier Execute steps already ran
+    pub executed_calls: u8,
+}
+
+impl BorshDeserialize for Proposal {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        Ok(Self {
+            index: BorshDeserialize::deserialize_reader(reader)?,
+            proposer: BorshDeserialize::deserialize_reader(reader)?,
+            multisig_create_key: BorshDeserialize::deserialize_reader(reader)?,
+            target_program_id: BorshDeserialize::deserialize_reader(reader)?,
+            target_instruction_data: BorshDeserialize::deserialize_reader(reader)?,
+            target_account_count: BorshDeserialize::deserialize_reader(reader)?,
+            pda_seeds: BorshDeserialize::deserialize_reader(reader)?,
+            authorized_indices: BorshDeserialize::deserialize_reader(reader)?,
+            approved: BorshDeserialize::deserialize_reader(reader)?,
+            rejected: BorshDeserialize::deserialize_reader(reader)?,
+            status: BorshDeserialize::deserialize_reader(reader)?,
+            config_action: BorshDeserialize::deserialize_reader(reader)?,
+            threshold_reached_at: read_appended(reader)?,
+            expires_at: read_appended(reader)?,
+            calls: read_appended(reader)?,
+            config_batch: read_appended(reader)?,
+            abstained: read_appended(reader)?,
+            target_accounts: read_appended(reader)?,
+            cancelled: read_appended(reader)?,
+            vote_nonces: read_appended(reader)?,
+            executed_calls: read_appended(reader)?,
+        })
+    }
 }
 
 impl Proposal {
@@ -183,7 +653,7 @@ impl Proposal {
         multisig_create_key: [u8; 32],
         target_program_id: ProgramId,
         target_instruction_data: Vec<u32>,
-        target_account_count: u8,
+        target_accounts: Vec<[u8; 32]>,
         pda_seeds: Vec<[u8; 32]>,
         authorized_indices: Vec<u8>,
     ) -> Self {
@@ -193,13 +663,23 @@ impl Proposal {
             multisig_create_key,
             target_program_id,
             target_instruction_data,
-            target_account_count,
+            target_account_count: u8::try_from(target_accounts.len())
+                .expect("target accounts are checked against MAX_BATCH_ACCOUNTS"),
             pda_seeds,
             authorized_indices,
             approved: vec![proposer],
             rejected: vec![],
             status: ProposalStatus::Active,
             config_action: None,
+            threshold_reached_at: None,
+            expires_at: None,
+            calls: vec![],
+            config_batch: vec![],
+            abstained: vec![],
+            target_accounts,
+            cancelled: vec![],
+            vote_nonces: vec![],
+            executed_calls: 0,
         }
     }
 
@@ -223,7 +703,128 @@ impl Proposal {
             rejected: vec![],
             status: ProposalStatus::Active,
             config_action: Some(action),
+            threshold_reached_at: None,
+            expires_at: None,
+            calls: vec![],
+            config_batch: vec![],
+            abstained: vec![],
+            target_accounts: vec![],
+            cancelled: vec![],
+            vote_nonces: vec![],
+            executed_calls: 0,
+        }
+    }
+
+    /// Create a new config batch proposal applying `actions` together
+    pub fn new_config_batch(
+        index: u64,
+        proposer: [u8; 32],
+        multisig_create_key: [u8; 32],
+        actions: Vec<ConfigAction>,
+    ) -> Self {
+        let mut proposal = Self::new(
+            index,
+            proposer,
+            multisig_create_key,
+            [0u32; 8],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+        proposal.config_batch = actions;
+        proposal
+    }
+
+    /// Whether executing this proposal changes the config instead of emitting ChainedCalls
+    pub fn is_config(&self) -> bool {
+        self.config_action.is_some() || !self.config_batch.is_empty()
+    }
+
+    /// The config actions Execute applies: the single action, or the batch
+    pub fn config_actions(&self) -> Vec<ConfigAction> {
+        match &self.config_action {
+            Some(action) => vec![action.clone()],
+            None => self.config_batch.clone(),
+        }
+    }
+
+    /// Create a new batch proposal executing `calls` in order
+    pub fn new_batch(
+        index: u64,
+        proposer: [u8; 32],
+        multisig_create_key: [u8; 32],
+        calls: Vec<ProposalCall>,
+    ) -> Self {
+        let mut proposal = Self::new(
+            index,
+            proposer,
+            multisig_create_key,
+            [0u32; 8],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+        proposal.calls = calls;
+        proposal
+    }
+
+    /// The calls Execute emits, in order: the batch, or the proposal's single call
+    pub fn calls(&self) -> Vec<ProposalCall> {
+        if !self.calls.is_empty() {
+            return self.calls.clone();
+        }
+        vec![ProposalCall {
+            target_program_id: self.target_program_id,
+            target_instruction_data: self.target_instruction_data.clone(),
+            target_accounts: self.target_accounts.clone(),
+            pda_seeds: self.pda_seeds.clone(),
+            authorized_indices: self.authorized_indices.clone(),
+        }]
+    }
+
+    /// Whether the proposal was created before target accounts were recorded:
+    /// it names only how many accounts its call takes, not which ones, so
+    /// Execute refuses it (it can still be rejected, cancelled and closed).
+    pub fn predates_target_accounts(&self) -> bool {
+        self.target_account_count > 0 && self.target_accounts.is_empty() && self.calls.is_empty()
+    }
+
+    /// The calls the next Execute emits, in order: the proposal's single
+    /// call, or the batch's next step (see `batch_step_len`).
+    pub fn next_calls(&self) -> Vec<ProposalCall> {
+        let calls = self.calls();
+        let pending = &calls[usize::from(self.executed_calls).min(calls.len())..];
+        pending[..batch_step_len(pending)].to_vec()
+    }
+
+    /// Whether Execute has run every call of the batch (always true for a
+    /// single call once it executed)
+    pub fn all_calls_executed(&self) -> bool {
+        usize::from(self.executed_calls) >= self.calls.len()
+    }
+
+    /// Target accounts the next Execute must receive, in order. A batch step
+    /// lists each account once, in order of first use across its calls.
+    pub fn execute_accounts(&self) -> Vec<[u8; 32]> {
+        if self.calls.is_empty() {
+            return self.target_accounts.clone();
         }
+        batch_accounts(&self.next_calls())
+    }
+
+    /// Indices of the multisig vaults the proposal's calls sign for, ascending
+    pub fn vault_indices(&self) -> Vec<u8> {
+        let mut indices: Vec<u8> = self
+            .calls()
+            .iter()
+            .flat_map(|c| c.pda_seeds.iter())
+            .filter_map(|seed| vault_index_of_seed(&self.multisig_create_key, seed))
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
     }
 
     /// Add an approval. Returns true if this was a new approval.
@@ -232,6 +833,7 @@ impl Proposal {
             return false;
         }
         self.rejected.retain(|r| r != &member);
+        self.abstained.retain(|a| a != &member);
         self.approved.push(member);
         true
     }
@@ -242,27 +844,145 @@ impl Proposal {
             return false;
         }
         self.approved.retain(|a| a != &member);
+        self.abstained.retain(|a| a != &member);
         self.rejected.push(member);
+        self.bump_vote_nonce(member);
         true
     }
 
-    /// Check if the proposal has enough approvals
-    pub fn has_threshold(&self, threshold: u8) -> bool {
-        self.approved.len() >= threshold as usize
+    /// Add an abstention. Returns true if this was a new abstention.
+    pub fn abstain(&mut self, member: [u8; 32]) -> bool {
+        if self.abstained.contains(&member) {
+            return false;
+        }
+        self.approved.retain(|a| a != &member);
+        self.rejected.retain(|r| r != &member);
+        self.abstained.push(member);
+        self.bump_vote_nonce(member);
+        true
+    }
+
+    /// Withdraw an approval, rejection or abstention, and a cancel vote.
+    /// Returns true if the member had voted.
+    pub fn withdraw_vote(&mut self, member: [u8; 32]) -> bool {
+        let votes = |p: &Self| p.approved.len() + p.rejected.len() + p.abstained.len() + p.cancelled.len();
+        let before = votes(self);
+        self.approved.retain(|a| a != &member);
+        self.rejected.retain(|r| r != &member);
+        self.abstained.retain(|a| a != &member);
+        self.cancelled.retain(|c| c != &member);
+        if before == votes(self) {
+            return false;
+        }
+        self.bump_vote_nonce(member);
+        true
+    }
+
+    /// How often `member` has rejected, abstained or withdrawn on this proposal
+    pub fn vote_nonce(&self, member: &[u8; 32]) -> u32 {
+        self.vote_nonces
+            .iter()
+            .find(|(m, _)| m == member)
+            .map_or(0, |(_, nonce)| *nonce)
+    }
+
+    fn bump_vote_nonce(&mut self, member: [u8; 32]) {
+        match self.vote_nonces.iter_mut().find(|(m, _)| m == &member) {
+            Some((_, nonce)) => *nonce += 1,
+            None => self.vote_nonces.push((member, 1)),
+        }
+    }
+
+    /// Add a cancel vote. Returns true if this was a new vote.
+    pub fn cancel(&mut self, member: [u8; 32]) -> bool {
+        if self.cancelled.contains(&member) {
+            return false;
+        }
+        self.cancelled.push(member);
+        true
+    }
+
+    /// Check if current members with enough weight voted to cancel
+    pub fn has_cancel_threshold(&self, state: &MultisigState) -> bool {
+        state.vote_weight(&self.cancelled) >= state.threshold_for(self) as u32
+    }
+
+    /// Approval weight from current members (votes of removed members don't count)
+    pub fn approval_weight(&self, state: &MultisigState) -> u32 {
+        state.vote_weight(&self.approved)
+    }
+
+    /// Check if the proposal has enough approval weight from current members
+    pub fn has_threshold(&self, state: &MultisigState) -> bool {
+        self.approval_weight(state) >= state.threshold_for(self) as u32
+    }
+
+    /// Check if the proposal's expiry has passed at clock time `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expires_at, Some(at) if now >= at)
+    }
+
+    /// Check if the proposal can never reach threshold with the current members.
+    /// Abstentions are left out: an abstainer may still withdraw and approve, and
+    /// Rejected is final.
+    pub fn is_dead(&self, state: &MultisigState) -> bool {
+        let remaining = state.total_weight() - state.vote_weight(&self.rejected);
+        remaining < state.threshold_for(self) as u32
+    }
+}
+
+/// Check one call's target accounts: at most MAX_BATCH_ACCOUNTS, and none
+/// twice. LEZ refuses a transaction that names an account twice, so such a
+/// call could never execute.
+pub fn validate_target_accounts(target_accounts: &[[u8; 32]]) -> Result<(), MultisigError> {
+    if target_accounts.len() > MAX_BATCH_ACCOUNTS {
+        return Err(MultisigError::TooManyTargetAccounts);
+    }
+    if (1..target_accounts.len()).any(|i| target_accounts[..i].contains(&target_accounts[i])) {
+        return Err(MultisigError::DuplicateTargetAccount);
+    }
+    Ok(())
+}
+
+/// Distinct target accounts of a batch, in order of first use.
+pub fn batch_accounts(calls: &[ProposalCall]) -> Vec<[u8; 32]> {
+    let mut accounts: Vec<[u8; 32]> = Vec::new();
+    for id in calls.iter().flat_map(|c| c.target_accounts.iter()) {
+        if !accounts.contains(id) {
+            accounts.push(*id);
+        }
     }
+    accounts
+}
 
-    /// Check if the proposal can never reach threshold
-    pub fn is_dead(&self, threshold: u8, member_count: u8) -> bool {
-        let remaining = member_count as usize - self.rejected.len();
-        remaining < threshold as usize
+/// How many of `calls`, from the first, one Execute runs: every call up to
+/// the first that uses an account an earlier call of the step already uses.
+/// Each ChainedCall's pre_states are the accounts as passed to Execute, fixed
+/// before any call writes, so a call on an account an earlier call changed
+/// has to wait for the next Execute, which passes the account as it is then.
+pub fn batch_step_len(calls: &[ProposalCall]) -> usize {
+    let mut seen: Vec<[u8; 32]> = Vec::new();
+    for (i, call) in calls.iter().enumerate() {
+        let mut ids = call.target_accounts.clone();
+        ids.sort_unstable();
+        ids.dedup();
+        if i > 0 && ids.iter().any(|id| seen.contains(id)) {
+            return i;
+        }
+        seen.extend(ids);
     }
+    calls.len()
 }
 
 // ---------------------------------------------------------------------------
 // Multisig state (persisted in the multisig state PDA)
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Default, BorshSerialize, BorshDeserialize)]
+/// Borsh layout: the original five fields, then every later field appended in
+/// declaration order. Decoding fills fields missing from the end of an older
+/// account with their defaults (see `BorshDeserialize` below), so state written
+/// before they existed keeps working; the next write stores the full layout.
+#[derive(Debug, Clone, Default, BorshSerialize)]
 pub struct MultisigState {
     /// Unique key used to derive this multisig's PDA
     pub create_key: [u8; 32],
@@ -274,6 +994,72 @@ pub struct MultisigState {
     pub members: Vec<[u8; 32]>,
     /// Transaction/proposal counter (incremented on each Propose)
     pub transaction_index: u64,
+    /// Proposals with index <= this were created under an older config and
+    /// can no longer be approved or executed (set on every config change)
+    pub stale_transaction_index: u64,
+    /// Delay (in clock units) between reaching threshold and execute; 0 = none
+    pub time_lock: u64,
+    /// Clock account whose data (u64 LE) is the current time for the time-lock and expiry
+    pub clock_account: [u8; 32],
+    /// Lifetime (in clock units) of proposals that don't set their own; 0 = never expire
+    pub default_expiry: u64,
+    /// Vote weight of each member, in `members` order. Empty = one vote per
+    /// member, which is how every unweighted multisig is stored.
+    pub weights: Vec<u8>,
+    /// Permission bitflags (PERMISSION_*) of each member, in `members` order.
+    /// Empty = every member has every permission.
+    pub permissions: Vec<u8>,
+    /// Indices of the vaults proposals and spending limits have used, ascending.
+    /// Vault 0 is the default vault and is not listed until used.
+    pub vaults: Vec<u8>,
+    /// Threshold for config proposals; 0 = `threshold`
+    pub config_threshold: u8,
+    /// Threshold for proposals that emit ChainedCalls; 0 = `threshold`
+    pub transfer_threshold: u8,
+    /// Per-target-program thresholds, overriding `transfer_threshold`
+    pub program_thresholds: Vec<ProgramThreshold>,
+    /// Programs proposals may call; empty = any program not denied
+    pub allowed_programs: Vec<ProgramId>,
+    /// Programs proposals may never call
+    pub denied_programs: Vec<ProgramId>,
+    /// Latest time read from `clock_account`; a clock reading earlier than it
+    /// is refused. Reset when SetTimeLock changes the clock account.
+    pub last_clock: u64,
+}
+
+/// Decode a field appended to an account layout after accounts were already
+/// written without it: at the end of the data it takes its default.
+fn read_appended<T: BorshDeserialize + Default, R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<T> {
+    let mut first = [0u8; 1];
+    if reader.read(&mut first)? == 0 {
+        return Ok(T::default());
+    }
+    T::deserialize_reader(&mut borsh::io::Read::chain(first.as_slice(), reader))
+}
+
+impl BorshDeserialize for MultisigState {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        Ok(Self {
+            create_key: BorshDeserialize::deserialize_reader(reader)?,
+            threshold: BorshDeserialize::deserialize_reader(reader)?,
+            member_count: BorshDeserialize::deserialize_reader(reader)?,
+            members: BorshDeserialize::deserialize_reader(reader)?,
+            transaction_index: BorshDeserialize::deserialize_reader(reader)?,
+            stale_transaction_index: read_appended(reader)?,
+            time_lock: read_appended(reader)?,
+            clock_account: read_appended(reader)?,
+            default_expiry: read_appended(reader)?,
+            weights: read_appended(reader)?,
+            permissions: read_appended(reader)?,
+            vaults: read_appended(reader)?,
+            config_threshold: read_appended(reader)?,
+            transfer_threshold: read_appended(reader)?,
+            program_thresholds: read_appended(reader)?,
+            allowed_programs: read_appended(reader)?,
+            denied_programs: read_appended(reader)?,
+            last_clock: read_appended(reader)?,
+        })
+    }
 }
 
 impl MultisigState {
@@ -285,6 +1071,19 @@ impl MultisigState {
             member_count,
             members,
             transaction_index: 0,
+            stale_transaction_index: 0,
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
+            vaults: vec![],
+            config_threshold: 0,
+            transfer_threshold: 0,
+            program_thresholds: vec![],
+            allowed_programs: vec![],
+            denied_programs: vec![],
+            last_clock: 0,
         }
     }
 
@@ -292,6 +1091,337 @@ impl MultisigState {
         self.members.contains(id)
     }
 
+    /// Permission bitflags of `id`; 0 for non-members
+    pub fn permissions_of(&self, id: &[u8; 32]) -> u8 {
+        match self.members.iter().position(|m| m == id) {
+            Some(i) => self.permissions.get(i).copied().unwrap_or(PERMISSION_ALL),
+            None => 0,
+        }
+    }
+
+    /// Whether `id` is a member holding every flag in `permission`
+    pub fn has_permission(&self, id: &[u8; 32], permission: u8) -> bool {
+        self.is_member(id) && self.permissions_of(id) & permission == permission
+    }
+
+    /// Fail with MissingPermission unless `id` holds `permission`
+    pub fn require_permission(&self, id: &[u8; 32], permission: u8) -> Result<(), MultisigError> {
+        if !self.has_permission(id, permission) {
+            return Err(MultisigError::MissingPermission);
+        }
+        Ok(())
+    }
+
+    /// Vote weight of `id`; 0 for non-members and members without PERMISSION_VOTE
+    pub fn weight_of(&self, id: &[u8; 32]) -> u32 {
+        if !self.has_permission(id, PERMISSION_VOTE) {
+            return 0;
+        }
+        match self.members.iter().position(|m| m == id) {
+            Some(i) => self.weights.get(i).map_or(1, |w| *w as u32),
+            None => 0,
+        }
+    }
+
+    /// Sum of all voting members' weights (N for an unweighted multisig)
+    pub fn total_weight(&self) -> u32 {
+        self.members.iter().map(|m| self.weight_of(m)).sum()
+    }
+
+    /// Total weight of the votes cast by accounts that are still members
+    pub fn vote_weight(&self, votes: &[[u8; 32]]) -> u32 {
+        votes.iter().map(|v| self.weight_of(v)).sum()
+    }
+
+    /// Check that `weights` is empty or one non-zero weight per member
+    pub fn validate_weights(&self) -> Result<(), MultisigError> {
+        if self.weights.is_empty() {
+            return Ok(());
+        }
+        if self.weights.len() != self.members.len() || self.weights.contains(&0) {
+            return Err(MultisigError::InvalidWeights);
+        }
+        Ok(())
+    }
+
+    /// Check that `permissions` is empty or one valid set of flags per member,
+    /// and that some member can still propose and some member can execute
+    pub fn validate_permissions(&self) -> Result<(), MultisigError> {
+        if self.permissions.is_empty() {
+            return Ok(());
+        }
+        if self.permissions.len() != self.members.len()
+            || self.permissions.iter().any(|p| p & !PERMISSION_ALL != 0)
+        {
+            return Err(MultisigError::InvalidPermissions);
+        }
+        let anyone_can = |permission: u8| self.permissions.iter().any(|p| p & permission != 0);
+        if !anyone_can(PERMISSION_INITIATE) || !anyone_can(PERMISSION_EXECUTE) {
+            return Err(MultisigError::InvalidPermissions);
+        }
+        Ok(())
+    }
+
+    /// Check that no member is listed twice. A duplicate would count its
+    /// weight twice in `total_weight` and when deciding a proposal is dead.
+    pub fn validate_members(&self) -> Result<(), MultisigError> {
+        if (1..self.members.len()).any(|i| self.members[..i].contains(&self.members[i])) {
+            return Err(MultisigError::DuplicateMember);
+        }
+        Ok(())
+    }
+
+    /// Check that a time-lock or default expiry comes with a clock account.
+    /// Without one no proposal could ever be timed, so every proposal would
+    /// be locked (or unable to be created) for good. A member's account holds
+    /// no time, and a member could pick what it holds, so it can't be the clock.
+    pub fn validate_clock(&self) -> Result<(), MultisigError> {
+        if self.time_lock == 0 && self.default_expiry == 0 {
+            return Ok(());
+        }
+        if self.clock_account == [0u8; 32] {
+            return Err(MultisigError::ClockAccountNotSet);
+        }
+        if self.is_member(&self.clock_account) {
+            return Err(MultisigError::InvalidClockConfig);
+        }
+        Ok(())
+    }
+
+    /// Check a new spending limit: a valid vault, non-zero allowance and only
+    /// current members may spend
+    pub fn validate_spending_limit(
+        &self,
+        vault_index: u8,
+        amount: u128,
+        members: &[[u8; 32]],
+    ) -> Result<(), MultisigError> {
+        if vault_index >= MAX_VAULTS {
+            return Err(MultisigError::InvalidVaultIndex);
+        }
+        if amount == 0 || members.is_empty() {
+            return Err(MultisigError::InvalidSpendingLimitParams);
+        }
+        if !members.iter().all(|m| self.is_member(m)) {
+            return Err(MultisigError::MemberNotFound);
+        }
+        Ok(())
+    }
+
+    /// Record that vault `vault_index` is in use
+    pub fn record_vault(&mut self, vault_index: u8) {
+        if let Err(i) = self.vaults.binary_search(&vault_index) {
+            self.vaults.insert(i, vault_index);
+        }
+    }
+
+    /// Threshold that applies to proposals of `class`
+    pub fn class_threshold(&self, class: &ActionClass) -> u8 {
+        let or_default = |t: u8| if t > 0 { t } else { self.threshold };
+        match class {
+            ActionClass::Config => or_default(self.config_threshold),
+            ActionClass::Transfer => or_default(self.transfer_threshold),
+            ActionClass::Program(program_id) => self
+                .program_thresholds
+                .iter()
+                .find(|p| p.program_id == *program_id)
+                .map_or_else(|| or_default(self.transfer_threshold), |p| p.threshold),
+        }
+    }
+
+    /// Approval weight `proposal` needs: the config threshold for config
+    /// proposals, else the highest threshold among the programs it calls
+    pub fn threshold_for(&self, proposal: &Proposal) -> u8 {
+        if proposal.is_config() {
+            return self.class_threshold(&ActionClass::Config);
+        }
+        proposal
+            .calls()
+            .iter()
+            .map(|c| self.class_threshold(&ActionClass::Program(c.target_program_id)))
+            .max()
+            .unwrap_or(self.threshold)
+    }
+
+    /// Highest threshold of any class
+    pub fn max_threshold(&self) -> u8 {
+        self.program_thresholds
+            .iter()
+            .map(|p| p.threshold)
+            .chain([self.threshold, self.config_threshold, self.transfer_threshold])
+            .max()
+            .unwrap_or(self.threshold)
+    }
+
+    /// Fail with ProgramNotAllowed if proposals may not call `program_id`
+    pub fn check_program(&self, program_id: &ProgramId) -> Result<(), MultisigError> {
+        if self.denied_programs.contains(program_id)
+            || (!self.allowed_programs.is_empty() && !self.allowed_programs.contains(program_id))
+        {
+            return Err(MultisigError::ProgramNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Whether a proposal was created before the last config change
+    pub fn is_stale(&self, proposal_index: u64) -> bool {
+        proposal_index <= self.stale_transaction_index
+    }
+
+    /// Invalidate every proposal created so far. Called when a config action
+    /// changes members or threshold, so old votes can't carry over.
+    pub fn invalidate_prior_proposals(&mut self) {
+        self.stale_transaction_index = self.transaction_index;
+    }
+
+    /// Apply config actions in order, then check that the resulting config is
+    /// valid. Intermediate states are not checked, so a batch may remove a
+    /// member before the add that keeps N >= M.
+    pub fn apply_config(&mut self, actions: &[ConfigAction]) -> Result<(), MultisigError> {
+        for action in actions {
+            match action {
+                ConfigAction::AddMember { new_member } => {
+                    if self.is_member(new_member) {
+                        return Err(MultisigError::AlreadyMember);
+                    }
+                    self.members.push(*new_member);
+                    if !self.weights.is_empty() {
+                        self.weights.push(1);
+                    }
+                    if !self.permissions.is_empty() {
+                        self.permissions.push(PERMISSION_ALL);
+                    }
+                }
+                ConfigAction::RemoveMember { member } => {
+                    let i = self
+                        .members
+                        .iter()
+                        .position(|m| m == member)
+                        .ok_or(MultisigError::MemberNotFound)?;
+                    self.members.remove(i);
+                    if !self.weights.is_empty() {
+                        self.weights.remove(i);
+                    }
+                    if !self.permissions.is_empty() {
+                        self.permissions.remove(i);
+                    }
+                }
+                ConfigAction::ChangeThreshold { new_threshold } => {
+                    self.threshold = *new_threshold;
+                }
+                ConfigAction::SetTimeLock { time_lock, clock_account } => {
+                    // A new clock need not share the old one's time scale
+                    if *clock_account != self.clock_account {
+                        self.last_clock = 0;
+                    }
+                    self.time_lock = *time_lock;
+                    self.clock_account = *clock_account;
+                }
+                ConfigAction::ReplaceMember { old, new } => {
+                    if self.is_member(new) {
+                        return Err(MultisigError::AlreadyMember);
+                    }
+                    let slot = self
+                        .members
+                        .iter_mut()
+                        .find(|m| *m == old)
+                        .ok_or(MultisigError::MemberNotFound)?;
+                    *slot = *new;
+                }
+                ConfigAction::SetWeight { member, weight } => {
+                    let i = self
+                        .members
+                        .iter()
+                        .position(|m| m == member)
+                        .ok_or(MultisigError::MemberNotFound)?;
+                    if self.weights.is_empty() {
+                        self.weights = vec![1; self.members.len()];
+                    }
+                    self.weights[i] = *weight;
+                }
+                ConfigAction::SetPermissions { member, permissions } => {
+                    let i = self
+                        .members
+                        .iter()
+                        .position(|m| m == member)
+                        .ok_or(MultisigError::MemberNotFound)?;
+                    if self.permissions.is_empty() {
+                        self.permissions = vec![PERMISSION_ALL; self.members.len()];
+                    }
+                    self.permissions[i] = *permissions;
+                }
+                ConfigAction::AddSpendingLimit { vault_index, amount, members, .. } => {
+                    self.validate_spending_limit(*vault_index, *amount, members)?;
+                    self.record_vault(*vault_index);
+                }
+                // Spending limits live in their own PDAs; Execute creates and closes them
+                ConfigAction::RemoveSpendingLimit { .. } => {}
+                ConfigAction::SetClassThreshold { class, threshold } => match class {
+                    ActionClass::Config => self.config_threshold = *threshold,
+                    ActionClass::Transfer => self.transfer_threshold = *threshold,
+                    ActionClass::Program(program_id) => {
+                        self.program_thresholds.retain(|p| p.program_id != *program_id);
+                        if *threshold > 0 {
+                            self.program_thresholds.push(ProgramThreshold {
+                                program_id: *program_id,
+                                threshold: *threshold,
+                            });
+                        }
+                    }
+                },
+                ConfigAction::SetProgramPolicy { target_program_id, policy } => {
+                    self.allowed_programs.retain(|p| p != target_program_id);
+                    self.denied_programs.retain(|p| p != target_program_id);
+                    match policy {
+                        ProgramPolicy::Default => {}
+                        ProgramPolicy::Allowed => self.allowed_programs.push(*target_program_id),
+                        ProgramPolicy::Denied => self.denied_programs.push(*target_program_id),
+                    }
+                }
+                // Execute wipes the state account instead of writing it back
+                ConfigAction::CloseMultisig => {}
+            }
+        }
+
+        // Each limit PDA can only be touched once per proposal
+        let limit_keys: Vec<[u8; 32]> = actions.iter().filter_map(ConfigAction::spending_limit_key).collect();
+        if (1..limit_keys.len()).any(|i| limit_keys[..i].contains(&limit_keys[i])) {
+            return Err(MultisigError::InvalidSpendingLimitParams);
+        }
+
+        if self.members.len() > 10 {
+            return Err(MultisigError::TooManyMembers);
+        }
+        self.validate_members()?;
+        if self.allowed_programs.len() + self.denied_programs.len() > MAX_POLICY_PROGRAMS {
+            return Err(MultisigError::TooManyPolicyPrograms);
+        }
+        self.member_count = self.members.len() as u8;
+        self.validate_weights()?;
+        self.validate_permissions()?;
+        self.validate_clock()?;
+        if self.threshold < 1 || self.program_thresholds.len() > MAX_PROGRAM_THRESHOLDS {
+            return Err(MultisigError::InvalidThreshold);
+        }
+        if self.max_threshold() as u32 > self.total_weight() {
+            let changed_threshold = actions.iter().any(|a| {
+                matches!(
+                    a,
+                    ConfigAction::ChangeThreshold { .. }
+                        | ConfigAction::SetWeight { .. }
+                        | ConfigAction::SetPermissions { .. }
+                        | ConfigAction::SetClassThreshold { .. }
+                )
+            });
+            return Err(if changed_threshold {
+                MultisigError::ThresholdExceedsMemberCount
+            } else {
+                MultisigError::RemovalBreaksThreshold
+            });
+        }
+        Ok(())
+    }
+
     /// Increment and return the next proposal index
     pub fn next_proposal_index(&mut self) -> u64 {
         self.transaction_index += 1;
@@ -300,85 +1430,250 @@ impl MultisigState {
 }
 
 // ---------------------------------------------------------------------------
-// PDA derivation helpers
+// Spending limit (stored in its own PDA account)
 // ---------------------------------------------------------------------------
 
-/// Compute PDA seed for a multisig identified by `create_key`.
-pub fn multisig_state_pda_seed(create_key: &[u8; 32]) -> PdaSeed {
-    let tag = b"multisig_state__"; // 16 bytes, padded
-    let mut seed = [0u8; 32];
-    for i in 0..tag.len() {
-        seed[i] = tag[i];
+/// An allowance letting `members` move up to `amount` of a token out of one
+/// of the multisig's vaults per `period` without a proposal. Created and closed by
+/// config proposals; UseSpendingLimit spends from it.
+/// PDA derived from: spending_limit_pda_seed(create_key, limit_key)
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct SpendingLimit {
+    /// The create_key of the parent multisig (for verification)
+    pub multisig_create_key: [u8; 32],
+    /// Key of this limit (for PDA derivation)
+    pub limit_key: [u8; 32],
+    /// Vault the transfers are taken from
+    pub vault_index: u8,
+    /// Token program the transfers are sent to
+    pub token_program_id: ProgramId,
+    /// Allowance per period
+    pub amount: u128,
+    /// Period length in clock units; 0 = `amount` is a one-off allowance that never resets
+    pub period: u64,
+    /// Members allowed to spend under this limit
+    pub members: Vec<[u8; 32]>,
+    /// Allowance left in the current period
+    pub remaining: u128,
+    /// Clock time at which the current period started
+    pub last_reset: u64,
+}
+
+impl SpendingLimit {
+    pub fn new(
+        multisig_create_key: [u8; 32],
+        limit_key: [u8; 32],
+        vault_index: u8,
+        token_program_id: ProgramId,
+        amount: u128,
+        period: u64,
+        members: Vec<[u8; 32]>,
+    ) -> Self {
+        Self {
+            multisig_create_key,
+            limit_key,
+            vault_index,
+            token_program_id,
+            amount,
+            period,
+            members,
+            remaining: amount,
+            last_reset: 0,
+        }
     }
-    for i in 0..32 {
-        seed[i] ^= create_key[i];
+
+    /// Refill the allowance if clock time `now` is in a later period. Periods
+    /// are aligned to multiples of `period`, so creating a limit needs no clock.
+    pub fn refresh(&mut self, now: u64) {
+        if self.period == 0 {
+            return;
+        }
+        let start = now - now % self.period;
+        if start > self.last_reset {
+            self.remaining = self.amount;
+            self.last_reset = start;
+        }
+    }
+
+    /// Deduct `amount` from the remaining allowance
+    pub fn spend(&mut self, amount: u128) -> Result<(), MultisigError> {
+        if amount > self.remaining {
+            return Err(MultisigError::SpendingLimitExceeded);
+        }
+        self.remaining -= amount;
+        Ok(())
     }
-    PdaSeed::new(seed)
 }
 
-/// Compute the on-chain AccountId (PDA) for a multisig.
-pub fn compute_multisig_state_pda(program_id: &ProgramId, create_key: &[u8; 32]) -> AccountId {
-    AccountId::from((program_id, &multisig_state_pda_seed(create_key)))
+// ---------------------------------------------------------------------------
+// Member account record (stored in a claimed member account)
+// ---------------------------------------------------------------------------
+
+/// Written into a member account when the multisig program claims it, binding
+/// the account to the multisig that claimed it. A wiped proposal or closed
+/// spending limit PDA is program-owned too, but holds no record, so it can't
+/// pass as a member account.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct MemberAccountRecord {
+    /// The create_key of the multisig that claimed the account
+    pub multisig_create_key: [u8; 32],
+    /// Set by ReleaseMemberAccount; a released account can be claimed again,
+    /// by any multisig of the program, when its holder signs
+    pub released: bool,
 }
 
-/// Compute PDA seed for a proposal.
-/// Each proposal gets a unique PDA: seed = XOR("multisig_prop___", create_key) XOR proposal_index in last 8 bytes.
-pub fn proposal_pda_seed(create_key: &[u8; 32], proposal_index: u64) -> PdaSeed {
-    let tag = b"multisig_prop___"; // 16 bytes
-    let mut seed = [0u8; 32];
-    for i in 0..tag.len() {
-        seed[i] = tag[i];
+impl MemberAccountRecord {
+    pub fn new(multisig_create_key: [u8; 32]) -> Self {
+        Self { multisig_create_key, released: false }
     }
-    // XOR create_key
-    for i in 0..32 {
-        seed[i] ^= create_key[i];
-    }
-    // Mix in proposal_index (big-endian in last 8 bytes)
-    let idx_bytes = proposal_index.to_be_bytes();
-    for i in 0..8 {
-        seed[24 + i] ^= idx_bytes[i];
-    }
-    PdaSeed::new(seed)
 }
 
-/// Compute the on-chain AccountId (PDA) for a proposal.
-pub fn compute_proposal_pda(program_id: &ProgramId, create_key: &[u8; 32], proposal_index: u64) -> AccountId {
-    AccountId::from((program_id, &proposal_pda_seed(create_key, proposal_index)))
-}
+// ---------------------------------------------------------------------------
+// Voter record (stored in its own PDA account)
+// ---------------------------------------------------------------------------
 
-/// Compute PDA seed for a multisig vault (holds assets authorized by the multisig).
-/// Uses SHA-256(pad32("multisig_vault__") || create_key) — matches lez-cli multi-seed derivation.
-/// This allows `lez-cli pda vault --create-key <key>` to work without any special tooling.
-pub fn vault_pda_seed(create_key: &[u8; 32]) -> PdaSeed {
-    // Matches lez-cli pda.rs hash_seeds: SHA-256(seed1_32bytes || seed2_32bytes)
-    let mut tag_padded = [0u8; 32];
-    let tag = b"multisig_vault__"; // 16 bytes
-    tag_padded[..tag.len()].copy_from_slice(tag);
-
-    let mut input = [0u8; 64];
-    input[..32].copy_from_slice(&tag_padded);
-    input[32..].copy_from_slice(create_key);
-
-    // Use sha2 for host-side hashing (no risc0 dep in multisig_core)
-    use sha2::{Sha256, Digest};
-    let hash: [u8; 32] = Sha256::digest(&input).into();
-    PdaSeed::new(hash)
+/// A member's program-owned stand-in account. The member signs instructions
+/// off-chain with their wallet key and ActAsVoter runs them with this record in
+/// the signer slot, so the wallet account is never claimed and one key can sit
+/// on any number of multisigs.
+/// PDA derived from: voter_record_pda_seed(create_key, member)
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct VoterRecord {
+    /// The create_key of the parent multisig (for verification)
+    pub multisig_create_key: [u8; 32],
+    /// Member account ID the record acts for
+    pub member: [u8; 32],
+    /// Number of ActAsVoter calls run so far (replay protection)
+    pub nonce: u64,
 }
 
-/// Compute the on-chain AccountId (PDA) for a multisig's vault.
-pub fn compute_vault_pda(program_id: &ProgramId, create_key: &[u8; 32]) -> AccountId {
-    AccountId::from((program_id, &vault_pda_seed(create_key)))
+impl VoterRecord {
+    pub fn new(multisig_create_key: [u8; 32], member: [u8; 32]) -> Self {
+        Self { multisig_create_key, member, nonce: 0 }
+    }
 }
 
-/// Get the raw [u8; 32] seed bytes for a vault PDA (for storage in proposals).
-pub fn vault_pda_seed_bytes(create_key: &[u8; 32]) -> [u8; 32] {
-    // Recompute hash directly since PdaSeed inner field is private
-    let mut tag_padded = [0u8; 32];
-    let tag = b"multisig_vault__";
-    tag_padded[..tag.len()].copy_from_slice(tag);
-    let mut input = [0u8; 64];
-    input[..32].copy_from_slice(&tag_padded);
-    input[32..].copy_from_slice(create_key);
-    use sha2::{Sha256, Digest};
-    Sha256::digest(&input).into()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_decodes_baseline_layout() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        // create_key, threshold, member_count, members, transaction_index
+        let bytes = borsh::to_vec(&([9u8; 32], 2u8, 3u8, members.clone(), 4u64)).unwrap();
+
+        let state: MultisigState = borsh::from_slice(&bytes).unwrap();
+        assert_eq!(state.create_key, [9u8; 32]);
+        assert_eq!(state.threshold, 2);
+        assert_eq!(state.members, members);
+        assert_eq!(state.transaction_index, 4);
+        assert!(state.weights.is_empty());
+        assert!(state.permissions.is_empty());
+        assert_eq!(state.time_lock, 0);
+        assert_eq!(state.weight_of(&members[0]), 1);
+        assert!(state.has_permission(&members[0], PERMISSION_ALL));
+    }
+
+    #[test]
+    fn test_proposal_decodes_baseline_layout() {
+        // index, proposer, multisig_create_key, target_program_id,
+        // target_instruction_data, target_account_count, pda_seeds,
+        // authorized_indices, approved, rejected, status, config_action
+        let mut bytes = borsh::to_vec(&(
+            3u64,
+            [1u8; 32],
+            [9u8; 32],
+            [7u32; 8],
+            vec![5u32],
+            2u8,
+            vec![[4u8; 32]],
+            vec![0u8],
+            vec![[1u8; 32]],
+            Vec::<[u8; 32]>::new(),
+        ))
+        .unwrap();
+        bytes.extend(borsh::to_vec(&(ProposalStatus::Active, None::<ConfigAction>)).unwrap());
+
+        let proposal: Proposal = borsh::from_slice(&bytes).unwrap();
+        assert_eq!(proposal.index, 3);
+        assert_eq!(proposal.target_account_count, 2);
+        assert_eq!(proposal.approved, vec![[1u8; 32]]);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert!(proposal.target_accounts.is_empty());
+        assert!(proposal.calls.is_empty());
+        assert_eq!(proposal.expires_at, None);
+        assert!(proposal.cancelled.is_empty());
+        assert!(proposal.predates_target_accounts());
+    }
+
+    #[test]
+    fn test_proposal_roundtrips_full_layout() {
+        let mut proposal = Proposal::new(1, [1u8; 32], [9u8; 32], [7u32; 8], vec![5], vec![[2u8; 32], [3u8; 32]], vec![], vec![0]);
+        proposal.abstained = vec![[4u8; 32]];
+        proposal.cancel([1u8; 32]);
+        proposal.reject([5u8; 32]);
+        proposal.executed_calls = 1;
+        assert_eq!(proposal.target_account_count, 2);
+        assert!(!proposal.predates_target_accounts());
+
+        let bytes = borsh::to_vec(&proposal).unwrap();
+        let decoded: Proposal = borsh::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.target_accounts, proposal.target_accounts);
+        assert_eq!(decoded.abstained, proposal.abstained);
+        assert_eq!(decoded.cancelled, proposal.cancelled);
+        assert_eq!(decoded.vote_nonces, vec![([5u8; 32], 1)]);
+        assert_eq!(decoded.executed_calls, 1);
+        // cutting into vote_nonces is an error, not a default
+        assert!(borsh::from_slice::<Proposal>(&bytes[..bytes.len() - 2]).is_err());
+    }
+
+    fn payment(vault: u8, recipient: u8) -> ProposalCall {
+        ProposalCall {
+            target_program_id: [7u32; 8],
+            target_instruction_data: vec![0],
+            target_accounts: vec![[vault; 32], [recipient; 32]],
+            pda_seeds: vec![],
+            authorized_indices: vec![0],
+        }
+    }
+
+    #[test]
+    fn test_batch_runs_in_steps_at_reused_accounts() {
+        // Vault 30 pays 40 and 41, vault 31 pays 42, then vault 30 pays 43
+        let calls = vec![payment(30, 40), payment(31, 42), payment(30, 41), payment(30, 43)];
+        assert_eq!(batch_step_len(&calls), 2);
+        assert_eq!(batch_step_len(&calls[2..]), 1);
+        assert_eq!(batch_step_len(&[payment(30, 30)]), 1);
+        assert_eq!(batch_step_len(&[]), 0);
+
+        let mut proposal = Proposal::new_batch(1, [1u8; 32], [9u8; 32], calls.clone());
+        assert_eq!(proposal.next_calls(), calls[..2].to_vec());
+        assert_eq!(proposal.execute_accounts(), vec![[30u8; 32], [40u8; 32], [31u8; 32], [42u8; 32]]);
+        assert!(!proposal.all_calls_executed());
+
+        proposal.executed_calls = 2;
+        assert_eq!(proposal.next_calls(), calls[2..3].to_vec());
+        assert_eq!(proposal.execute_accounts(), vec![[30u8; 32], [41u8; 32]]);
+
+        proposal.executed_calls = 4;
+        assert!(proposal.next_calls().is_empty());
+        assert!(proposal.all_calls_executed());
+    }
+
+    #[test]
+    fn test_state_roundtrips_full_layout() {
+        let mut state = MultisigState::new([9u8; 32], 3, vec![[1u8; 32], [2u8; 32]]);
+        state.weights = vec![2, 1];
+        state.denied_programs = vec![[5u32; 8]];
+        state.last_clock = 1_000;
+
+        let decoded: MultisigState = borsh::from_slice(&borsh::to_vec(&state).unwrap()).unwrap();
+        assert_eq!(decoded.weights, state.weights);
+        assert_eq!(decoded.denied_programs, state.denied_programs);
+        assert_eq!(decoded.last_clock, 1_000);
+        // a truncated field is still an error, not a default
+        let bytes = borsh::to_vec(&state).unwrap();
+        assert!(borsh::from_slice::<MultisigState>(&bytes[..bytes.len() - 1]).is_err());
+    }
 }
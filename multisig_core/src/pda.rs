@@ -0,0 +1,267 @@
+// Canonical PDA derivation for the multisig program.
+//
+// This is the single source of truth for every PDA the program owns. The
+// guest, the FFI, the CLI (via the IDL seed annotations) and the e2e tests
+// must all produce the same addresses, so anything that needs a PDA goes
+// through these helpers.
+//
+// Seed scheme (matches lez-cli multi-seed hashing):
+//   seed       = SHA-256(pad32(segment_0) || pad32(segment_1) || ...)
+//   account_id = NSSA PDA(program_id, seed)
+//
+// Every segment is zero-padded to 32 bytes; integer segments are encoded as
+// u64 little-endian. The seed is always hashed, even for a single segment.
+//
+//   multisig_state: ["multisig_state__", create_key]
+//   proposal:       ["multisig_prop___", create_key, proposal_index]
+//   vault 0:        ["multisig_vault__", create_key]
+//   vault N (N>0):  ["multisig_vault__", create_key, vault_index]
+//   spending limit: ["multisig_limit__", create_key, limit_key]
+//   voter record:   ["multisig_voter__", create_key, member]
+//
+// Published test vectors live in docs/pda-test-vectors.json.
+//
+// The state and proposal PDAs were derived by XOR of tag and create_key before
+// this scheme; see CHANGELOG.md for the break and migration.
+
+use nssa_core::account::AccountId;
+use nssa_core::program::{PdaSeed, ProgramId};
+use sha2::{Digest, Sha256};
+
+/// Tag segment for the multisig state PDA (16 bytes).
+pub const MULTISIG_STATE_TAG: &[u8; 16] = b"multisig_state__";
+/// Tag segment for proposal PDAs (16 bytes).
+pub const PROPOSAL_TAG: &[u8; 16] = b"multisig_prop___";
+/// Tag segment for the vault PDA (16 bytes).
+pub const VAULT_TAG: &[u8; 16] = b"multisig_vault__";
+/// Tag segment for spending limit PDAs (16 bytes).
+pub const SPENDING_LIMIT_TAG: &[u8; 16] = b"multisig_limit__";
+/// Tag segment for voter record PDAs (16 bytes).
+pub const VOTER_RECORD_TAG: &[u8; 16] = b"multisig_voter__";
+
+/// Hash seed segments into a 32-byte PDA seed.
+/// Each segment is zero-padded to 32 bytes; segments longer than 32 bytes are rejected.
+pub fn hash_seed_segments(segments: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for segment in segments {
+        assert!(segment.len() <= 32, "PDA seed segment longer than 32 bytes");
+        let mut padded = [0u8; 32];
+        padded[..segment.len()].copy_from_slice(segment);
+        hasher.update(padded);
+    }
+    hasher.finalize().into()
+}
+
+/// Compute the on-chain AccountId (PDA) for raw seed segments.
+/// Generated clients that only know an account's IDL seeds derive it here.
+pub fn compute_pda(program_id: &ProgramId, segments: &[&[u8]]) -> AccountId {
+    AccountId::from((program_id, &PdaSeed::new(hash_seed_segments(segments))))
+}
+
+/// Raw seed bytes for the multisig state PDA.
+pub fn multisig_state_pda_seed_bytes(create_key: &[u8; 32]) -> [u8; 32] {
+    hash_seed_segments(&[MULTISIG_STATE_TAG, create_key])
+}
+
+/// Compute PDA seed for a multisig identified by `create_key`.
+pub fn multisig_state_pda_seed(create_key: &[u8; 32]) -> PdaSeed {
+    PdaSeed::new(multisig_state_pda_seed_bytes(create_key))
+}
+
+/// Compute the on-chain AccountId (PDA) for a multisig.
+pub fn compute_multisig_state_pda(program_id: &ProgramId, create_key: &[u8; 32]) -> AccountId {
+    AccountId::from((program_id, &multisig_state_pda_seed(create_key)))
+}
+
+/// Raw seed bytes for a proposal PDA.
+pub fn proposal_pda_seed_bytes(create_key: &[u8; 32], proposal_index: u64) -> [u8; 32] {
+    hash_seed_segments(&[PROPOSAL_TAG, create_key, &proposal_index.to_le_bytes()])
+}
+
+/// Compute PDA seed for a proposal.
+pub fn proposal_pda_seed(create_key: &[u8; 32], proposal_index: u64) -> PdaSeed {
+    PdaSeed::new(proposal_pda_seed_bytes(create_key, proposal_index))
+}
+
+/// Compute the on-chain AccountId (PDA) for a proposal.
+pub fn compute_proposal_pda(program_id: &ProgramId, create_key: &[u8; 32], proposal_index: u64) -> AccountId {
+    AccountId::from((program_id, &proposal_pda_seed(create_key, proposal_index)))
+}
+
+/// Number of vaults per multisig; valid vault indices are 0..MAX_VAULTS.
+pub const MAX_VAULTS: u8 = 16;
+
+/// Get the raw [u8; 32] seed bytes for a vault PDA (for storage in proposals).
+/// Vault 0 keeps the original single-vault seed (no index segment), so
+/// multisigs created before indexed vaults keep their vault address.
+pub fn vault_pda_seed_bytes(create_key: &[u8; 32], vault_index: u8) -> [u8; 32] {
+    if vault_index == 0 {
+        return hash_seed_segments(&[VAULT_TAG, create_key]);
+    }
+    hash_seed_segments(&[VAULT_TAG, create_key, &(vault_index as u64).to_le_bytes()])
+}
+
+/// Compute PDA seed for a multisig vault (holds assets authorized by the multisig).
+pub fn vault_pda_seed(create_key: &[u8; 32], vault_index: u8) -> PdaSeed {
+    PdaSeed::new(vault_pda_seed_bytes(create_key, vault_index))
+}
+
+/// Compute the on-chain AccountId (PDA) for one of a multisig's vaults.
+pub fn compute_vault_pda(program_id: &ProgramId, create_key: &[u8; 32], vault_index: u8) -> AccountId {
+    AccountId::from((program_id, &vault_pda_seed(create_key, vault_index)))
+}
+
+/// Index of the multisig's vault whose seed is `seed`, if it is one.
+pub fn vault_index_of_seed(create_key: &[u8; 32], seed: &[u8; 32]) -> Option<u8> {
+    (0..MAX_VAULTS).find(|i| vault_pda_seed_bytes(create_key, *i) == *seed)
+}
+
+/// Raw seed bytes for a spending limit PDA.
+pub fn spending_limit_pda_seed_bytes(create_key: &[u8; 32], limit_key: &[u8; 32]) -> [u8; 32] {
+    hash_seed_segments(&[SPENDING_LIMIT_TAG, create_key, limit_key])
+}
+
+/// Compute PDA seed for a spending limit.
+pub fn spending_limit_pda_seed(create_key: &[u8; 32], limit_key: &[u8; 32]) -> PdaSeed {
+    PdaSeed::new(spending_limit_pda_seed_bytes(create_key, limit_key))
+}
+
+/// Compute the on-chain AccountId (PDA) for a spending limit.
+pub fn compute_spending_limit_pda(program_id: &ProgramId, create_key: &[u8; 32], limit_key: &[u8; 32]) -> AccountId {
+    AccountId::from((program_id, &spending_limit_pda_seed(create_key, limit_key)))
+}
+
+/// Raw seed bytes for a member's voter record PDA.
+pub fn voter_record_pda_seed_bytes(create_key: &[u8; 32], member: &[u8; 32]) -> [u8; 32] {
+    hash_seed_segments(&[VOTER_RECORD_TAG, create_key, member])
+}
+
+/// Compute PDA seed for a member's voter record.
+pub fn voter_record_pda_seed(create_key: &[u8; 32], member: &[u8; 32]) -> PdaSeed {
+    PdaSeed::new(voter_record_pda_seed_bytes(create_key, member))
+}
+
+/// Compute the on-chain AccountId (PDA) for a member's voter record.
+pub fn compute_voter_record_pda(program_id: &ProgramId, create_key: &[u8; 32], member: &[u8; 32]) -> AccountId {
+    AccountId::from((program_id, &voter_record_pda_seed(create_key, member)))
+}
+
+/// Check whether `seed` is a PDA seed owned by the multisig identified by `create_key`.
+/// Only the multisig's own vault seeds may be attached to a proposal's ChainedCall —
+/// any other seed would let this multisig's members authorize a PDA they don't govern
+/// (e.g. another multisig's vault under the same program).
+pub fn is_multisig_pda_seed(create_key: &[u8; 32], seed: &[u8; 32]) -> bool {
+    vault_index_of_seed(create_key, seed).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!("../../docs/pda-test-vectors.json");
+
+    fn hex32(s: &str) -> [u8; 32] {
+        let bytes: Vec<u8> = (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_published_vectors() {
+        let doc: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+        let vectors = doc["vectors"].as_array().unwrap();
+        assert!(!vectors.is_empty());
+
+        for v in vectors {
+            let program_id: ProgramId = serde_json::from_value(v["program_id"].clone()).unwrap();
+            let create_key = hex32(v["create_key"].as_str().unwrap());
+            let index = v["proposal_index"].as_u64().unwrap();
+
+            assert_eq!(multisig_state_pda_seed_bytes(&create_key), hex32(v["multisig_state_seed"].as_str().unwrap()));
+            assert_eq!(
+                *compute_multisig_state_pda(&program_id, &create_key).value(),
+                hex32(v["multisig_state_account_id"].as_str().unwrap())
+            );
+            assert_eq!(proposal_pda_seed_bytes(&create_key, index), hex32(v["proposal_seed"].as_str().unwrap()));
+            assert_eq!(
+                *compute_proposal_pda(&program_id, &create_key, index).value(),
+                hex32(v["proposal_account_id"].as_str().unwrap())
+            );
+            assert_eq!(vault_pda_seed_bytes(&create_key, 0), hex32(v["vault_seed"].as_str().unwrap()));
+            assert_eq!(
+                *compute_vault_pda(&program_id, &create_key, 0).value(),
+                hex32(v["vault_account_id"].as_str().unwrap())
+            );
+            assert_eq!(vault_pda_seed_bytes(&create_key, 1), hex32(v["vault_1_seed"].as_str().unwrap()));
+            assert_eq!(
+                *compute_vault_pda(&program_id, &create_key, 1).value(),
+                hex32(v["vault_1_account_id"].as_str().unwrap())
+            );
+            let limit_key = hex32(v["limit_key"].as_str().unwrap());
+            assert_eq!(
+                spending_limit_pda_seed_bytes(&create_key, &limit_key),
+                hex32(v["spending_limit_seed"].as_str().unwrap())
+            );
+            assert_eq!(
+                *compute_spending_limit_pda(&program_id, &create_key, &limit_key).value(),
+                hex32(v["spending_limit_account_id"].as_str().unwrap())
+            );
+            let member = hex32(v["member"].as_str().unwrap());
+            assert_eq!(voter_record_pda_seed_bytes(&create_key, &member), hex32(v["voter_record_seed"].as_str().unwrap()));
+            assert_eq!(
+                *compute_voter_record_pda(&program_id, &create_key, &member).value(),
+                hex32(v["voter_record_account_id"].as_str().unwrap())
+            );
+            assert_eq!(
+                compute_pda(&program_id, &[PROPOSAL_TAG, &create_key, &index.to_le_bytes()]),
+                compute_proposal_pda(&program_id, &create_key, index)
+            );
+        }
+    }
+
+    #[test]
+    fn test_pdas_are_distinct_per_kind_and_index() {
+        let program_id: ProgramId = [7u32; 8];
+        let create_key = [9u8; 32];
+        let state = compute_multisig_state_pda(&program_id, &create_key);
+        let vault = compute_vault_pda(&program_id, &create_key, 0);
+        let p1 = compute_proposal_pda(&program_id, &create_key, 1);
+        let p2 = compute_proposal_pda(&program_id, &create_key, 2);
+        let limit = compute_spending_limit_pda(&program_id, &create_key, &[1u8; 32]);
+        let voter = compute_voter_record_pda(&program_id, &create_key, &[1u8; 32]);
+
+        assert_ne!(state, vault);
+        assert_ne!(state, p1);
+        assert_ne!(p1, p2);
+        assert_ne!(p1, vault);
+        assert_ne!(limit, vault);
+        assert_ne!(vault, compute_vault_pda(&program_id, &create_key, 1));
+        assert_ne!(compute_vault_pda(&program_id, &create_key, 1), compute_vault_pda(&program_id, &create_key, 2));
+        assert_ne!(limit, compute_spending_limit_pda(&program_id, &create_key, &[2u8; 32]));
+        assert_ne!(voter, limit);
+        assert_ne!(voter, compute_voter_record_pda(&program_id, &create_key, &[2u8; 32]));
+    }
+
+    #[test]
+    fn test_only_own_vault_seed_is_multisig_seed() {
+        let create_key = [9u8; 32];
+        assert!(is_multisig_pda_seed(&create_key, &vault_pda_seed_bytes(&create_key, 0)));
+        assert!(is_multisig_pda_seed(&create_key, &vault_pda_seed_bytes(&create_key, MAX_VAULTS - 1)));
+        assert!(!is_multisig_pda_seed(&create_key, &vault_pda_seed_bytes(&create_key, MAX_VAULTS)));
+        assert!(!is_multisig_pda_seed(&create_key, &vault_pda_seed_bytes(&[8u8; 32], 0)));
+        assert!(!is_multisig_pda_seed(&create_key, &multisig_state_pda_seed_bytes(&create_key)));
+        assert!(!is_multisig_pda_seed(&create_key, &proposal_pda_seed_bytes(&create_key, 1)));
+        assert!(!is_multisig_pda_seed(&create_key, &spending_limit_pda_seed_bytes(&create_key, &[1u8; 32])));
+        assert!(!is_multisig_pda_seed(&create_key, &voter_record_pda_seed_bytes(&create_key, &[1u8; 32])));
+    }
+
+    #[test]
+    fn test_vault_index_of_seed() {
+        let create_key = [9u8; 32];
+        assert_eq!(vault_index_of_seed(&create_key, &vault_pda_seed_bytes(&create_key, 0)), Some(0));
+        assert_eq!(vault_index_of_seed(&create_key, &vault_pda_seed_bytes(&create_key, 3)), Some(3));
+        assert_eq!(vault_index_of_seed(&create_key, &vault_pda_seed_bytes(&[8u8; 32], 3)), None);
+    }
+}
@@ -0,0 +1,238 @@
+// Off-chain member signatures.
+//
+// Instead of sending its own Approve transaction, a member can sign a
+// proposal's approval digest off-chain; a relayer then submits any number of
+// those signatures in one ApproveWithSignatures transaction, and the guest
+// verifies each of them against the multisig's members.
+//
+// Keys and signatures are those of the LEZ wallet: BIP-340 Schnorr over
+// secp256k1, with a public account's ID derived from its 32-byte public key
+// exactly as nssa's `AccountId::from(&PublicKey)` does.
+//
+//   digest = SHA-256(pad32("multisig_approve") || program_id (u32 words LE) || create_key
+//                    || pad32(index u64 LE) || SHA-256(borsh((calls, config_actions, expires_at)))
+//                    || member || pad32(vote_nonce u32 LE))
+//
+// The digest commits to the program the multisig lives under, to what the
+// proposal does and until when, and to the signing member's vote nonce on the
+// proposal. Rejecting, abstaining or withdrawing bumps that nonce, so an
+// approval signed before the member changed their vote can't be replayed.
+//
+// A member with a voter record signs whole instructions the same way:
+//
+//   digest = SHA-256(pad32("multisig_voter_i") || program_id (u32 words LE) || create_key || member
+//                    || pad32(nonce u64 LE) || SHA-256(instruction words as u32 LE))
+//
+// The record's nonce goes up on every call, so a signed instruction runs once,
+// and only under the program that holds the record.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use k256::schnorr::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use nssa_core::program::ProgramId;
+
+use crate::pda::hash_seed_segments;
+use crate::Proposal;
+
+/// Tag segment for approval digests (16 bytes).
+pub const APPROVAL_TAG: &[u8; 16] = b"multisig_approve";
+
+/// Tag segment for voter call digests (16 bytes).
+pub const VOTER_CALL_TAG: &[u8; 16] = b"multisig_voter_i";
+
+/// Prefix nssa hashes with a public key to derive its account ID.
+const PUBLIC_ACCOUNT_ID_PREFIX: &[u8; 32] = b"/NSSA/v0.2/AccountId/Public/\x00\x00\x00\x00";
+
+/// One member's off-chain signature: an approval, or an ActAsVoter call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct MemberSignature {
+    /// The member's 32-byte (x-only) public key
+    pub public_key: [u8; 32],
+    /// 64-byte BIP-340 Schnorr signature over the approval or voter call digest
+    pub signature: Vec<u8>,
+}
+
+/// The digest `member` signs to approve `proposal`, held by the multisig
+/// program `program_id`, off-chain, at their current vote nonce.
+pub fn approval_digest(program_id: &ProgramId, proposal: &Proposal, member: &[u8; 32]) -> [u8; 32] {
+    let content = borsh::to_vec(&(proposal.calls(), proposal.config_actions(), proposal.expires_at)).unwrap();
+    let content_hash: [u8; 32] = Sha256::digest(&content).into();
+    hash_seed_segments(&[
+        APPROVAL_TAG,
+        &program_id_bytes(program_id),
+        &proposal.multisig_create_key,
+        &proposal.index.to_le_bytes(),
+        &content_hash,
+        member,
+        &proposal.vote_nonce(member).to_le_bytes(),
+    ])
+}
+
+/// The digest a member signs to run `instruction` through their voter record,
+/// held by the multisig program `program_id`, while its nonce is `nonce`.
+pub fn voter_call_digest(
+    program_id: &ProgramId,
+    create_key: &[u8; 32],
+    member: &[u8; 32],
+    nonce: u64,
+    instruction: &[u32],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for word in instruction {
+        hasher.update(word.to_le_bytes());
+    }
+    let instruction_hash: [u8; 32] = hasher.finalize().into();
+    hash_seed_segments(&[
+        VOTER_CALL_TAG,
+        &program_id_bytes(program_id),
+        create_key,
+        member,
+        &nonce.to_le_bytes(),
+        &instruction_hash,
+    ])
+}
+
+/// The program ID as a 32-byte segment, each word little-endian.
+fn program_id_bytes(program_id: &ProgramId) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(program_id) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// Account ID of the public account controlled by `public_key`.
+pub fn account_id_from_public_key(public_key: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(PUBLIC_ACCOUNT_ID_PREFIX);
+    hasher.update(public_key);
+    hasher.finalize().into()
+}
+
+/// Whether `signature` is `public_key`'s signature over `digest`.
+pub fn verify_approval(public_key: &[u8; 32], signature: &[u8], digest: &[u8; 32]) -> bool {
+    let Ok(key) = VerifyingKey::from_bytes(public_key) else {
+        return false;
+    };
+    let Ok(signature) = Signature::try_from(signature) else {
+        return false;
+    };
+    key.verify_raw(digest, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigAction;
+    use k256::schnorr::SigningKey;
+
+    const PROGRAM_ID: ProgramId = [42u32; 8];
+
+    fn make_proposal() -> Proposal {
+        Proposal::new(1, [1u8; 32], [9u8; 32], [42u32; 8], vec![0u32], vec![[30u8; 32]], vec![], vec![0])
+    }
+
+    fn sign(secret: u8, digest: &[u8; 32]) -> ([u8; 32], Vec<u8>) {
+        let key = SigningKey::from_bytes(&[secret; 32]).unwrap();
+        let signature = key.sign_raw(digest, &[0u8; 32]).unwrap();
+        (key.verifying_key().to_bytes().into(), signature.to_bytes().to_vec())
+    }
+
+    const MEMBER: [u8; 32] = [2u8; 32];
+
+    #[test]
+    fn test_signature_verifies_only_for_its_digest() {
+        let digest = approval_digest(&PROGRAM_ID, &make_proposal(), &MEMBER);
+        let (public_key, signature) = sign(5, &digest);
+
+        assert!(verify_approval(&public_key, &signature, &digest));
+        assert!(!verify_approval(&public_key, &signature, &[0u8; 32]));
+        assert!(!verify_approval(&sign(6, &digest).0, &signature, &digest));
+        assert!(!verify_approval(&public_key, &signature[..63], &digest));
+    }
+
+    #[test]
+    fn test_digest_ignores_approvals_but_not_content() {
+        let proposal = make_proposal();
+        let digest = approval_digest(&PROGRAM_ID, &proposal, &MEMBER);
+
+        let mut voted = proposal.clone();
+        voted.approve([3u8; 32]);
+        voted.reject([4u8; 32]);
+        voted.approve(MEMBER);
+        assert_eq!(approval_digest(&PROGRAM_ID, &voted, &MEMBER), digest);
+
+        let mut retargeted = proposal.clone();
+        retargeted.target_accounts = vec![[31u8; 32]];
+        assert_ne!(approval_digest(&PROGRAM_ID, &retargeted, &MEMBER), digest);
+
+        let mut reindexed = proposal.clone();
+        reindexed.index = 2;
+        assert_ne!(approval_digest(&PROGRAM_ID, &reindexed, &MEMBER), digest);
+
+        let config = Proposal::new_config(1, [1u8; 32], [9u8; 32], ConfigAction::ChangeThreshold { new_threshold: 2 });
+        assert_ne!(approval_digest(&PROGRAM_ID, &config, &MEMBER), digest);
+    }
+
+    #[test]
+    fn test_digest_binds_member_and_vote_nonce() {
+        let proposal = make_proposal();
+        let digest = approval_digest(&PROGRAM_ID, &proposal, &MEMBER);
+
+        assert_ne!(approval_digest(&PROGRAM_ID, &proposal, &[3u8; 32]), digest);
+
+        let mut rejected = proposal.clone();
+        rejected.reject(MEMBER);
+        assert_ne!(approval_digest(&PROGRAM_ID, &rejected, &MEMBER), digest);
+
+        let mut abstained = proposal.clone();
+        abstained.abstain(MEMBER);
+        assert_ne!(approval_digest(&PROGRAM_ID, &abstained, &MEMBER), digest);
+
+        let mut withdrawn = proposal.clone();
+        withdrawn.approve(MEMBER);
+        withdrawn.withdraw_vote(MEMBER);
+        assert_ne!(approval_digest(&PROGRAM_ID, &withdrawn, &MEMBER), digest);
+        // Approving again doesn't bring the old digest back
+        withdrawn.approve(MEMBER);
+        assert_ne!(approval_digest(&PROGRAM_ID, &withdrawn, &MEMBER), digest);
+    }
+
+    #[test]
+    fn test_digest_binds_program_and_expiry() {
+        let proposal = make_proposal();
+        let digest = approval_digest(&PROGRAM_ID, &proposal, &MEMBER);
+
+        assert_ne!(approval_digest(&[43u32; 8], &proposal, &MEMBER), digest);
+
+        let mut expiring = proposal.clone();
+        expiring.expires_at = Some(100);
+        assert_ne!(approval_digest(&PROGRAM_ID, &expiring, &MEMBER), digest);
+
+        let mut later = expiring.clone();
+        later.expires_at = Some(101);
+        assert_ne!(
+            approval_digest(&PROGRAM_ID, &later, &MEMBER),
+            approval_digest(&PROGRAM_ID, &expiring, &MEMBER)
+        );
+    }
+
+    #[test]
+    fn test_voter_call_digest_binds_program_nonce_and_instruction() {
+        let digest = voter_call_digest(&PROGRAM_ID, &[9u8; 32], &[1u8; 32], 0, &[1, 2, 3]);
+
+        assert_ne!(voter_call_digest(&PROGRAM_ID, &[9u8; 32], &[1u8; 32], 1, &[1, 2, 3]), digest);
+        assert_ne!(voter_call_digest(&PROGRAM_ID, &[9u8; 32], &[1u8; 32], 0, &[1, 2, 4]), digest);
+        assert_ne!(voter_call_digest(&PROGRAM_ID, &[9u8; 32], &[2u8; 32], 0, &[1, 2, 3]), digest);
+        assert_ne!(voter_call_digest(&PROGRAM_ID, &[8u8; 32], &[1u8; 32], 0, &[1, 2, 3]), digest);
+        assert_ne!(voter_call_digest(&[43u32; 8], &[9u8; 32], &[1u8; 32], 0, &[1, 2, 3]), digest);
+    }
+
+    #[test]
+    fn test_account_id_depends_on_public_key() {
+        assert_ne!(account_id_from_public_key(&[1u8; 32]), account_id_from_public_key(&[2u8; 32]));
+        assert_ne!(account_id_from_public_key(&[1u8; 32]), [1u8; 32]);
+    }
+}
@@ -0,0 +1,190 @@
+// Abstain handler — a member with Vote permission records an explicit
+// abstention. It replaces the member's earlier approval or rejection and never
+// counts toward threshold. It never rejects the proposal either: the member
+// can still withdraw it and approve.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership/threshold)
+// - accounts[1]: voter account (must be authorized = is a signer)
+// - accounts[2]: proposal PDA account (owned by multisig program)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigError, Proposal, ProposalStatus, PERMISSION_VOTE};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let voter_account = &accounts[1];
+    let proposal_account = &accounts[2];
+
+    if !voter_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
+
+    let (state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    let voter_id = *voter_account.account_id.value();
+    if !state.is_member(&voter_id) {
+        return Err(MultisigError::NotAMember);
+    }
+    state.require_permission(&voter_id, PERMISSION_VOTE)?;
+
+    crate::accounts::check_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
+    let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
+    let mut proposal: Proposal = borsh::from_slice(&proposal_data)
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive);
+    }
+
+    if !proposal.abstain(voter_id) {
+        return Err(MultisigError::AlreadyAbstained);
+    }
+
+    // A switched approval can drop the proposal back below threshold
+    if !proposal.has_threshold(&state) {
+        proposal.threshold_reached_at = None;
+    }
+
+    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let mut proposal_post = proposal_account.account.clone();
+    proposal_post.data = proposal_bytes.try_into().unwrap();
+
+    let multisig_post = multisig_account.account.clone();
+    let voter_post = voter_account.account.clone();
+
+    Ok((
+        vec![
+            AccountPostState::new(multisig_post),
+            AccountPostState::new(voter_post),
+            AccountPostState::new(proposal_post),
+        ],
+        vec![],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::{Account, AccountId};
+    use nssa_core::program::ProgramId;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda, MultisigState};
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
+    fn make_proposal() -> Proposal {
+        let fake_program_id: ProgramId = [42u32; 8];
+        Proposal::new(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            fake_program_id,
+            vec![0u32],
+            vec![[30u8; 32]],
+            vec![],
+            vec![],
+        )
+    }
+
+    fn abstain_with(signer: [u8; 32], threshold: u8, proposal: &Proposal) -> Result<Proposal, MultisigError> {
+        let mut state = MultisigState::new([0u8; 32], threshold, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&signer, vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(proposal).unwrap(), false),
+        ];
+        let (post_states, _) = handle(&accounts, 1)?;
+        Ok(borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap())
+    }
+
+    #[test]
+    fn test_abstain_replaces_approval() {
+        let mut proposal = make_proposal();
+        proposal.approve([2u8; 32]);
+
+        let proposal = abstain_with([2u8; 32], 2, &proposal).unwrap();
+
+        assert_eq!(proposal.approved, vec![[1u8; 32]]);
+        assert_eq!(proposal.abstained, vec![[2u8; 32]]);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+    }
+
+    #[test]
+    fn test_abstention_leaves_proposal_active() {
+        // 3-of-3: an abstention leaves only 2 possible approvals until it is withdrawn
+        let proposal = abstain_with([2u8; 32], 3, &make_proposal()).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+    }
+
+    #[test]
+    fn test_withdrawn_abstention_can_still_reach_threshold() {
+        let mut state = MultisigState::new([0u8; 32], 3, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        let mut proposal = make_proposal();
+        proposal.approve([3u8; 32]);
+        let proposal = abstain_with([2u8; 32], 3, &proposal).unwrap();
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+        ];
+        let (post_states, _) = crate::withdraw_vote::handle(&accounts, 1).unwrap();
+        let mut proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert!(proposal.abstained.is_empty());
+        assert!(proposal.approve([2u8; 32]));
+        assert!(proposal.has_threshold(&state));
+    }
+
+    #[test]
+    fn test_duplicate_abstain_fails() {
+        let proposal = abstain_with([2u8; 32], 2, &make_proposal()).unwrap();
+
+        let err = abstain_with([2u8; 32], 2, &proposal).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyAbstained);
+    }
+
+    #[test]
+    fn test_approve_after_abstain_clears_abstention() {
+        let mut proposal = abstain_with([2u8; 32], 2, &make_proposal()).unwrap();
+
+        assert!(proposal.approve([2u8; 32]));
+        assert!(proposal.abstained.is_empty());
+    }
+
+    #[test]
+    fn test_abstain_non_member_fails() {
+        let err = abstain_with([99u8; 32], 2, &make_proposal()).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+}
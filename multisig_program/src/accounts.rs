@@ -0,0 +1,58 @@
+// Account checks shared by every handler.
+//
+// The #[lez_program] annotations (`pda = ...`, `signer`) describe each
+// instruction's accounts, but the guest runs `process()`, so handlers check
+// them again themselves.
+//
+// LEZ gives a program no way to learn its own ID, so the multisig state
+// account's owner stands in for it: the state must sit at the state PDA of its
+// create_key under that owner, and the proposal, spending limit, voter record
+// and member accounts a handler uses must have that same owner. Every handler
+// writes one of those accounts, and LEZ only lets a program write accounts it
+// owns, so a state account forged by another program never gets that far.
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::ProgramId;
+use multisig_core::{compute_multisig_state_pda, compute_proposal_pda, MultisigError, MultisigState};
+
+/// Read the multisig state from `account`, which must be the state PDA of its
+/// create_key under its owner. Returns the state and that owner.
+pub fn load_state(account: &AccountWithMetadata) -> Result<(MultisigState, ProgramId), MultisigError> {
+    let data: Vec<u8> = account.account.data.clone().into();
+    let state: MultisigState = borsh::from_slice(&data)
+        .map_err(|_| MultisigError::InvalidMultisigState)?;
+    let program_id = account.account.program_owner;
+    if account.account_id != compute_multisig_state_pda(&program_id, &state.create_key) {
+        return Err(MultisigError::MultisigStateMismatch);
+    }
+    Ok((state, program_id))
+}
+
+/// Check that `account` is the multisig's proposal PDA for `index`, owned by the program.
+pub fn check_proposal(
+    account: &AccountWithMetadata,
+    program_id: &ProgramId,
+    create_key: &[u8; 32],
+    index: u64,
+) -> Result<(), MultisigError> {
+    if account.account.program_owner != *program_id
+        || account.account_id != compute_proposal_pda(program_id, create_key, index)
+    {
+        return Err(MultisigError::ProposalAccountMismatch);
+    }
+    Ok(())
+}
+
+/// Check that `account` is the multisig's proposal PDA for `index`, for a
+/// proposal being created (it has no owner until it is claimed).
+pub fn check_new_proposal(
+    account: &AccountWithMetadata,
+    program_id: &ProgramId,
+    create_key: &[u8; 32],
+    index: u64,
+) -> Result<(), MultisigError> {
+    if account.account_id != compute_proposal_pda(program_id, create_key, index) {
+        return Err(MultisigError::ProposalAccountMismatch);
+    }
+    Ok(())
+}
@@ -1,62 +1,89 @@
-// Approve handler — any member approves an existing proposal
+// Approve handler — a member with Vote permission approves an existing proposal
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership)
 // - accounts[1]: approver account (must be authorized = is a signer)
 // - accounts[2]: proposal PDA account (owned by multisig program)
+// - accounts[3]: clock account (when the proposal expires, or the multisig is
+//   time-locked and this approval reaches threshold)
 
 use nssa_core::account::AccountWithMetadata;
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{MultisigState, Proposal, ProposalStatus};
+use multisig_core::{MultisigError, Proposal, ProposalStatus, PERMISSION_VOTE};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
-    _proposal_index: u64,
-) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
-    assert!(accounts.len() >= 3, "Approve requires multisig_state + approver + proposal accounts");
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
 
     let multisig_account = &accounts[0];
     let approver_account = &accounts[1];
     let proposal_account = &accounts[2];
 
-    assert!(approver_account.is_authorized, "Approver must sign the transaction");
+    if !approver_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
 
     // Read multisig state for membership check
-    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let state: MultisigState = borsh::from_slice(&state_data)
-        .expect("Failed to deserialize multisig state");
+    let (mut state, program_id) = crate::accounts::load_state(multisig_account)?;
 
     let approver_id = *approver_account.account_id.value();
-    assert!(state.is_member(&approver_id), "Approver is not a multisig member");
+    if !state.is_member(&approver_id) {
+        return Err(MultisigError::NotAMember);
+    }
+    state.require_permission(&approver_id, PERMISSION_VOTE)?;
 
     // Read and update proposal
+    crate::accounts::check_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
     let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
     let mut proposal: Proposal = borsh::from_slice(&proposal_data)
-        .expect("Failed to deserialize proposal");
+        .map_err(|_| MultisigError::InvalidProposal)?;
 
-    assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
-    assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive);
+    }
+    if state.is_stale(proposal.index) {
+        return Err(MultisigError::StaleProposal);
+    }
+
+    let clock_account = accounts.get(3);
+    if crate::clock::is_expired(&mut state, &proposal, clock_account)? {
+        return Err(MultisigError::ProposalExpired);
+    }
 
     let is_new = proposal.approve(approver_id);
-    assert!(is_new, "Member has already approved this proposal");
+    if !is_new {
+        return Err(MultisigError::AlreadyApproved);
+    }
+
+    crate::clock::record_threshold_reached(&mut state, &mut proposal, clock_account)?;
 
     // Write back proposal
     let proposal_bytes = borsh::to_vec(&proposal).unwrap();
     let mut proposal_post = proposal_account.account.clone();
     proposal_post.data = proposal_bytes.try_into().unwrap();
 
-    // Multisig state unchanged, but must return post_state for every pre_state
-    let multisig_post = multisig_account.account.clone();
+    // The state records the clock time read, if any
+    let mut multisig_post = multisig_account.account.clone();
+    multisig_post.data = borsh::to_vec(&state).unwrap().try_into().unwrap();
     let approver_post = approver_account.account.clone();
 
-    (
-        vec![
-            AccountPostState::new(multisig_post),
-            AccountPostState::new(approver_post),
-            AccountPostState::new(proposal_post),
-        ],
-        vec![],
-    )
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(approver_post),
+        AccountPostState::new(proposal_post),
+    ];
+    if let Some(clock) = clock_account {
+        post_states.push(AccountPostState::new(clock.account.clone()));
+    }
+
+    Ok((post_states, vec![]))
 }
 
 #[cfg(test)]
@@ -65,6 +92,7 @@ mod tests {
     use nssa_core::account::{Account, AccountId};
     use nssa_core::program::ProgramId;
     use multisig_core::MultisigState;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
 
     fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
         let mut account = Account::default();
@@ -76,6 +104,14 @@ mod tests {
         }
     }
 
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
     fn make_multisig_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
         let mut state = MultisigState::new([0u8; 32], threshold, members);
         state.transaction_index = 1; // proposal exists
@@ -90,7 +126,7 @@ mod tests {
             [0u8; 32], // create_key matches multisig
             fake_program_id,
             vec![0u32],
-            1,
+            vec![[30u8; 32]],
             vec![],
             vec![],
         );
@@ -104,12 +140,12 @@ mod tests {
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[2u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, _) = handle(&accounts, 1);
+        let (post_states, _) = handle(&accounts, 1).unwrap();
 
         let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
         assert_eq!(proposal.approved.len(), 2);
@@ -118,18 +154,124 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "already approved")]
+    fn test_approve_records_threshold_time_with_time_lock() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+        state.time_lock = 100;
+        state.clock_account = [77u8; 32];
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
+            make_account(&[77u8; 32], 1_000u64.to_le_bytes().to_vec(), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(post_states.len(), 4);
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.threshold_reached_at, Some(1_000));
+    }
+
+    #[test]
+    fn test_approve_reaching_threshold_without_clock_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+        state.time_lock = 100;
+        state.clock_account = [77u8; 32];
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingClockAccount);
+    }
+
+    #[test]
+    fn test_approve_stale_proposal_fails() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let mut state = MultisigState::new([0u8; 32], 2, members);
+        state.transaction_index = 2;
+        state.invalidate_prior_proposals(); // config change after proposal 1
+        let proposal_data = make_proposal([1u8; 32]);
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::StaleProposal);
+    }
+
+    #[test]
     fn test_approve_duplicate_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_multisig_state(2, members);
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyApproved);
+    }
+
+    #[test]
+    fn test_approve_expired_proposal_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+        state.clock_account = [77u8; 32];
+        let mut proposal: Proposal = borsh::from_slice(&make_proposal([1u8; 32])).unwrap();
+        proposal.expires_at = Some(1_000);
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&[77u8; 32], 1_000u64.to_le_bytes().to_vec(), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalExpired);
+    }
+
+    #[test]
+    fn test_approve_expiring_proposal_without_clock_fails() {
+        let mut proposal: Proposal = borsh::from_slice(&make_proposal([1u8; 32])).unwrap();
+        proposal.expires_at = Some(1_000);
+
+        let accounts = vec![
+            make_account(&state_id(), make_multisig_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingClockAccount);
+    }
+
+    #[test]
+    fn test_approve_without_vote_permission_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        state.permissions = vec![7, 7, multisig_core::PERMISSION_INITIATE | multisig_core::PERMISSION_EXECUTE];
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[3u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
         ];
 
-        handle(&accounts, 1);
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingPermission);
     }
 }
@@ -0,0 +1,223 @@
+// ApproveAndExecute handler — approves a proposal and, when that approval
+// brings it to threshold, executes it in the same transaction.
+//
+// Runs approve::handle, then execute::handle on the approved proposal, so the
+// checks are exactly those of a separate Approve followed by Execute. Without
+// a threshold (or while a time-lock still has to run), it only approves. A
+// time-lock starts when threshold is first reached, so a time-locked proposal
+// executes here only on a later approval, once the lock has elapsed. An
+// approver without Execute permission also only approves; a member who can
+// execute then runs Execute.
+//
+// Expected accounts (the Execute layout):
+// - accounts[0]: multisig_state PDA (read threshold/membership)
+// - accounts[1]: approver (must be authorized signer, member with Vote permission;
+//   the proposal is executed only if it also has Execute permission)
+// - accounts[2]: proposal PDA account (owned by multisig program)
+// - accounts[3]: clock account (only when the multisig has a time-lock or the proposal expires)
+// - accounts[3..] / accounts[4..]: target accounts, validated against the
+//   proposal exactly as in Execute when it is executed; passed through unchanged otherwise
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigError, Proposal, PERMISSION_EXECUTE};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let (mut state, _) = crate::accounts::load_state(&accounts[0])?;
+    let proposal_data: Vec<u8> = accounts[2].account.data.clone().into();
+    let proposal: Proposal = borsh::from_slice(&proposal_data)
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    // Approve sees the clock (if Execute needs one) but not the targets
+    let approve_len = if crate::clock::required_for_execute(&state, &proposal) { 4 } else { 3 };
+    let approve_accounts = &accounts[..approve_len.min(accounts.len())];
+    let (approve_posts, _) = crate::approve::handle(approve_accounts, proposal_index)?;
+
+    let approved: Proposal = borsh::from_slice(&Vec::from(approve_posts[2].account().data.clone()))
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    let clock_account = if approve_len == 4 { accounts.get(3) } else { None };
+    let can_execute = state.has_permission(accounts[1].account_id.value(), PERMISSION_EXECUTE);
+    if !can_execute
+        || !approved.has_threshold(&state)
+        || !crate::clock::time_lock_elapsed(&mut state, &approved, clock_account)?
+    {
+        let mut post_states = approve_posts;
+        for target in &accounts[approve_accounts.len()..] {
+            post_states.push(AccountPostState::new(target.account.clone()));
+        }
+        return Ok((post_states, vec![]));
+    }
+
+    let mut execute_accounts = accounts.to_vec();
+    execute_accounts[0].account = approve_posts[0].account().clone();
+    execute_accounts[2].account = approve_posts[2].account().clone();
+    crate::execute::handle(&execute_accounts, proposal_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::{Account, AccountId};
+    use nssa_core::program::ProgramId;
+    use multisig_core::{MultisigState, ProposalStatus, PERMISSION_INITIATE, PERMISSION_VOTE};
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
+    fn make_proposal() -> Proposal {
+        let fake_program_id: ProgramId = [42u32; 8];
+        Proposal::new(
+            1,
+            [1u8; 32],
+            [0u8; 32],
+            fake_program_id,
+            vec![0u32],
+            vec![[30u8; 32]],
+            vec![],
+            vec![0],
+        )
+    }
+
+    fn make_accounts(state: &MultisigState, approver: [u8; 32], target: [u8; 32]) -> Vec<AccountWithMetadata> {
+        vec![
+            make_account(&state_id(), borsh::to_vec(state).unwrap(), false),
+            make_account(&approver, vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&make_proposal()).unwrap(), false),
+            make_account(&target, vec![], false),
+        ]
+    }
+
+    fn proposal_after(post_states: &[AccountPostState]) -> Proposal {
+        borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap()
+    }
+
+    fn members() -> Vec<[u8; 32]> {
+        vec![[1u8; 32], [2u8; 32], [3u8; 32]]
+    }
+
+    #[test]
+    fn test_threshold_approval_executes() {
+        let state = MultisigState::new([0u8; 32], 2, members());
+
+        let (post_states, chained) = handle(&make_accounts(&state, [2u8; 32], [30u8; 32]), 1).unwrap();
+
+        assert_eq!(chained.len(), 1);
+        assert!(chained[0].pre_states[0].is_authorized);
+        assert_eq!(post_states.len(), 4);
+        let proposal = proposal_after(&post_states);
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(proposal.approved, vec![[1u8; 32], [2u8; 32]]);
+    }
+
+    #[test]
+    fn test_below_threshold_only_approves() {
+        let state = MultisigState::new([0u8; 32], 3, members());
+
+        let (post_states, chained) = handle(&make_accounts(&state, [2u8; 32], [30u8; 32]), 1).unwrap();
+
+        assert!(chained.is_empty());
+        // Targets are passed through unchanged
+        assert_eq!(post_states.len(), 4);
+        let proposal = proposal_after(&post_states);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.approved.len(), 2);
+    }
+
+    #[test]
+    fn test_time_locked_multisig_only_approves() {
+        let mut state = MultisigState::new([0u8; 32], 2, members());
+        state.time_lock = 100;
+        state.clock_account = [77u8; 32];
+        let mut accounts = make_accounts(&state, [2u8; 32], [30u8; 32]);
+        accounts.insert(3, make_account(&[77u8; 32], 500u64.to_le_bytes().to_vec(), false));
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        let proposal = proposal_after(&post_states);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.threshold_reached_at, Some(500));
+    }
+
+    #[test]
+    fn test_time_locked_multisig_executes_after_lock() {
+        let mut state = MultisigState::new([0u8; 32], 2, members());
+        state.time_lock = 100;
+        state.clock_account = [77u8; 32];
+        // Threshold 2 was reached at 500 by members 1 and 3; member 2 approves after the lock
+        let mut proposal = make_proposal();
+        proposal.approve([3u8; 32]);
+        proposal.threshold_reached_at = Some(500);
+        let mut accounts = make_accounts(&state, [2u8; 32], [30u8; 32]);
+        accounts[2] = make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false);
+        accounts.insert(3, make_account(&[77u8; 32], 600u64.to_le_bytes().to_vec(), false));
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(chained.len(), 1);
+        assert_eq!(proposal_after(&post_states).status, ProposalStatus::Executed);
+
+        // One tick earlier, it only approves
+        accounts[3] = make_account(&[77u8; 32], 599u64.to_le_bytes().to_vec(), false);
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        let proposal = proposal_after(&post_states);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.approved.len(), 3);
+    }
+
+    #[test]
+    fn test_substituted_target_fails() {
+        let state = MultisigState::new([0u8; 32], 2, members());
+
+        let err = handle(&make_accounts(&state, [2u8; 32], [31u8; 32]), 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountMismatch);
+    }
+
+    #[test]
+    fn test_approver_without_execute_permission_only_approves() {
+        let mut state = MultisigState::new([0u8; 32], 2, members());
+        state.permissions = vec![7, PERMISSION_INITIATE | PERMISSION_VOTE, 7];
+
+        let (post_states, chained) = handle(&make_accounts(&state, [2u8; 32], [30u8; 32]), 1).unwrap();
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 4);
+        let proposal = proposal_after(&post_states);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert!(proposal.has_threshold(&state));
+    }
+
+    #[test]
+    fn test_duplicate_approval_fails() {
+        let state = MultisigState::new([0u8; 32], 2, members());
+
+        let err = handle(&make_accounts(&state, [1u8; 32], [30u8; 32]), 1).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyApproved);
+    }
+}
@@ -0,0 +1,318 @@
+// ApproveWithSignatures handler — records approvals members signed off-chain.
+//
+// Each member signs their approval digest for the proposal with their account
+// key; a relayer collects the signatures and submits them in one transaction. The
+// relayer only pays for and signs the transaction — every approval is checked
+// exactly as in Approve, against the member account derived from the
+// signature's public key. Signatures of members who already approved are
+// still verified but otherwise skipped, so a relayer can resubmit a bundle
+// after some of its members approved directly; the bundle fails only if it
+// adds no approval at all. The digest includes the member's vote nonce, which
+// their rejections, abstentions and withdrawals bump, so a signature collected
+// before the member changed their vote no longer verifies.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership)
+// - accounts[1]: relayer account (must be authorized = is a signer; need not be a member)
+// - accounts[2]: proposal PDA account (owned by multisig program)
+// - accounts[3]: clock account (when the proposal expires, or the multisig is
+//   time-locked and these approvals reach threshold)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{
+    account_id_from_public_key, approval_digest, verify_approval, MemberSignature, MultisigError,
+    Proposal, ProposalStatus, PERMISSION_VOTE,
+};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    proposal_index: u64,
+    signatures: &[MemberSignature],
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+    if signatures.is_empty() {
+        return Err(MultisigError::EmptySignatureBundle);
+    }
+
+    let multisig_account = &accounts[0];
+    let relayer_account = &accounts[1];
+    let proposal_account = &accounts[2];
+
+    if !relayer_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
+
+    let (mut state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    crate::accounts::check_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
+    let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
+    let mut proposal: Proposal = borsh::from_slice(&proposal_data)
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive);
+    }
+    if state.is_stale(proposal.index) {
+        return Err(MultisigError::StaleProposal);
+    }
+
+    let clock_account = accounts.get(3);
+    if crate::clock::is_expired(&mut state, &proposal, clock_account)? {
+        return Err(MultisigError::ProposalExpired);
+    }
+
+    let mut new_approvals = 0;
+    for member_signature in signatures {
+        let member_id = account_id_from_public_key(&member_signature.public_key);
+        if !state.is_member(&member_id) {
+            return Err(MultisigError::NotAMember);
+        }
+        state.require_permission(&member_id, PERMISSION_VOTE)?;
+        let digest = approval_digest(&program_id, &proposal, &member_id);
+        if !verify_approval(&member_signature.public_key, &member_signature.signature, &digest) {
+            return Err(MultisigError::InvalidApprovalSignature);
+        }
+        if proposal.approve(member_id) {
+            new_approvals += 1;
+        }
+    }
+    if new_approvals == 0 {
+        return Err(MultisigError::AlreadyApproved);
+    }
+
+    crate::clock::record_threshold_reached(&mut state, &mut proposal, clock_account)?;
+
+    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let mut proposal_post = proposal_account.account.clone();
+    proposal_post.data = proposal_bytes.try_into().unwrap();
+
+    // The state records the clock time read, if any
+    let mut multisig_post = multisig_account.account.clone();
+    multisig_post.data = borsh::to_vec(&state).unwrap().try_into().unwrap();
+    let relayer_post = relayer_account.account.clone();
+
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(relayer_post),
+        AccountPostState::new(proposal_post),
+    ];
+    if let Some(clock) = clock_account {
+        post_states.push(AccountPostState::new(clock.account.clone()));
+    }
+
+    Ok((post_states, vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::schnorr::SigningKey;
+    use nssa_core::account::{Account, AccountId};
+    use nssa_core::program::ProgramId;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda, MultisigState};
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
+    fn signing_key(secret: u8) -> SigningKey {
+        SigningKey::from_bytes(&[secret; 32]).unwrap()
+    }
+
+    fn member_of(secret: u8) -> [u8; 32] {
+        account_id_from_public_key(&signing_key(secret).verifying_key().to_bytes().into())
+    }
+
+    fn sign(secret: u8, proposal: &Proposal) -> MemberSignature {
+        sign_for(&[0u32; 8], secret, proposal)
+    }
+
+    fn sign_for(program_id: &ProgramId, secret: u8, proposal: &Proposal) -> MemberSignature {
+        let key = signing_key(secret);
+        let digest = approval_digest(program_id, proposal, &member_of(secret));
+        MemberSignature {
+            public_key: key.verifying_key().to_bytes().into(),
+            signature: key.sign_raw(&digest, &[0u8; 32]).unwrap().to_bytes().to_vec(),
+        }
+    }
+
+    fn make_state(threshold: u8) -> MultisigState {
+        MultisigState::new([0u8; 32], threshold, vec![member_of(1), member_of(2), member_of(3)])
+    }
+
+    fn make_proposal() -> Proposal {
+        let fake_program_id: ProgramId = [42u32; 8];
+        Proposal::new(
+            1,
+            member_of(1),
+            [0u8; 32],
+            fake_program_id,
+            vec![0u32],
+            vec![[30u8; 32]],
+            vec![],
+            vec![],
+        )
+    }
+
+    fn approve_with(
+        state: &MultisigState,
+        proposal: &Proposal,
+        signatures: &[MemberSignature],
+    ) -> Result<Proposal, MultisigError> {
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(state).unwrap(), false),
+            // The relayer is not a member
+            make_account(&[99u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(proposal).unwrap(), false),
+        ];
+        let (post_states, _) = handle(&accounts, 1, signatures)?;
+        Ok(borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap())
+    }
+
+    #[test]
+    fn test_signatures_add_approvals() {
+        let proposal = make_proposal();
+        let signatures = vec![sign(2, &proposal), sign(3, &proposal)];
+
+        let proposal = approve_with(&make_state(3), &proposal, &signatures).unwrap();
+
+        assert_eq!(proposal.approved, vec![member_of(1), member_of(2), member_of(3)]);
+        assert!(proposal.has_threshold(&make_state(3)));
+    }
+
+    #[test]
+    fn test_signature_over_other_proposal_fails() {
+        let proposal = make_proposal();
+        let mut other = make_proposal();
+        other.target_accounts = vec![[31u8; 32]];
+
+        let err = approve_with(&make_state(2), &proposal, &[sign(2, &other)]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+    }
+
+    #[test]
+    fn test_signature_for_other_program_fails() {
+        let proposal = make_proposal();
+
+        let err = approve_with(&make_state(2), &proposal, &[sign_for(&[1u32; 8], 2, &proposal)]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+    }
+
+    #[test]
+    fn test_forged_signature_fails() {
+        let proposal = make_proposal();
+        // Member 2's key, member 3's signature
+        let mut forged = sign(3, &proposal);
+        forged.public_key = sign(2, &proposal).public_key;
+
+        let err = approve_with(&make_state(2), &proposal, &[forged]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+    }
+
+    #[test]
+    fn test_non_member_signature_fails() {
+        let proposal = make_proposal();
+
+        let err = approve_with(&make_state(2), &proposal, &[sign(4, &proposal)]).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+
+    #[test]
+    fn test_existing_approvals_are_skipped() {
+        let proposal = make_proposal();
+        // Member 1 approved as proposer; member 2 appears twice
+        let signatures = vec![sign(1, &proposal), sign(2, &proposal), sign(2, &proposal)];
+
+        let proposal = approve_with(&make_state(3), &proposal, &signatures).unwrap();
+
+        assert_eq!(proposal.approved, vec![member_of(1), member_of(2)]);
+    }
+
+    #[test]
+    fn test_bundle_without_new_approvals_fails() {
+        let proposal = make_proposal();
+
+        let err = approve_with(&make_state(3), &proposal, &[sign(1, &proposal)]).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyApproved);
+    }
+
+    #[test]
+    fn test_existing_approval_with_bad_signature_fails() {
+        let proposal = make_proposal();
+        let mut other = make_proposal();
+        other.target_accounts = vec![[31u8; 32]];
+        let signatures = vec![sign(1, &other), sign(2, &proposal)];
+
+        let err = approve_with(&make_state(3), &proposal, &signatures).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+    }
+
+    #[test]
+    fn test_signature_replayed_after_reject_fails() {
+        let proposal = make_proposal();
+        let signature = sign(2, &proposal);
+        let mut proposal = approve_with(&make_state(3), &proposal, &[signature.clone()]).unwrap();
+        assert!(proposal.approved.contains(&member_of(2)));
+
+        proposal.reject(member_of(2));
+        let err = approve_with(&make_state(3), &proposal, &[signature]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+
+        // A fresh signature at the new vote nonce approves again
+        let proposal = approve_with(&make_state(3), &proposal, &[sign(2, &proposal)]).unwrap();
+        assert!(proposal.approved.contains(&member_of(2)));
+    }
+
+    #[test]
+    fn test_signature_replayed_after_abstain_or_withdraw_fails() {
+        let proposal = make_proposal();
+        let signature = sign(2, &proposal);
+        let approved = approve_with(&make_state(3), &proposal, &[signature.clone()]).unwrap();
+
+        let mut abstained = approved.clone();
+        abstained.abstain(member_of(2));
+        let err = approve_with(&make_state(3), &abstained, &[signature.clone()]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+
+        let mut withdrawn = approved;
+        withdrawn.withdraw_vote(member_of(2));
+        let err = approve_with(&make_state(3), &withdrawn, &[signature]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidApprovalSignature);
+    }
+
+    #[test]
+    fn test_empty_bundle_fails() {
+        let err = approve_with(&make_state(2), &make_proposal(), &[]).unwrap_err();
+        assert_eq!(err, MultisigError::EmptySignatureBundle);
+    }
+
+    #[test]
+    fn test_member_without_vote_permission_fails() {
+        let mut state = make_state(2);
+        state.permissions = vec![7, 5, 7];
+        let proposal = make_proposal();
+
+        let err = approve_with(&state, &proposal, &[sign(2, &proposal)]).unwrap_err();
+        assert_eq!(err, MultisigError::MissingPermission);
+    }
+}
@@ -0,0 +1,210 @@
+// Cancel handler — withdraw an Active proposal before it is executed
+//
+// The proposer cancels their own proposal immediately. Any other member adds a
+// cancel vote; once `threshold` members have voted, the proposal is cancelled.
+// This covers approved-but-not-executed proposals the multisig no longer wants.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership/threshold)
+// - accounts[1]: canceller account (must be authorized = is a signer)
+// - accounts[2]: proposal PDA account (owned by multisig program)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{MultisigError, Proposal, ProposalStatus};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let canceller_account = &accounts[1];
+    let proposal_account = &accounts[2];
+
+    if !canceller_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
+
+    let (state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    let canceller_id = *canceller_account.account_id.value();
+    if !state.is_member(&canceller_id) {
+        return Err(MultisigError::NotAMember);
+    }
+
+    crate::accounts::check_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
+    let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
+    let mut proposal: Proposal = borsh::from_slice(&proposal_data)
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive);
+    }
+
+    let is_new = proposal.cancel(canceller_id);
+    if !is_new {
+        return Err(MultisigError::AlreadyCancelled);
+    }
+
+    // Proposer withdraws unilaterally; everyone else needs M cancel votes
+    if canceller_id == proposal.proposer || proposal.has_cancel_threshold(&state) {
+        proposal.status = ProposalStatus::Cancelled;
+    }
+
+    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
+    let mut proposal_post = proposal_account.account.clone();
+    proposal_post.data = proposal_bytes.try_into().unwrap();
+
+    let multisig_post = multisig_account.account.clone();
+    let canceller_post = canceller_account.account.clone();
+
+    Ok((
+        vec![
+            AccountPostState::new(multisig_post),
+            AccountPostState::new(canceller_post),
+            AccountPostState::new(proposal_post),
+        ],
+        vec![],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::{Account, AccountId};
+    use nssa_core::program::ProgramId;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda, MultisigState};
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
+    fn make_multisig_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
+        let mut state = MultisigState::new([0u8; 32], threshold, members);
+        state.transaction_index = 1;
+        borsh::to_vec(&state).unwrap()
+    }
+
+    fn make_proposal(proposer: [u8; 32], approvals: Vec<[u8; 32]>) -> Proposal {
+        let fake_program_id: ProgramId = [42u32; 8];
+        let mut proposal = Proposal::new(
+            1,
+            proposer,
+            [0u8; 32],
+            fake_program_id,
+            vec![0u32],
+            vec![[30u8; 32]],
+            vec![],
+            vec![],
+        );
+        for a in approvals {
+            proposal.approve(a);
+        }
+        proposal
+    }
+
+    fn cancel_with(signer: [u8; 32], proposal: &Proposal) -> Result<Proposal, MultisigError> {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let accounts = vec![
+            make_account(&state_id(), make_multisig_state(2, members), false),
+            make_account(&signer, vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(proposal).unwrap(), false),
+        ];
+        let (post_states, _) = handle(&accounts, 1)?;
+        Ok(borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap())
+    }
+
+    #[test]
+    fn test_proposer_cancels_immediately() {
+        let proposal = make_proposal([1u8; 32], vec![]);
+
+        let proposal = cancel_with([1u8; 32], &proposal).unwrap();
+
+        assert_eq!(proposal.status, ProposalStatus::Cancelled);
+        assert_eq!(proposal.cancelled, vec![[1u8; 32]]);
+    }
+
+    #[test]
+    fn test_member_vote_below_threshold_stays_active() {
+        let proposal = make_proposal([1u8; 32], vec![]);
+
+        let proposal = cancel_with([2u8; 32], &proposal).unwrap();
+
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.cancelled, vec![[2u8; 32]]);
+    }
+
+    #[test]
+    fn test_threshold_votes_cancel_approved_proposal() {
+        // Fully approved (2-of-3) but not executed
+        let proposal = make_proposal([1u8; 32], vec![[2u8; 32]]);
+        let state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        assert!(proposal.has_threshold(&state));
+
+        let proposal = cancel_with([2u8; 32], &proposal).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        let proposal = cancel_with([3u8; 32], &proposal).unwrap();
+
+        assert_eq!(proposal.status, ProposalStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_duplicate_cancel_vote_fails() {
+        let proposal = make_proposal([1u8; 32], vec![]);
+        let proposal = cancel_with([2u8; 32], &proposal).unwrap();
+
+        let err = cancel_with([2u8; 32], &proposal).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyCancelled);
+    }
+
+    #[test]
+    fn test_cancel_inactive_proposal_fails() {
+        let mut proposal = make_proposal([1u8; 32], vec![[2u8; 32]]);
+        proposal.status = ProposalStatus::Executed;
+
+        let err = cancel_with([1u8; 32], &proposal).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalNotActive);
+    }
+
+    #[test]
+    fn test_cancel_non_member_fails() {
+        let proposal = make_proposal([1u8; 32], vec![]);
+
+        let err = cancel_with([99u8; 32], &proposal).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+
+    #[test]
+    fn test_cancel_without_signature_fails() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let accounts = vec![
+            make_account(&state_id(), make_multisig_state(2, members), false),
+            make_account(&[1u8; 32], vec![], false),
+            make_account(&proposal_id(1), borsh::to_vec(&make_proposal([1u8; 32], vec![])).unwrap(), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingSignature);
+    }
+}
@@ -0,0 +1,133 @@
+// Clock source for the execution time-lock and proposal expiry.
+//
+// LEZ passes programs only their accounts and instruction data — there is no
+// block height or timestamp in the program input. Both features therefore read
+// a clock account: its data starts with the current time as a u64 (LE). Which
+// account is trusted as the clock is fixed in `MultisigState.clock_account`
+// (chosen at CreateMultisig or by a SetTimeLock proposal), so a caller can't
+// substitute an account with a forged time.
+//
+// That is all the program can check: LEZ has no system clock account, so
+// whatever program writes the chosen account decides the time. Its owner can
+// shorten a time-lock, revive or expire proposals, and refill spending limits
+// early. Members must pick an account written by a program they trust to
+// track real time; CreateMultisig and SetTimeLock only refuse the multisig's
+// own state and member accounts, which hold no time.
+//
+// The state keeps the latest time read (`last_clock`) and refuses an earlier
+// one, so a clock that is rewound can't revive an expired proposal or restart
+// a spending limit's period. Each handler that reads the clock writes the state
+// back with it.
+//
+// While `time_lock == 0` and a proposal has no expiry, no clock account is read
+// or required.
+
+use nssa_core::account::AccountWithMetadata;
+use multisig_core::{MultisigError, MultisigState, Proposal};
+
+/// Check that the multisig's clock isn't its own state account, whose data
+/// starts with the create_key. Member accounts are refused by `validate_clock`;
+/// the state PDA's ID isn't known without the program's.
+pub fn check_not_state(state: &MultisigState, state_account: &AccountWithMetadata) -> Result<(), MultisigError> {
+    if (state.time_lock > 0 || state.default_expiry > 0) && state.clock_account == *state_account.account_id.value() {
+        return Err(MultisigError::InvalidClockConfig);
+    }
+    Ok(())
+}
+
+/// Read the current time from `clock`, which must be the multisig's clock
+/// account, and record it as the state's `last_clock`.
+pub fn now(state: &mut MultisigState, clock: Option<&AccountWithMetadata>) -> Result<u64, MultisigError> {
+    let clock = clock.ok_or(MultisigError::MissingClockAccount)?;
+    if *clock.account_id.value() != state.clock_account {
+        return Err(MultisigError::ClockAccountMismatch);
+    }
+    let data: Vec<u8> = clock.account.data.clone().into();
+    let bytes: [u8; 8] = data
+        .get(..8)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(MultisigError::InvalidClockAccount)?;
+    let time = u64::from_le_bytes(bytes);
+    if time < state.last_clock {
+        return Err(MultisigError::ClockWentBackwards);
+    }
+    state.last_clock = time;
+    Ok(time)
+}
+
+/// Record when the proposal first reached threshold, if the multisig is time-locked.
+/// The clock is only read at the moment threshold is reached.
+pub fn record_threshold_reached(
+    state: &mut MultisigState,
+    proposal: &mut Proposal,
+    clock: Option<&AccountWithMetadata>,
+) -> Result<(), MultisigError> {
+    if state.time_lock == 0
+        || proposal.threshold_reached_at.is_some()
+        || !proposal.has_threshold(state)
+    {
+        return Ok(());
+    }
+    proposal.threshold_reached_at = Some(now(state, clock)?);
+    Ok(())
+}
+
+/// Clock time at which a new proposal expires: `expiry` after now, or the
+/// multisig's `default_expiry` when `expiry` is 0. `None` if neither is set.
+pub fn expires_at(
+    state: &mut MultisigState,
+    expiry: u64,
+    clock: Option<&AccountWithMetadata>,
+) -> Result<Option<u64>, MultisigError> {
+    let lifetime = if expiry > 0 { expiry } else { state.default_expiry };
+    if lifetime == 0 {
+        return Ok(None);
+    }
+    Ok(Some(now(state, clock)?.saturating_add(lifetime)))
+}
+
+/// Whether the proposal's expiry has passed. Proposals without one never expire
+/// and don't need the clock.
+pub fn is_expired(
+    state: &mut MultisigState,
+    proposal: &Proposal,
+    clock: Option<&AccountWithMetadata>,
+) -> Result<bool, MultisigError> {
+    if proposal.expires_at.is_none() {
+        return Ok(false);
+    }
+    Ok(proposal.is_expired(now(state, clock)?))
+}
+
+/// Whether Execute must be passed the clock account for this proposal.
+pub fn required_for_execute(state: &MultisigState, proposal: &Proposal) -> bool {
+    state.time_lock > 0 || proposal.expires_at.is_some()
+}
+
+/// Whether the time-lock has elapsed since the proposal reached threshold.
+/// Always true without a time-lock, in which case the clock isn't read.
+pub fn time_lock_elapsed(
+    state: &mut MultisigState,
+    proposal: &Proposal,
+    clock: Option<&AccountWithMetadata>,
+) -> Result<bool, MultisigError> {
+    if state.time_lock == 0 {
+        return Ok(true);
+    }
+    let Some(reached_at) = proposal.threshold_reached_at else {
+        return Ok(false);
+    };
+    Ok(now(state, clock)? >= reached_at.saturating_add(state.time_lock))
+}
+
+/// Check that the time-lock has elapsed since the proposal reached threshold.
+pub fn check_time_lock(
+    state: &mut MultisigState,
+    proposal: &Proposal,
+    clock: Option<&AccountWithMetadata>,
+) -> Result<(), MultisigError> {
+    if !time_lock_elapsed(state, proposal, clock)? {
+        return Err(MultisigError::TimeLockNotElapsed);
+    }
+    Ok(())
+}
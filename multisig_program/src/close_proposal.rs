@@ -0,0 +1,282 @@
+// CloseProposal handler — clears finished proposal accounts.
+//
+// Executed/rejected/cancelled proposals are never read again, so their data
+// can be wiped. The account keeps its program_owner (LEZ never lets an owner
+// change), so a closed proposal PDA is not `Account::default()` and can never
+// be re-initialized by Propose; Approve/Execute on it fail to deserialize.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership)
+// - accounts[1]: closer (must be authorized signer, must be member)
+// - accounts[2]: proposal PDA account at `proposal_index` (must not be Active)
+// - accounts[3..]: optional older proposals to close in the same call
+//   (each must belong to this multisig, have index < proposal_index, not be Active)
+//
+// Stale proposals (created before the last config change) can never be
+// approved or executed, so they are closable even while still Active.
+
+use nssa_core::account::{Account, AccountWithMetadata};
+use nssa_core::program::{AccountPostState, ChainedCall, ProgramId};
+use multisig_core::{MultisigError, MultisigState, Proposal, ProposalStatus};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let closer_account = &accounts[1];
+    let proposal_account = &accounts[2];
+    let older_proposals = &accounts[3..];
+
+    if !closer_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
+
+    let (state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    let closer_id = *closer_account.account_id.value();
+    if !state.is_member(&closer_id) {
+        return Err(MultisigError::NotAMember);
+    }
+
+    let proposal = read_closable(proposal_account, &state, &program_id)?;
+    if proposal.index != proposal_index {
+        return Err(MultisigError::ProposalIndexMismatch);
+    }
+
+    let mut post_states = vec![
+        AccountPostState::new(multisig_account.account.clone()),
+        AccountPostState::new(closer_account.account.clone()),
+        AccountPostState::new(cleared(proposal_account)),
+    ];
+
+    for older in older_proposals {
+        let proposal = read_closable(older, &state, &program_id)?;
+        if proposal.index >= proposal_index {
+            return Err(MultisigError::ProposalIndexMismatch);
+        }
+        post_states.push(AccountPostState::new(cleared(older)));
+    }
+
+    Ok((post_states, vec![]))
+}
+
+/// Deserialize a proposal and check it belongs to this multisig, sits at the
+/// proposal PDA for its index and is finished (or stale).
+fn read_closable(
+    account: &AccountWithMetadata,
+    state: &MultisigState,
+    program_id: &ProgramId,
+) -> Result<Proposal, MultisigError> {
+    let data: Vec<u8> = account.account.data.clone().into();
+    let proposal: Proposal = borsh::from_slice(&data)
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    crate::accounts::check_proposal(account, program_id, &state.create_key, proposal.index)?;
+    if proposal.status == ProposalStatus::Active && !state.is_stale(proposal.index) {
+        return Err(MultisigError::CannotCloseActiveProposal);
+    }
+    Ok(proposal)
+}
+
+/// Copy of the account with its data wiped (owner and balance are kept).
+fn cleared(account: &AccountWithMetadata) -> Account {
+    let mut closed = account.account.clone();
+    closed.data = Vec::new().try_into().unwrap();
+    closed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::AccountId;
+    use nssa_core::program::ProgramId;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
+    fn make_state(members: Vec<[u8; 32]>) -> Vec<u8> {
+        let mut state = MultisigState::new([0u8; 32], 2, members);
+        state.transaction_index = 3;
+        borsh::to_vec(&state).unwrap()
+    }
+
+    fn make_proposal(index: u64, create_key: [u8; 32], status: ProposalStatus) -> Vec<u8> {
+        let fake_program_id: ProgramId = [42u32; 8];
+        let mut proposal = Proposal::new(
+            index,
+            [1u8; 32],
+            create_key,
+            fake_program_id,
+            vec![0u32],
+            vec![[30u8; 32]],
+            vec![],
+            vec![],
+        );
+        proposal.status = status;
+        borsh::to_vec(&proposal).unwrap()
+    }
+
+    fn members() -> Vec<[u8; 32]> {
+        vec![[1u8; 32], [2u8; 32], [3u8; 32]]
+    }
+
+    #[test]
+    fn test_close_executed_proposal_clears_data() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal(1, [0u8; 32], ProposalStatus::Executed), false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 3);
+        assert!(Vec::from(post_states[2].account().data.clone()).is_empty());
+        // Cleared proposal can no longer be read as a proposal
+        assert!(borsh::from_slice::<Proposal>(&Vec::from(post_states[2].account().data.clone())).is_err());
+    }
+
+    #[test]
+    fn test_close_with_older_proposals() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(3), make_proposal(3, [0u8; 32], ProposalStatus::Rejected), false),
+            make_account(&proposal_id(1), make_proposal(1, [0u8; 32], ProposalStatus::Executed), false),
+            make_account(&proposal_id(2), make_proposal(2, [0u8; 32], ProposalStatus::Cancelled), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 3).unwrap();
+
+        assert_eq!(post_states.len(), 5);
+        for post in &post_states[2..] {
+            assert!(Vec::from(post.account().data.clone()).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_close_active_proposal_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal(1, [0u8; 32], ProposalStatus::Active), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::CannotCloseActiveProposal);
+    }
+
+    #[test]
+    fn test_close_stale_active_proposal() {
+        let mut state = MultisigState::new([0u8; 32], 2, members());
+        state.transaction_index = 3;
+        state.invalidate_prior_proposals();
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(2), make_proposal(2, [0u8; 32], ProposalStatus::Active), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 2).unwrap();
+        assert!(Vec::from(post_states[2].account().data.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_close_older_active_proposal_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(2), make_proposal(2, [0u8; 32], ProposalStatus::Executed), false),
+            make_account(&proposal_id(1), make_proposal(1, [0u8; 32], ProposalStatus::Active), false),
+        ];
+
+        let err = handle(&accounts, 2).unwrap_err();
+        assert_eq!(err, MultisigError::CannotCloseActiveProposal);
+    }
+
+    #[test]
+    fn test_close_older_proposal_with_higher_index_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal(1, [0u8; 32], ProposalStatus::Executed), false),
+            make_account(&proposal_id(2), make_proposal(2, [0u8; 32], ProposalStatus::Executed), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalIndexMismatch);
+    }
+
+    #[test]
+    fn test_close_wrong_index_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(2), make_proposal(2, [0u8; 32], ProposalStatus::Executed), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalIndexMismatch);
+    }
+
+    #[test]
+    fn test_close_other_multisig_proposal_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal(1, [7u8; 32], ProposalStatus::Executed), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalMultisigMismatch);
+    }
+
+    #[test]
+    fn test_close_already_closed_proposal_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidProposal);
+    }
+
+    #[test]
+    fn test_close_non_member_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(members()), false),
+            make_account(&[99u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal(1, [0u8; 32], ProposalStatus::Executed), false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+}
@@ -2,66 +2,101 @@
 
 use nssa_core::account::{Account, AccountWithMetadata};
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::MultisigState;
+use multisig_core::{compute_multisig_state_pda, MultisigError, MultisigState};
 
 /// Handle CreateMultisig instruction
 /// 
 /// Expected accounts:
 /// - accounts[0]: multisig_state (PDA, uninitialized) — derived from (program_id, create_key)
-/// - accounts[1..N+1]: member accounts (must be Account::default() = uninitialized/fresh)
+/// - accounts[1..N+1]: member accounts (must be Account::default() = uninitialized/fresh,
+///   or released by ReleaseMemberAccount and signing), or none at all
 ///
-/// All member accounts are claimed by the multisig program during creation.
-/// This means members must use fresh keypairs dedicated to this multisig.
+/// Given member accounts, all of them are claimed by the multisig program during
+/// creation. This means members must use fresh keypairs dedicated to this multisig,
+/// or accounts an earlier multisig has released.
 /// After claiming, member accounts have program_owner = multisig_program_id,
 /// which allows them to be included in subsequent instructions without
 /// triggering LEZ validation rule 7.
 ///
+/// Without member accounts nothing is claimed; members then act with their
+/// ordinary wallet keys through voter records (CreateVoterRecord / ActAsVoter).
+///
 /// Authorization: anyone can create a new multisig (create_key makes PDA unique)
+///
+/// `weights` gives each member's vote weight (in `members` order); empty means
+/// one vote each. `threshold` is then the approval weight required.
+/// `permissions` gives each member's PERMISSION_* flags; empty means all.
+/// Members without PERMISSION_VOTE carry no weight.
 pub fn handle(
     accounts: &[AccountWithMetadata],
     create_key: &[u8; 32],
     threshold: u8,
     members: &[[u8; 32]],
-) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
+    time_lock: u64,
+    clock_account: &[u8; 32],
+    default_expiry: u64,
+    weights: &[u8],
+    permissions: &[u8],
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    let mut state = MultisigState::new(*create_key, threshold, members.to_vec());
+    state.weights = weights.to_vec();
+    state.permissions = permissions.to_vec();
+    state.time_lock = time_lock;
+    state.clock_account = *clock_account;
+    state.default_expiry = default_expiry;
+
     // Validate inputs
-    assert!(!members.is_empty(), "Multisig must have at least one member");
-    assert!(threshold >= 1, "Threshold must be at least 1");
-    assert!((threshold as usize) <= members.len(), "Threshold cannot exceed member count");
-    assert!(members.len() <= 10, "Maximum 10 members for PoC");
-
-    // We need multisig_state + all member accounts
-    assert!(
-        accounts.len() >= 1 + members.len(),
-        "CreateMultisig requires multisig_state + {} member accounts, got {}",
-        members.len(),
-        accounts.len()
-    );
+    if members.is_empty() {
+        return Err(MultisigError::NoMembers);
+    }
+    state.validate_members()?;
+    state.validate_weights()?;
+    state.validate_permissions()?;
+    state.validate_clock()?;
+    if threshold < 1 {
+        return Err(MultisigError::InvalidThreshold);
+    }
+    if threshold as u32 > state.total_weight() {
+        return Err(MultisigError::ThresholdExceedsMemberCount);
+    }
+    if members.len() > 10 {
+        return Err(MultisigError::TooManyMembers);
+    }
+
+    // We need multisig_state + all member accounts, or multisig_state alone
+    let claim_members = accounts.len() > 1;
+    if accounts.is_empty() || (claim_members && accounts.len() < 1 + members.len()) {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+    let member_accounts = if claim_members { &accounts[1..1 + members.len()] } else { &[] };
 
     // Verify multisig state account is uninitialized
-    assert!(
-        accounts[0].account == Account::default(),
-        "Multisig state account must be uninitialized"
-    );
-
-    // Verify each member account is uninitialized and matches the member list
-    for (i, member_id) in members.iter().enumerate() {
-        let member_account = &accounts[1 + i];
-        assert!(
-            member_account.account == Account::default(),
-            "Member account {} must be uninitialized (fresh keypair required)",
-            i
-        );
-        assert_eq!(
-            member_account.account_id.value(),
-            member_id,
-            "Member account {} ID does not match member list",
-            i
-        );
+    if accounts[0].account != Account::default() {
+        return Err(MultisigError::AccountAlreadyInitialized);
+    }
+    crate::clock::check_not_state(&state, &accounts[0])?;
+
+    // Each member account must match the member list, and be fresh (a new
+    // keypair) or released by an earlier multisig of this program
+    let mut member_posts = Vec::new();
+    for (member_account, member_id) in member_accounts.iter().zip(members) {
+        if member_account.account_id.value() != member_id {
+            return Err(MultisigError::MemberAccountMismatch);
+        }
+        // The new state account has no owner yet, so a released account's owner
+        // stands in for this program: it is this program exactly when the
+        // state PDA derives from it
+        let program_id = member_account.account.program_owner;
+        if member_account.account != Account::default()
+            && accounts[0].account_id != compute_multisig_state_pda(&program_id, create_key)
+        {
+            return Err(MultisigError::AccountAlreadyInitialized);
+        }
+        // Claim member account, recording the multisig it belongs to
+        member_posts.push(crate::member_account::claim(member_account, &program_id, create_key)?);
     }
 
     // Create multisig state
-    let state = MultisigState::new(*create_key, threshold, members.to_vec());
-    
     let mut multisig_account = Account::default();
     let state_bytes = borsh::to_vec(&state).unwrap();
     multisig_account.data = state_bytes.try_into().unwrap();
@@ -70,19 +105,16 @@ pub fn handle(
     // Claiming member accounts satisfies LEZ Rule 7: the executor (a member) must be
     // owned by the multisig program for Execute to work.
     let mut post_states = vec![AccountPostState::new_claimed(multisig_account)];
+    post_states.extend(member_posts);
     
-    for i in 0..members.len() {
-        // Claim member account (empty data, just establishing ownership)
-        post_states.push(AccountPostState::new_claimed(accounts[1 + i].account.clone()));
-    }
-    
-    (post_states, vec![])
+    Ok((post_states, vec![]))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use nssa_core::account::{Account, AccountId};
+    use multisig_core::MemberAccountRecord;
 
     fn make_account(id: &[u8; 32], authorized: bool) -> AccountWithMetadata {
         AccountWithMetadata {
@@ -102,7 +134,7 @@ mod tests {
             accounts.push(make_account(m, false));
         }
 
-        let (post_states, chained) = handle(&accounts, &create_key, 2, &members);
+        let (post_states, chained) = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap();
 
         assert!(chained.is_empty());
         // state + 3 member accounts
@@ -117,40 +149,106 @@ mod tests {
         assert_eq!(state.members, members);
         assert_eq!(state.create_key, create_key);
         assert_eq!(state.transaction_index, 0);
+
+        // Each member account is claimed with a record of this multisig
+        for post in &post_states[1..] {
+            assert!(post.requires_claim());
+            let record: MemberAccountRecord =
+                borsh::from_slice(&Vec::from(post.account().data.clone())).unwrap();
+            assert_eq!(record.multisig_create_key, create_key);
+        }
+    }
+
+    #[test]
+    fn test_create_multisig_with_time_lock_and_expiry() {
+        let create_key = [0u8; 32];
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let accounts = vec![
+            make_account(&[10u8; 32], false),
+            make_account(&[1u8; 32], false),
+            make_account(&[2u8; 32], false),
+        ];
+
+        let (post_states, _) = handle(&accounts, &create_key, 2, &members, 3600, &[77u8; 32], 86_400, &[], &[]).unwrap();
+
+        let state: MultisigState = borsh::from_slice(&Vec::from(post_states[0].account().data.clone())).unwrap();
+        assert_eq!(state.time_lock, 3600);
+        assert_eq!(state.clock_account, [77u8; 32]);
+        assert_eq!(state.default_expiry, 86_400);
+    }
+
+    #[test]
+    fn test_create_multisig_time_lock_without_clock_fails() {
+        let create_key = [0u8; 32];
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let accounts = vec![
+            make_account(&[10u8; 32], false),
+            make_account(&[1u8; 32], false),
+            make_account(&[2u8; 32], false),
+        ];
+
+        let err = handle(&accounts, &create_key, 2, &members, 3600, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::ClockAccountNotSet);
+        let err = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 86_400, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::ClockAccountNotSet);
+    }
+
+    #[test]
+    fn test_create_multisig_clock_on_own_account_fails() {
+        let create_key = [0u8; 32];
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let accounts = vec![make_account(&[10u8; 32], false)];
+
+        // A member's account, the state account
+        for clock in [[2u8; 32], [10u8; 32]] {
+            let err = handle(&accounts, &create_key, 2, &members, 3600, &clock, 0, &[], &[]).unwrap_err();
+            assert_eq!(err, MultisigError::InvalidClockConfig);
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Threshold must be at least 1")]
     fn test_create_multisig_zero_threshold_fails() {
         let create_key = [1u8; 32];
         let members: Vec<[u8; 32]> = vec![[10u8; 32]];
         let mut accounts = vec![make_account(&[99u8; 32], false)];
         accounts.push(make_account(&[10u8; 32], false));
-        handle(&accounts, &create_key, 0, &members);
+        let err = handle(&accounts, &create_key, 0, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidThreshold);
+    }
+
+    #[test]
+    fn test_create_multisig_duplicate_member_fails() {
+        let create_key = [1u8; 32];
+        // Listed twice, [10] would weigh 2 and a 2-of-2 would need one key
+        let members: Vec<[u8; 32]> = vec![[10u8; 32], [10u8; 32]];
+        let accounts = vec![make_account(&[99u8; 32], false)];
+        let err = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::DuplicateMember);
+        let err = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[2, 1], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::DuplicateMember);
     }
 
     #[test]
-    #[should_panic(expected = "Threshold cannot exceed member count")]
     fn test_create_multisig_threshold_exceeds_members_fails() {
         let create_key = [1u8; 32];
         let members: Vec<[u8; 32]> = vec![[10u8; 32], [11u8; 32]];
         let mut accounts = vec![make_account(&[99u8; 32], false)];
         for m in &members { accounts.push(make_account(m, false)); }
-        handle(&accounts, &create_key, 3, &members);
+        let err = handle(&accounts, &create_key, 3, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
     }
 
     #[test]
-    #[should_panic(expected = "Maximum 10 members")]
     fn test_create_multisig_too_many_members_fails() {
         let create_key = [1u8; 32];
         let members: Vec<[u8; 32]> = (0u8..11).map(|i| [i; 32]).collect();
         let mut accounts = vec![make_account(&[99u8; 32], false)];
         for m in &members { accounts.push(make_account(m, false)); }
-        handle(&accounts, &create_key, 1, &members);
+        let err = handle(&accounts, &create_key, 1, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::TooManyMembers);
     }
 
     #[test]
-    #[should_panic(expected = "must be uninitialized")]
     fn test_create_multisig_already_initialized_fails() {
         let create_key = [1u8; 32];
         let members: Vec<[u8; 32]> = vec![[10u8; 32]];
@@ -166,6 +264,111 @@ mod tests {
             },
             make_account(&[10u8; 32], false),
         ];
-        handle(&accounts, &create_key, 1, &members);
+        let err = handle(&accounts, &create_key, 1, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::AccountAlreadyInitialized);
+    }
+
+    #[test]
+    fn test_create_weighted_multisig() {
+        let create_key = [1u8; 32];
+        // Two founders with weight 2, one advisor with weight 1; threshold 4 of 5
+        let members: Vec<[u8; 32]> = vec![[10u8; 32], [11u8; 32], [12u8; 32]];
+        let mut accounts = vec![make_account(&[99u8; 32], false)];
+        for m in &members { accounts.push(make_account(m, false)); }
+
+        let (post_states, _) = handle(&accounts, &create_key, 4, &members, 0, &[0u8; 32], 0, &[2, 2, 1], &[]).unwrap();
+
+        let state: MultisigState = borsh::from_slice(&Vec::from(post_states[0].account().data.clone())).unwrap();
+        assert_eq!(state.weights, vec![2, 2, 1]);
+        assert_eq!(state.total_weight(), 5);
+        assert_eq!(state.weight_of(&[10u8; 32]), 2);
+
+        let err = handle(&accounts, &create_key, 6, &members, 0, &[0u8; 32], 0, &[2, 2, 1], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+    }
+
+    #[test]
+    fn test_create_multisig_invalid_weights_fails() {
+        let create_key = [1u8; 32];
+        let members: Vec<[u8; 32]> = vec![[10u8; 32], [11u8; 32]];
+        let mut accounts = vec![make_account(&[99u8; 32], false)];
+        for m in &members { accounts.push(make_account(m, false)); }
+
+        let err = handle(&accounts, &create_key, 1, &members, 0, &[0u8; 32], 0, &[1], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidWeights);
+        let err = handle(&accounts, &create_key, 1, &members, 0, &[0u8; 32], 0, &[1, 0], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidWeights);
+    }
+
+    #[test]
+    fn test_create_multisig_with_permissions() {
+        let create_key = [1u8; 32];
+        let members: Vec<[u8; 32]> = vec![[10u8; 32], [11u8; 32], [12u8; 32]];
+        let mut accounts = vec![make_account(&[99u8; 32], false)];
+        for m in &members { accounts.push(make_account(m, false)); }
+
+        // Bot [12] proposes and executes but has no vote: threshold 3 is unreachable
+        let err = handle(&accounts, &create_key, 3, &members, 0, &[0u8; 32], 0, &[], &[7, 7, 5]).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+
+        let (post_states, _) = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[], &[7, 7, 5]).unwrap();
+        let state: MultisigState = borsh::from_slice(&Vec::from(post_states[0].account().data.clone())).unwrap();
+        assert_eq!(state.permissions, vec![7, 7, 5]);
+        assert_eq!(state.total_weight(), 2);
+
+        // Unknown flag bits
+        let err = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[], &[7, 7, 8]).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidPermissions);
+    }
+
+    #[test]
+    fn test_create_multisig_without_member_accounts() {
+        let create_key = [1u8; 32];
+        let members: Vec<[u8; 32]> = vec![[10u8; 32], [11u8; 32]];
+        let accounts = vec![make_account(&[99u8; 32], false)];
+
+        let (post_states, _) = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap();
+
+        // Only the state is claimed; members act through voter records
+        assert_eq!(post_states.len(), 1);
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.members, members);
+    }
+
+    #[test]
+    fn test_create_multisig_partial_member_accounts_fails() {
+        let create_key = [1u8; 32];
+        let members: Vec<[u8; 32]> = vec![[10u8; 32], [11u8; 32]];
+        let accounts = vec![make_account(&[99u8; 32], false), make_account(&[10u8; 32], false)];
+
+        let err = handle(&accounts, &create_key, 2, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::NotEnoughAccounts);
+    }
+
+    #[test]
+    fn test_create_multisig_reclaims_released_account() {
+        let create_key = [1u8; 32];
+        let program_id = [5u32; 8];
+        let members: Vec<[u8; 32]> = vec![[10u8; 32]];
+        let state_id = compute_multisig_state_pda(&program_id, &create_key);
+
+        // Released by an earlier multisig of the same program
+        let record = MemberAccountRecord { multisig_create_key: [2u8; 32], released: true };
+        let mut released = make_account(&[10u8; 32], true);
+        released.account.program_owner = program_id;
+        released.account.data = borsh::to_vec(&record).unwrap().try_into().unwrap();
+
+        let accounts = vec![make_account(state_id.value(), false), released.clone()];
+        let (post_states, _) = handle(&accounts, &create_key, 1, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap();
+        let record: MemberAccountRecord =
+            borsh::from_slice(&Vec::from(post_states[1].account().data.clone())).unwrap();
+        assert_eq!(record, MemberAccountRecord::new(create_key));
+
+        // Not at the state PDA under the account's owner: not this program's account
+        let accounts = vec![make_account(&[99u8; 32], false), released];
+        let err = handle(&accounts, &create_key, 1, &members, 0, &[0u8; 32], 0, &[], &[]).unwrap_err();
+        assert_eq!(err, MultisigError::AccountAlreadyInitialized);
     }
 }
@@ -0,0 +1,643 @@
+// Instruction dispatch — the one path from an Instruction to its handler.
+//
+// The guest binary (methods/guest/src/bin/multisig.rs) calls `process()`
+// directly, and every #[lez_program] instruction function builds its
+// Instruction and calls it too, so both entry points run the same code. The
+// macro's `pda = ...` and `signer` annotations are not relied on: each handler
+// checks its accounts itself (see accounts.rs). The tests below substitute
+// each annotated account, and run every instruction through both entry
+// points to check they agree.
+//
+// The guest aborts with the error's Display text ("... (MultisigError <code>): ...").
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{ConfigAction, Instruction, MultisigError};
+
+use crate::{
+    abstain, approve, approve_and_execute, approve_with_signatures, cancel, close_proposal, create_multisig,
+    execute, member_account, propose, propose_batch, propose_config, reject, spending_limit, voter_record,
+    withdraw_vote,
+};
+
+pub fn process(
+    accounts: &[AccountWithMetadata],
+    instruction: &Instruction,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    // Handlers check that the state account is the PDA of the create_key in its
+    // own data; this ties that create_key to the instruction's. ReleaseMemberAccount
+    // also takes a closed multisig's wiped state and checks it against the
+    // instruction's create_key itself.
+    if !matches!(instruction, Instruction::CreateMultisig { .. } | Instruction::ReleaseMemberAccount { .. }) {
+        let state_account = accounts.first().ok_or(MultisigError::NotEnoughAccounts)?;
+        let (state, _) = crate::accounts::load_state(state_account)?;
+        if state.create_key != *instruction.create_key() {
+            return Err(MultisigError::MultisigStateMismatch);
+        }
+    }
+
+    match instruction {
+        Instruction::CreateMultisig { create_key, threshold, members, time_lock, clock_account, default_expiry, weights, permissions } =>
+            create_multisig::handle(accounts, create_key, *threshold, members, *time_lock, clock_account, *default_expiry, weights, permissions),
+        Instruction::Propose { target_program_id, target_instruction_data, target_accounts, pda_seeds, authorized_indices, expiry, proposal_index, .. } =>
+            propose::handle(accounts, target_program_id, target_instruction_data, target_accounts, pda_seeds, authorized_indices, *expiry, *proposal_index),
+        Instruction::Approve { proposal_index, .. } => approve::handle(accounts, *proposal_index),
+        Instruction::Reject { proposal_index, .. } => reject::handle(accounts, *proposal_index),
+        Instruction::Execute { proposal_index, .. } => execute::handle(accounts, *proposal_index),
+        Instruction::CloseProposal { proposal_index, .. } => close_proposal::handle(accounts, *proposal_index),
+        Instruction::Cancel { proposal_index, .. } => cancel::handle(accounts, *proposal_index),
+        Instruction::ProposeAddMember { new_member, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::AddMember { new_member: *new_member }, *proposal_index),
+        Instruction::ProposeRemoveMember { member, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::RemoveMember { member: *member }, *proposal_index),
+        Instruction::ProposeChangeThreshold { new_threshold, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::ChangeThreshold { new_threshold: *new_threshold }, *proposal_index),
+        Instruction::ProposeSetTimeLock { time_lock, clock_account, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::SetTimeLock { time_lock: *time_lock, clock_account: *clock_account }, *proposal_index),
+        Instruction::ProposeBatch { calls, expiry, proposal_index, .. } =>
+            propose_batch::handle(accounts, calls, *expiry, *proposal_index),
+        Instruction::ProposeConfigBatch { actions, proposal_index, .. } =>
+            propose_config::handle_batch(accounts, actions.clone(), *proposal_index),
+        Instruction::ProposeReplaceMember { old, new, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::ReplaceMember { old: *old, new: *new }, *proposal_index),
+        Instruction::ProposeSetWeight { member, weight, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::SetWeight { member: *member, weight: *weight }, *proposal_index),
+        Instruction::ProposeSetPermissions { member, permissions, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::SetPermissions { member: *member, permissions: *permissions }, *proposal_index),
+        Instruction::ProposeAddSpendingLimit { limit_key, vault_index, token_program_id, amount, period, members, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::AddSpendingLimit {
+                limit_key: *limit_key,
+                vault_index: *vault_index,
+                token_program_id: *token_program_id,
+                amount: *amount,
+                period: *period,
+                members: members.clone(),
+            }, *proposal_index),
+        Instruction::ProposeRemoveSpendingLimit { limit_key, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::RemoveSpendingLimit { limit_key: *limit_key }, *proposal_index),
+        Instruction::UseSpendingLimit { amount, .. } => spending_limit::handle(accounts, *amount),
+        Instruction::ProposeSetClassThreshold { class, threshold, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::SetClassThreshold { class: *class, threshold: *threshold }, *proposal_index),
+        Instruction::ProposeSetProgramPolicy { target_program_id, policy, proposal_index, .. } =>
+            propose_config::handle(accounts, ConfigAction::SetProgramPolicy { target_program_id: *target_program_id, policy: *policy }, *proposal_index),
+        Instruction::WithdrawVote { proposal_index, .. } => withdraw_vote::handle(accounts, *proposal_index),
+        Instruction::Abstain { proposal_index, .. } => abstain::handle(accounts, *proposal_index),
+        Instruction::ApproveAndExecute { proposal_index, .. } => approve_and_execute::handle(accounts, *proposal_index),
+        Instruction::ApproveWithSignatures { proposal_index, signatures, .. } =>
+            approve_with_signatures::handle(accounts, *proposal_index, signatures),
+        Instruction::CreateVoterRecord { member, .. } => voter_record::handle(accounts, member),
+        Instruction::ActAsVoter { voter_signature, instruction, .. } => {
+            let (accounts, inner) = voter_record::authorize(accounts, voter_signature, instruction)?;
+            process(&accounts, &inner)
+        }
+        Instruction::ReleaseMemberAccount { create_key } => member_account::handle(accounts, create_key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::schnorr::SigningKey;
+    use nssa_core::account::{Account, AccountId};
+    use nssa_core::program::ProgramId;
+    use lez_framework::prelude::{LezError, LezResult};
+    use multisig_core::{
+        account_id_from_public_key, approval_digest, compute_multisig_state_pda, compute_proposal_pda,
+        compute_spending_limit_pda, compute_vault_pda, compute_voter_record_pda, voter_call_digest,
+        ActionClass, MemberAccountRecord, MemberSignature, MultisigState, ProgramPolicy, Proposal, ProposalCall, ProposalStatus,
+        SpendingLimit, VoterRecord,
+    };
+
+    const PROGRAM_ID: ProgramId = [7u32; 8];
+    const OTHER_PROGRAM_ID: ProgramId = [8u32; 8];
+    const TARGET_PROGRAM_ID: ProgramId = [42u32; 8];
+    const CREATE_KEY: [u8; 32] = [3u8; 32];
+    const LIMIT_KEY: [u8; 32] = [5u8; 32];
+
+    /// An instruction with accounts it succeeds on, and the account slots its
+    /// #[lez_program] function marks `signer` and `pda = ...` (with the error
+    /// a substituted PDA gets).
+    struct Case {
+        name: &'static str,
+        instruction: Instruction,
+        accounts: Vec<AccountWithMetadata>,
+        signer: Option<usize>,
+        pda: Option<(usize, MultisigError)>,
+    }
+
+    fn signing_key(secret: u8) -> SigningKey {
+        SigningKey::from_bytes(&[secret; 32]).unwrap()
+    }
+
+    fn member_of(secret: u8) -> [u8; 32] {
+        account_id_from_public_key(&signing_key(secret).verifying_key().to_bytes().into())
+    }
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, owner: ProgramId) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        account.program_owner = owner;
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: false,
+        }
+    }
+
+    /// A claimed member account signing the transaction.
+    fn signer(id: [u8; 32]) -> AccountWithMetadata {
+        let mut account = make_account(&id, vec![], PROGRAM_ID);
+        account.is_authorized = true;
+        account
+    }
+
+    fn state_account() -> AccountWithMetadata {
+        let mut state = MultisigState::new(CREATE_KEY, 2, vec![member_of(1), member_of(2), member_of(3)]);
+        state.transaction_index = 1;
+        let id = compute_multisig_state_pda(&PROGRAM_ID, &CREATE_KEY);
+        make_account(id.value(), borsh::to_vec(&state).unwrap(), PROGRAM_ID)
+    }
+
+    fn make_proposal(approvers: &[u8]) -> Proposal {
+        let mut proposal = Proposal::new(1, member_of(1), CREATE_KEY, TARGET_PROGRAM_ID, vec![0u32], vec![[30u8; 32]], vec![], vec![]);
+        for secret in &approvers[1..] {
+            proposal.approve(member_of(*secret));
+        }
+        proposal
+    }
+
+    fn proposal_account(proposal: &Proposal) -> AccountWithMetadata {
+        let id = compute_proposal_pda(&PROGRAM_ID, &CREATE_KEY, proposal.index);
+        make_account(id.value(), borsh::to_vec(proposal).unwrap(), PROGRAM_ID)
+    }
+
+    fn new_proposal_account() -> AccountWithMetadata {
+        let id = compute_proposal_pda(&PROGRAM_ID, &CREATE_KEY, 2);
+        make_account(id.value(), vec![], [0u32; 8])
+    }
+
+    fn target_account() -> AccountWithMetadata {
+        make_account(&[30u8; 32], vec![], [0u32; 8])
+    }
+
+    fn propose_case(name: &'static str, instruction: Instruction) -> Case {
+        Case {
+            name,
+            instruction,
+            accounts: vec![state_account(), signer(member_of(1)), new_proposal_account()],
+            signer: Some(1),
+            pda: Some((2, MultisigError::ProposalAccountMismatch)),
+        }
+    }
+
+    fn vote_case(name: &'static str, instruction: Instruction, voter: u8, proposal: Proposal) -> Case {
+        Case {
+            name,
+            instruction,
+            accounts: vec![state_account(), signer(member_of(voter)), proposal_account(&proposal)],
+            signer: Some(1),
+            pda: Some((2, MultisigError::ProposalAccountMismatch)),
+        }
+    }
+
+    fn cases(create_key: [u8; 32]) -> Vec<Case> {
+        let ck = create_key;
+        let mut executed = make_proposal(&[1, 2]);
+        executed.status = ProposalStatus::Executed;
+
+        let mut execute = vote_case("execute", Instruction::Execute { proposal_index: 1, create_key: ck }, 1, make_proposal(&[1, 2]));
+        execute.accounts.push(target_account());
+        let mut approve_and_execute =
+            vote_case("approve_and_execute", Instruction::ApproveAndExecute { proposal_index: 1, create_key: ck }, 2, make_proposal(&[1]));
+        approve_and_execute.accounts.push(target_account());
+
+        let relayed = make_proposal(&[1]);
+        let key = signing_key(2);
+        let approval = MemberSignature {
+            public_key: key.verifying_key().to_bytes().into(),
+            signature: key.sign_raw(&approval_digest(&PROGRAM_ID, &relayed, &member_of(2)), &[0u8; 32]).unwrap().to_bytes().to_vec(),
+        };
+        let mut approve_with_signatures = vote_case(
+            "approve_with_signatures",
+            Instruction::ApproveWithSignatures { proposal_index: 1, create_key: ck, signatures: vec![approval] },
+            2,
+            relayed,
+        );
+        // The relayer need not be a member
+        approve_with_signatures.accounts[1] = signer([99u8; 32]);
+
+        let approve_words = risc0_zkvm::serde::to_vec(&Instruction::Approve { proposal_index: 1, create_key: CREATE_KEY }).unwrap();
+        let voter_call = MemberSignature {
+            public_key: key.verifying_key().to_bytes().into(),
+            signature: key
+                .sign_raw(&voter_call_digest(&PROGRAM_ID, &CREATE_KEY, &member_of(2), 0, &approve_words), &[0u8; 32])
+                .unwrap()
+                .to_bytes()
+                .to_vec(),
+        };
+        let record_id = compute_voter_record_pda(&PROGRAM_ID, &CREATE_KEY, &member_of(2));
+        let record = VoterRecord::new(CREATE_KEY, member_of(2));
+
+        let limit = SpendingLimit::new(CREATE_KEY, LIMIT_KEY, 0, TARGET_PROGRAM_ID, 100, 0, vec![member_of(1)]);
+        let limit_id = compute_spending_limit_pda(&PROGRAM_ID, &CREATE_KEY, &LIMIT_KEY);
+        let vault_id = compute_vault_pda(&PROGRAM_ID, &CREATE_KEY, 0);
+
+        let mut member_account = signer(member_of(4));
+        member_account.account.balance = 100;
+        member_account.account.data = borsh::to_vec(&MemberAccountRecord::new(CREATE_KEY)).unwrap().try_into().unwrap();
+
+        vec![
+            propose_case("propose", Instruction::Propose {
+                target_program_id: TARGET_PROGRAM_ID,
+                target_instruction_data: vec![0u32],
+                target_accounts: vec![[30u8; 32]],
+                pda_seeds: vec![],
+                authorized_indices: vec![],
+                expiry: 0,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            vote_case("approve", Instruction::Approve { proposal_index: 1, create_key: ck }, 2, make_proposal(&[1])),
+            vote_case("reject", Instruction::Reject { proposal_index: 1, create_key: ck }, 3, make_proposal(&[1])),
+            execute,
+            vote_case("close_proposal", Instruction::CloseProposal { proposal_index: 1, create_key: ck }, 1, executed),
+            vote_case("cancel", Instruction::Cancel { proposal_index: 1, create_key: ck }, 1, make_proposal(&[1])),
+            propose_case("propose_add_member", Instruction::ProposeAddMember {
+                new_member: member_of(4),
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_remove_member", Instruction::ProposeRemoveMember {
+                member: member_of(3),
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_change_threshold", Instruction::ProposeChangeThreshold {
+                new_threshold: 1,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_set_time_lock", Instruction::ProposeSetTimeLock {
+                time_lock: 0,
+                clock_account: [0u8; 32],
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_batch", Instruction::ProposeBatch {
+                calls: vec![ProposalCall {
+                    target_program_id: TARGET_PROGRAM_ID,
+                    target_instruction_data: vec![0u32],
+                    target_accounts: vec![[30u8; 32]],
+                    pda_seeds: vec![],
+                    authorized_indices: vec![],
+                }],
+                expiry: 0,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_config_batch", Instruction::ProposeConfigBatch {
+                actions: vec![ConfigAction::ChangeThreshold { new_threshold: 1 }],
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_replace_member", Instruction::ProposeReplaceMember {
+                old: member_of(3),
+                new: member_of(4),
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_set_weight", Instruction::ProposeSetWeight {
+                member: member_of(3),
+                weight: 2,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_set_permissions", Instruction::ProposeSetPermissions {
+                member: member_of(3),
+                permissions: 1,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_add_spending_limit", Instruction::ProposeAddSpendingLimit {
+                limit_key: LIMIT_KEY,
+                vault_index: 0,
+                token_program_id: TARGET_PROGRAM_ID,
+                amount: 100,
+                period: 0,
+                members: vec![member_of(1)],
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_remove_spending_limit", Instruction::ProposeRemoveSpendingLimit {
+                limit_key: LIMIT_KEY,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            Case {
+                name: "use_spending_limit",
+                instruction: Instruction::UseSpendingLimit { amount: 10, limit_key: LIMIT_KEY, create_key: ck },
+                accounts: vec![
+                    state_account(),
+                    signer(member_of(1)),
+                    make_account(limit_id.value(), borsh::to_vec(&limit).unwrap(), PROGRAM_ID),
+                    make_account(vault_id.value(), vec![], [0u32; 8]),
+                    make_account(&[31u8; 32], vec![], [0u32; 8]),
+                ],
+                signer: Some(1),
+                pda: Some((2, MultisigError::SpendingLimitMismatch)),
+            },
+            propose_case("propose_set_class_threshold", Instruction::ProposeSetClassThreshold {
+                class: ActionClass::Config,
+                threshold: 2,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            propose_case("propose_set_program_policy", Instruction::ProposeSetProgramPolicy {
+                target_program_id: TARGET_PROGRAM_ID,
+                policy: ProgramPolicy::Allowed,
+                create_key: ck,
+                proposal_index: 2,
+            }),
+            vote_case("withdraw_vote", Instruction::WithdrawVote { proposal_index: 1, create_key: ck }, 2, make_proposal(&[1, 2])),
+            vote_case("abstain", Instruction::Abstain { proposal_index: 1, create_key: ck }, 3, make_proposal(&[1])),
+            approve_and_execute,
+            approve_with_signatures,
+            Case {
+                name: "create_voter_record",
+                instruction: Instruction::CreateVoterRecord { create_key: ck, member: member_of(2) },
+                accounts: vec![state_account(), make_account(record_id.value(), vec![], [0u32; 8])],
+                signer: None,
+                pda: Some((1, MultisigError::VoterRecordMismatch)),
+            },
+            Case {
+                name: "act_as_voter",
+                instruction: Instruction::ActAsVoter { create_key: ck, voter_signature: voter_call, instruction: approve_words },
+                accounts: vec![
+                    state_account(),
+                    make_account(record_id.value(), borsh::to_vec(&record).unwrap(), PROGRAM_ID),
+                    proposal_account(&make_proposal(&[1])),
+                ],
+                signer: None,
+                pda: None,
+            },
+            Case {
+                name: "release_member_account",
+                instruction: Instruction::ReleaseMemberAccount { create_key: ck },
+                accounts: vec![state_account(), member_account, make_account(&[40u8; 32], vec![], [0u32; 8])],
+                signer: Some(1),
+                pda: None,
+            },
+        ]
+    }
+
+    /// Split off the first `N` accounts, as the macro hands them to an
+    /// instruction function, from the trailing ones.
+    fn split<const N: usize>(accounts: &[AccountWithMetadata]) -> ([AccountWithMetadata; N], Vec<AccountWithMetadata>) {
+        (accounts[..N].to_vec().try_into().unwrap(), accounts[N..].to_vec())
+    }
+
+    /// Run `instruction` through its #[lez_program] function.
+    fn call_entry_point(accounts: &[AccountWithMetadata], instruction: &Instruction) -> LezResult {
+        use crate::multisig_program as entry;
+
+        match instruction.clone() {
+            Instruction::CreateMultisig { create_key, threshold, members, time_lock, clock_account, default_expiry, weights, permissions } => {
+                let ([state], rest) = split(accounts);
+                entry::create_multisig(state, rest, create_key, threshold, members, time_lock, clock_account, default_expiry, weights, permissions)
+            }
+            Instruction::Propose { target_program_id, target_instruction_data, target_accounts, pda_seeds, authorized_indices, expiry, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose(state, signer, proposal, rest, target_program_id, target_instruction_data, target_accounts, pda_seeds, authorized_indices, expiry, create_key, proposal_index)
+            }
+            Instruction::Approve { proposal_index, create_key } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::approve(state, signer, proposal, rest, proposal_index, create_key)
+            }
+            Instruction::Reject { proposal_index, create_key } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::reject(state, signer, proposal, rest, proposal_index, create_key)
+            }
+            Instruction::Execute { proposal_index, create_key } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::execute(state, signer, proposal, rest, proposal_index, create_key)
+            }
+            Instruction::CloseProposal { proposal_index, create_key } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::close_proposal(state, signer, proposal, rest, proposal_index, create_key)
+            }
+            Instruction::Cancel { proposal_index, create_key } => {
+                let ([state, signer, proposal], _) = split(accounts);
+                entry::cancel(state, signer, proposal, proposal_index, create_key)
+            }
+            Instruction::ProposeAddMember { new_member, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_add_member(state, signer, proposal, rest, new_member, create_key, proposal_index)
+            }
+            Instruction::ProposeRemoveMember { member, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_remove_member(state, signer, proposal, rest, member, create_key, proposal_index)
+            }
+            Instruction::ProposeChangeThreshold { new_threshold, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_change_threshold(state, signer, proposal, rest, new_threshold, create_key, proposal_index)
+            }
+            Instruction::ProposeSetTimeLock { time_lock, clock_account, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_set_time_lock(state, signer, proposal, rest, time_lock, clock_account, create_key, proposal_index)
+            }
+            Instruction::ProposeBatch { calls, expiry, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_batch(state, signer, proposal, rest, calls, expiry, create_key, proposal_index)
+            }
+            Instruction::ProposeConfigBatch { actions, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_config_batch(state, signer, proposal, rest, actions, create_key, proposal_index)
+            }
+            Instruction::ProposeReplaceMember { old, new, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_replace_member(state, signer, proposal, rest, old, new, create_key, proposal_index)
+            }
+            Instruction::ProposeSetWeight { member, weight, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_set_weight(state, signer, proposal, rest, member, weight, create_key, proposal_index)
+            }
+            Instruction::ProposeSetPermissions { member, permissions, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_set_permissions(state, signer, proposal, rest, member, permissions, create_key, proposal_index)
+            }
+            Instruction::ProposeAddSpendingLimit { limit_key, vault_index, token_program_id, amount, period, members, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_add_spending_limit(state, signer, proposal, rest, limit_key, vault_index, token_program_id, amount, period, members, create_key, proposal_index)
+            }
+            Instruction::ProposeRemoveSpendingLimit { limit_key, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_remove_spending_limit(state, signer, proposal, rest, limit_key, create_key, proposal_index)
+            }
+            Instruction::UseSpendingLimit { amount, limit_key, create_key } => {
+                let ([state, signer, limit], rest) = split(accounts);
+                entry::use_spending_limit(state, signer, limit, rest, amount, limit_key, create_key)
+            }
+            Instruction::ProposeSetClassThreshold { class, threshold, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_set_class_threshold(state, signer, proposal, rest, class, threshold, create_key, proposal_index)
+            }
+            Instruction::ProposeSetProgramPolicy { target_program_id, policy, create_key, proposal_index } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::propose_set_program_policy(state, signer, proposal, rest, target_program_id, policy, create_key, proposal_index)
+            }
+            Instruction::WithdrawVote { proposal_index, create_key } => {
+                let ([state, signer, proposal], _) = split(accounts);
+                entry::withdraw_vote(state, signer, proposal, proposal_index, create_key)
+            }
+            Instruction::Abstain { proposal_index, create_key } => {
+                let ([state, signer, proposal], _) = split(accounts);
+                entry::abstain(state, signer, proposal, proposal_index, create_key)
+            }
+            Instruction::ApproveAndExecute { proposal_index, create_key } => {
+                let ([state, signer, proposal], rest) = split(accounts);
+                entry::approve_and_execute(state, signer, proposal, rest, proposal_index, create_key)
+            }
+            Instruction::ApproveWithSignatures { proposal_index, create_key, signatures } => {
+                let ([state, relayer, proposal], rest) = split(accounts);
+                entry::approve_with_signatures(state, relayer, proposal, rest, proposal_index, create_key, signatures)
+            }
+            Instruction::CreateVoterRecord { create_key, member } => {
+                let ([state, record], _) = split(accounts);
+                entry::create_voter_record(state, record, create_key, member)
+            }
+            Instruction::ActAsVoter { create_key, voter_signature, instruction } => {
+                let ([state, record], rest) = split(accounts);
+                entry::act_as_voter(state, record, rest, create_key, voter_signature, instruction)
+            }
+            Instruction::ReleaseMemberAccount { create_key } => {
+                let ([state, member, recipient], _) = split(accounts);
+                entry::release_member_account(state, member, recipient, create_key)
+            }
+        }
+    }
+
+    /// The accounts a case succeeds on, and each substitution of a state,
+    /// signer or PDA account the other tests make.
+    fn account_variants(case: &Case) -> Vec<Vec<AccountWithMetadata>> {
+        let mut variants = vec![case.accounts.clone()];
+        let mut moved_state = case.accounts.clone();
+        moved_state[0].account_id = AccountId::new([97u8; 32]);
+        variants.push(moved_state);
+        if let Some(slot) = case.signer {
+            let mut unsigned = case.accounts.clone();
+            unsigned[slot].is_authorized = false;
+            variants.push(unsigned);
+        }
+        if let Some((slot, _)) = case.pda {
+            let mut moved = case.accounts.clone();
+            moved[slot].account_id = AccountId::new([98u8; 32]);
+            variants.push(moved);
+        }
+        variants
+    }
+
+    /// A run's post states and chained calls, or its error code. The output
+    /// types only promise Debug, so that is what is compared.
+    fn outcome<T: std::fmt::Debug>(result: Result<T, u32>) -> Result<String, u32> {
+        result.map(|output| format!("{output:?}"))
+    }
+
+    #[test]
+    fn test_entry_points_match_process() {
+        // CreateMultisig has no case: its new state is a default account, so
+        // nothing tells the handler its program ID. A state created at any
+        // other address fails every later instruction with MultisigStateMismatch.
+        let create = Instruction::CreateMultisig {
+            create_key: CREATE_KEY,
+            threshold: 2,
+            members: vec![member_of(1), member_of(2)],
+            time_lock: 0,
+            clock_account: [0u8; 32],
+            default_expiry: 0,
+            weights: vec![],
+            permissions: vec![],
+        };
+        let state_id = compute_multisig_state_pda(&PROGRAM_ID, &CREATE_KEY);
+        let mut runs = vec![("create_multisig", create, vec![make_account(state_id.value(), vec![], [0u32; 8])])];
+        for (case, other_key) in cases(CREATE_KEY).into_iter().zip(cases([4u8; 32])) {
+            runs.push((case.name, other_key.instruction, case.accounts.clone()));
+            for accounts in account_variants(&case) {
+                runs.push((case.name, case.instruction.clone(), accounts));
+            }
+        }
+
+        for (name, instruction, accounts) in runs {
+            let via_process = process(&accounts, &instruction).map_err(MultisigError::code);
+            let via_entry_point = call_entry_point(&accounts, &instruction)
+                .map(|output| (output.post_states, output.chained_calls))
+                .map_err(|err| match err {
+                    LezError::Custom { code, .. } => code,
+                    other => panic!("{name}: {other:?}"),
+                });
+            assert_eq!(outcome(via_entry_point), outcome(via_process), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_every_case_succeeds() {
+        for case in cases(CREATE_KEY) {
+            if let Err(err) = process(&case.accounts, &case.instruction) {
+                panic!("{}: {err}", case.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_substituted_state_fails() {
+        for case in cases(CREATE_KEY) {
+            let mut moved = case.accounts.clone();
+            moved[0].account_id = AccountId::new([97u8; 32]);
+            let mut foreign = case.accounts.clone();
+            foreign[0].account.program_owner = OTHER_PROGRAM_ID;
+
+            for accounts in [moved, foreign] {
+                let err = process(&accounts, &case.instruction).unwrap_err();
+                assert_eq!(err, MultisigError::MultisigStateMismatch, "{}", case.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_other_multisigs_create_key_fails() {
+        for (case, valid) in cases([4u8; 32]).into_iter().zip(cases(CREATE_KEY)) {
+            let err = process(&valid.accounts, &case.instruction).unwrap_err();
+            assert_eq!(err, MultisigError::MultisigStateMismatch, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_unsigned_signer_fails() {
+        for case in cases(CREATE_KEY) {
+            let Some(slot) = case.signer else { continue };
+            let mut accounts = case.accounts.clone();
+            accounts[slot].is_authorized = false;
+
+            let err = process(&accounts, &case.instruction).unwrap_err();
+            assert_eq!(err, MultisigError::MissingSignature, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_substituted_pda_fails() {
+        for case in cases(CREATE_KEY) {
+            let Some((slot, expected)) = case.pda else { continue };
+            let mut moved = case.accounts.clone();
+            moved[slot].account_id = AccountId::new([98u8; 32]);
+            let err = process(&moved, &case.instruction).unwrap_err();
+            assert_eq!(err, expected, "{}", case.name);
+
+            // An existing PDA must also be the program's own
+            if case.accounts[slot].account.program_owner == PROGRAM_ID {
+                let mut foreign = case.accounts.clone();
+                foreign[slot].account.program_owner = OTHER_PROGRAM_ID;
+                let err = process(&foreign, &case.instruction).unwrap_err();
+                assert_eq!(err, expected, "{}", case.name);
+            }
+        }
+    }
+}
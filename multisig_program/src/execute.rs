@@ -2,95 +2,110 @@
 //
 // The multisig doesn't execute actions directly. It builds a ChainedCall
 // to the target program specified in the proposal, delegating actual execution.
+// A batch proposal emits one ChainedCall per call, in order. Calls on disjoint
+// accounts run together in this transaction and succeed or fail as a whole; a
+// call on an account an earlier call already uses waits for the next Execute,
+// which passes that account as the earlier call left it (batch_step_len). The
+// proposal stays Active until its last step has run.
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read threshold/membership)
-// - accounts[1]: executor (must be authorized signer, must be member)
+// - accounts[1]: executor (must be authorized signer, member with Execute permission)
 // - accounts[2]: proposal PDA account (owned by multisig program)
-// - accounts[3..]: target accounts to pass to the ChainedCall
+// - accounts[3]: clock account (only when the multisig has a time-lock or the proposal expires)
+// - accounts[3..] / accounts[4..]: target accounts to pass to the ChainedCall (must match
+//   proposal.execute_accounts() exactly: the single call's targets, or the
+//   accounts of the batch's next step, each once in order of first use). Config proposals take the accounts of the
+//   members their AddMember/ReplaceMember actions bring in (optional, but all or
+//   none; claimed if fresh or released), then the spending limit PDAs their AddSpendingLimit/RemoveSpendingLimit
+//   actions create or close.
 
 use nssa_core::account::AccountWithMetadata;
 use nssa_core::program::{AccountPostState, ChainedCall, PdaSeed};
-use multisig_core::{ConfigAction, MultisigState, Proposal, ProposalStatus};
+use multisig_core::{is_multisig_pda_seed, ConfigAction, MultisigError, Proposal, ProposalStatus, PERMISSION_EXECUTE};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
-    _proposal_index: u64,
-) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
-    assert!(accounts.len() >= 3, "Execute requires at least multisig_state + executor + proposal");
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
 
     let multisig_account = &accounts[0];
     let executor_account = &accounts[1];
     let proposal_account = &accounts[2];
-    let target_accounts = &accounts[3..];
 
-    assert!(executor_account.is_authorized, "Executor must sign the transaction");
+    if !executor_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
 
     // Read multisig state
-    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let mut state: MultisigState = borsh::from_slice(&state_data)
-        .expect("Failed to deserialize multisig state");
+    let (mut state, program_id) = crate::accounts::load_state(multisig_account)?;
 
     let executor_id = *executor_account.account_id.value();
-    assert!(state.is_member(&executor_id), "Executor is not a multisig member");
+    if !state.is_member(&executor_id) {
+        return Err(MultisigError::NotAMember);
+    }
+    state.require_permission(&executor_id, PERMISSION_EXECUTE)?;
 
     // Read proposal
+    crate::accounts::check_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
     let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
     let mut proposal: Proposal = borsh::from_slice(&proposal_data)
-        .expect("Failed to deserialize proposal");
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    // Time-locked multisigs and expiring proposals take the clock account ahead of the targets
+    let (clock_account, target_accounts) = if crate::clock::required_for_execute(&state, &proposal) {
+        (accounts.get(3), accounts.get(4..).unwrap_or(&[]))
+    } else {
+        (None, &accounts[3..])
+    };
 
-    assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
-    assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
-    assert!(
-        proposal.has_threshold(state.threshold),
-        "Proposal does not have enough approvals: need {}, have {}",
-        state.threshold,
-        proposal.approved.len()
-    );
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive);
+    }
+    if state.is_stale(proposal.index) {
+        return Err(MultisigError::StaleProposal);
+    }
+    if crate::clock::is_expired(&mut state, &proposal, clock_account)? {
+        return Err(MultisigError::ProposalExpired);
+    }
+    if !proposal.has_threshold(&state) {
+        return Err(MultisigError::ThresholdNotReached);
+    }
+    crate::clock::check_time_lock(&mut state, &proposal, clock_account)?;
 
     // Mark as executed
     proposal.status = ProposalStatus::Executed;
 
     // Handle config change vs transfer proposal
-    if let Some(config_action) = &proposal.config_action {
+    if proposal.is_config() {
         // Config change: modify MultisigState directly, no ChainedCall
-        assert_eq!(
-            target_accounts.len(), 0,
-            "Config change proposals should not have target accounts"
-        );
+        let actions = proposal.config_actions();
 
-        match config_action {
-            ConfigAction::AddMember { new_member } => {
-                assert!(!state.is_member(new_member), "Account is already a member");
-                assert!(state.member_count < 10, "Maximum 10 members");
-                state.members.push(*new_member);
-                state.member_count += 1;
-            }
-            ConfigAction::RemoveMember { member } => {
-                assert!(state.is_member(member), "Account is not a member");
-                assert!(
-                    state.member_count - 1 >= state.threshold,
-                    "Cannot remove member: would make member count ({}) less than threshold ({})",
-                    state.member_count - 1,
-                    state.threshold
-                );
-                state.members.retain(|m| m != member);
-                state.member_count -= 1;
-            }
-            ConfigAction::ChangeThreshold { new_threshold } => {
-                assert!(*new_threshold >= 1, "Threshold must be at least 1");
-                assert!(
-                    *new_threshold <= state.member_count,
-                    "Threshold ({}) cannot exceed member count ({})",
-                    new_threshold,
-                    state.member_count
-                );
-                state.threshold = *new_threshold;
-            }
-        }
+        // A batch is applied as a whole; only the resulting config is validated
+        state.apply_config(&actions)?;
+        crate::clock::check_not_state(&state, multisig_account)?;
+
+        let program_id = multisig_account.account.program_owner;
+        let (member_posts, limit_accounts) =
+            crate::member_account::claim_new_members(&program_id, &state.create_key, &actions, target_accounts)?;
+        let limit_posts = crate::spending_limit::execute_config(&program_id, &state, &actions, limit_accounts)?;
 
-        // Write back updated state
-        let state_bytes = borsh::to_vec(&state).unwrap();
+        // Votes on every earlier proposal were cast under the old config
+        state.invalidate_prior_proposals();
+
+        // Write back updated state; closing wipes it, leaving the PDA owned and
+        // empty, so no instruction but ReleaseMemberAccount accepts it again
+        let state_bytes = if actions.iter().any(ConfigAction::closes_multisig) {
+            Vec::new()
+        } else {
+            borsh::to_vec(&state).unwrap()
+        };
         let mut multisig_post = multisig_account.account.clone();
         multisig_post.data = state_bytes.try_into().unwrap();
 
@@ -100,53 +115,95 @@ pub fn handle(
 
         let executor_post = executor_account.account.clone();
 
-        (
-            vec![
-                AccountPostState::new(multisig_post),
-                AccountPostState::new(executor_post),
-                AccountPostState::new(proposal_post),
-            ],
-            vec![],
-        )
+        let mut post_states = vec![
+            AccountPostState::new(multisig_post),
+            AccountPostState::new(executor_post),
+            AccountPostState::new(proposal_post),
+        ];
+        if let Some(clock) = clock_account {
+            post_states.push(AccountPostState::new(clock.account.clone()));
+        }
+        post_states.extend(member_posts);
+        post_states.extend(limit_posts);
+
+        Ok((post_states, vec![]))
     } else {
-        // Transfer proposal: emit ChainedCall
-        assert_eq!(
-            target_accounts.len(),
-            proposal.target_account_count as usize,
-            "Expected {} target accounts, got {}",
-            proposal.target_account_count,
-            target_accounts.len()
-        );
+        // Transfer proposal: emit one ChainedCall per call
+        // An older proposal never recorded which accounts members approved
+        if proposal.predates_target_accounts() {
+            return Err(MultisigError::ProposalPredatesTargetAccounts);
+        }
+        let expected_accounts = proposal.execute_accounts();
+        if target_accounts.len() != expected_accounts.len() {
+            return Err(MultisigError::TargetAccountCountMismatch);
+        }
+        // Accounts must be exactly the ones members approved, in the same order
+        for (acc, expected) in target_accounts.iter().zip(expected_accounts.iter()) {
+            if acc.account_id.value() != expected {
+                return Err(MultisigError::TargetAccountMismatch);
+            }
+        }
 
-        let target_program_id = proposal.target_program_id.clone();
-        let target_instruction_data = proposal.target_instruction_data.clone();
-        let pda_seeds: Vec<PdaSeed> = proposal.pda_seeds.iter().map(|s| PdaSeed::new(*s)).collect();
-        let authorized_indices = proposal.authorized_indices.clone();
+        let calls = proposal.next_calls();
+        if !proposal.calls.is_empty() {
+            proposal.executed_calls += calls.len() as u8;
+            // More steps to go: the next Execute runs them against the new state
+            if !proposal.all_calls_executed() {
+                proposal.status = ProposalStatus::Active;
+            }
+        }
+
+        // Re-check seeds at execute time so a proposal can never carry
+        // authority over a PDA that belongs to another multisig
+        let seeds_ok = calls
+            .iter()
+            .flat_map(|c| c.pda_seeds.iter())
+            .all(|seed| is_multisig_pda_seed(&proposal.multisig_create_key, seed));
+        if !seeds_ok {
+            return Err(MultisigError::ForeignPdaSeed);
+        }
+
+        // The program policy may have changed since the proposal was created
+        for call in &calls {
+            state.check_program(&call.target_program_id)?;
+        }
 
         let proposal_bytes = borsh::to_vec(&proposal).unwrap();
         let mut proposal_post = proposal_account.account.clone();
         proposal_post.data = proposal_bytes.try_into().unwrap();
 
-        let chained_pre_states: Vec<AccountWithMetadata> = target_accounts
+        // Each call sees its accounts as passed to Execute; no two calls of a step share one
+        let chained_calls: Vec<ChainedCall> = calls
             .iter()
-            .enumerate()
-            .map(|(i, acc)| {
-                let mut acc = acc.clone();
-                if authorized_indices.contains(&(i as u8)) {
-                    acc.is_authorized = true;
+            .map(|call| {
+                let pre_states: Vec<AccountWithMetadata> = call
+                    .target_accounts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, id)| {
+                        let mut acc = target_accounts
+                            .iter()
+                            .find(|a| a.account_id.value() == id)
+                            .unwrap()
+                            .clone();
+                        if call.authorized_indices.contains(&(i as u8)) {
+                            acc.is_authorized = true;
+                        }
+                        acc
+                    })
+                    .collect();
+                ChainedCall {
+                    program_id: call.target_program_id,
+                    instruction_data: call.target_instruction_data.clone(),
+                    pre_states,
+                    pda_seeds: call.pda_seeds.iter().map(|s| PdaSeed::new(*s)).collect(),
                 }
-                acc
             })
             .collect();
 
-        let chained_call = ChainedCall {
-            program_id: target_program_id,
-            instruction_data: target_instruction_data,
-            pre_states: chained_pre_states,
-            pda_seeds,
-        };
-
-        let multisig_post = multisig_account.account.clone();
+        // The state records the clock time read, if any
+        let mut multisig_post = multisig_account.account.clone();
+        multisig_post.data = borsh::to_vec(&state).unwrap().try_into().unwrap();
         let executor_post = executor_account.account.clone();
 
         let mut post_states = vec![
@@ -154,12 +211,15 @@ pub fn handle(
             AccountPostState::new(executor_post),
             AccountPostState::new(proposal_post),
         ];
+        if let Some(clock) = clock_account {
+            post_states.push(AccountPostState::new(clock.account.clone()));
+        }
 
         for target in target_accounts {
             post_states.push(AccountPostState::new(target.account.clone()));
         }
 
-        (post_states, vec![chained_call])
+        Ok((post_states, chained_calls))
     }
 }
 
@@ -169,6 +229,7 @@ mod tests {
     use nssa_core::account::{Account, AccountId};
     use nssa_core::program::ProgramId;
     use multisig_core::{MultisigState, Proposal, ProposalStatus};
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
 
     fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
         let mut account = Account::default();
@@ -180,11 +241,27 @@ mod tests {
         }
     }
 
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
     fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
         borsh::to_vec(&MultisigState::new([0u8; 32], threshold, members)).unwrap()
     }
 
-    fn make_proposal_with_approvals(approvals: Vec<[u8; 32]>, target_account_count: u8) -> Vec<u8> {
+    fn make_proposal_with_approvals(approvals: Vec<[u8; 32]>, target_accounts: Vec<[u8; 32]>) -> Vec<u8> {
+        make_proposal_with_seeds(approvals, target_accounts, vec![])
+    }
+
+    fn make_proposal_with_seeds(
+        approvals: Vec<[u8; 32]>,
+        target_accounts: Vec<[u8; 32]>,
+        pda_seeds: Vec<[u8; 32]>,
+    ) -> Vec<u8> {
         let fake_program_id: ProgramId = [42u32; 8];
         let mut proposal = Proposal::new(
             1,
@@ -192,8 +269,8 @@ mod tests {
             [0u8; 32],
             fake_program_id,
             vec![0u32],
-            target_account_count,
-            vec![],
+            target_accounts,
+            pda_seeds,
             vec![0u8], // first target account is authorized
         );
         for approver in &approvals[1..] {
@@ -207,16 +284,16 @@ mod tests {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members);
         // 2 approvals (member 1 auto, member 2 added)
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),   // multisig state
+            make_account(&state_id(), state_data, false),   // multisig state
             make_account(&[1u8; 32], vec![], true),           // executor (member)
-            make_account(&[20u8; 32], proposal_data, false),  // proposal PDA
+            make_account(&proposal_id(1), proposal_data, false),  // proposal PDA
             make_account(&[30u8; 32], vec![], false),          // target account
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
 
         assert_eq!(chained.len(), 1);
         assert_eq!(post_states.len(), 4);
@@ -233,56 +310,396 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "enough approvals")]
     fn test_execute_below_threshold_fails() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members);
         // Only 1 approval (proposer only)
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32]], vec![[30u8; 32]]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
             make_account(&[30u8; 32], vec![], false),
         ];
 
-        handle(&accounts, 1);
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdNotReached);
+    }
+
+    #[test]
+    fn test_execute_removed_member_approval_not_counted() {
+        // [9; 32] approved, then was removed: only 1 current approval remains
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [9u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdNotReached);
+    }
+
+    #[test]
+    fn test_execute_stale_proposal_fails() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let mut state = MultisigState::new([0u8; 32], 2, members);
+        state.transaction_index = 2;
+        state.invalidate_prior_proposals();
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::StaleProposal);
+    }
+
+    fn make_time_locked_accounts(reached_at: u64, now: u64, clock_id: [u8; 32]) -> Vec<AccountWithMetadata> {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.time_lock = 100;
+        state.clock_account = [77u8; 32];
+        let mut proposal: Proposal = borsh::from_slice(
+            &make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]])
+        ).unwrap();
+        proposal.threshold_reached_at = Some(reached_at);
+
+        vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&clock_id, now.to_le_bytes().to_vec(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_after_time_lock_elapsed() {
+        let accounts = make_time_locked_accounts(1_000, 1_100, [77u8; 32]);
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(post_states.len(), 5);
+        assert_eq!(chained[0].pre_states.len(), 1);
+        assert_eq!(chained[0].pre_states[0].account_id, AccountId::new([30u8; 32]));
+    }
+
+    #[test]
+    fn test_execute_before_time_lock_elapsed_fails() {
+        let accounts = make_time_locked_accounts(1_000, 1_099, [77u8; 32]);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TimeLockNotElapsed);
+    }
+
+    #[test]
+    fn test_execute_with_wrong_clock_account_fails() {
+        let accounts = make_time_locked_accounts(1_000, 5_000, [78u8; 32]);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ClockAccountMismatch);
+    }
+
+    fn make_expiring_accounts(expires_at: u64, now: u64) -> Vec<AccountWithMetadata> {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.clock_account = [77u8; 32];
+        let mut proposal: Proposal = borsh::from_slice(
+            &make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]])
+        ).unwrap();
+        proposal.expires_at = Some(expires_at);
+
+        vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&[77u8; 32], now.to_le_bytes().to_vec(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_before_expiry() {
+        let accounts = make_expiring_accounts(2_000, 1_999);
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(post_states.len(), 5);
+        assert_eq!(chained[0].pre_states[0].account_id, AccountId::new([30u8; 32]));
+    }
+
+    #[test]
+    fn test_execute_expired_proposal_fails() {
+        let accounts = make_expiring_accounts(2_000, 2_000);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalExpired);
     }
 
     #[test]
-    #[should_panic(expected = "Expected 1 target accounts, got 0")]
     fn test_execute_wrong_account_count_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
 
         // Missing the target account
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
             // no target account!
         ];
 
-        handle(&accounts, 1);
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountCountMismatch);
     }
 
     #[test]
-    #[should_panic(expected = "not a multisig member")]
     fn test_execute_non_member_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
-        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], 1);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[99u8; 32], vec![], true), // NOT a member
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+
+    #[test]
+    fn test_execute_substituted_target_account_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        // Executor swaps in a different account for the approved target
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[66u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountMismatch);
+    }
+
+    #[test]
+    fn test_execute_reordered_target_accounts_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_proposal_with_approvals(
+            vec![[1u8; 32], [2u8; 32]],
+            vec![[30u8; 32], [31u8; 32], [32u8; 32]],
+        );
+
+        // Same accounts, but the last two are swapped
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+            make_account(&[32u8; 32], vec![], false),
+            make_account(&[31u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountMismatch);
+    }
+
+    #[test]
+    fn test_execute_proposal_without_recorded_targets_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+        // A proposal from before target_accounts only knows how many there are
+        let mut proposal = Proposal::new(1, [1u8; 32], [0u8; 32], [42u32; 8], vec![0u32], vec![[30u8; 32]], vec![], vec![0u8]);
+        proposal.approve([2u8; 32]);
+        proposal.target_accounts.clear();
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalPredatesTargetAccounts);
+    }
+
+    #[test]
+    fn test_execute_with_own_vault_seed() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 0);
+        let proposal_data = make_proposal_with_seeds(
+            vec![[1u8; 32], [2u8; 32]],
+            vec![[30u8; 32]],
+            vec![vault_seed],
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
             make_account(&[30u8; 32], vec![], false),
         ];
 
-        handle(&accounts, 1);
+        let (_, chained) = handle(&accounts, 1).unwrap();
+        assert_eq!(chained[0].pda_seeds.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_other_multisig_vault_seed_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+        // Proposal stored with the vault seed of multisig [7; 32] (e.g. written before
+        // propose-time validation existed) — must not be usable to drain that vault
+        let foreign_seed = multisig_core::vault_pda_seed_bytes(&[7u8; 32], 0);
+        let proposal_data = make_proposal_with_seeds(
+            vec![[1u8; 32], [2u8; 32]],
+            vec![[30u8; 32]],
+            vec![foreign_seed],
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ForeignPdaSeed);
+    }
+
+    // -- Batch proposal tests --
+
+    fn make_batch_accounts(accounts_passed: Vec<[u8; 32]>, seed: [u8; 32]) -> Vec<AccountWithMetadata> {
+        make_batch(
+            vec![vec![[30u8; 32], [31u8; 32]], vec![[33u8; 32], [32u8; 32]]],
+            accounts_passed,
+            seed,
+        )
+    }
+
+    fn make_batch(
+        call_targets: Vec<Vec<[u8; 32]>>,
+        accounts_passed: Vec<[u8; 32]>,
+        seed: [u8; 32],
+    ) -> Vec<AccountWithMetadata> {
+        let calls = call_targets
+            .into_iter()
+            .map(|targets| multisig_core::ProposalCall {
+                target_program_id: [42u32; 8],
+                target_instruction_data: vec![0u32],
+                target_accounts: targets,
+                pda_seeds: vec![seed],
+                authorized_indices: vec![0],
+            })
+            .collect();
+        let mut proposal = Proposal::new_batch(1, [1u8; 32], [0u8; 32], calls);
+        proposal.approve([2u8; 32]);
+
+        let mut accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+        ];
+        for id in accounts_passed {
+            accounts.push(make_account(&id, vec![], false));
+        }
+        accounts
+    }
+
+    #[test]
+    fn test_execute_batch_emits_chained_call_per_call() {
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 0);
+        let accounts = make_batch_accounts(vec![[30u8; 32], [31u8; 32], [33u8; 32], [32u8; 32]], vault_seed);
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(post_states.len(), 7);
+        assert_eq!(chained.len(), 2);
+        let ids = |c: &ChainedCall| c.pre_states.iter().map(|p| *p.account_id.value()).collect::<Vec<_>>();
+        assert_eq!(ids(&chained[0]), vec![[30u8; 32], [31u8; 32]]);
+        assert_eq!(ids(&chained[1]), vec![[33u8; 32], [32u8; 32]]);
+        // Each call's source is authorized, its recipient is not
+        for call in &chained {
+            assert!(call.pre_states[0].is_authorized);
+            assert!(!call.pre_states[1].is_authorized);
+        }
+    }
+
+    #[test]
+    fn test_execute_batch_with_repeated_account_fails() {
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 0);
+        // An account passed twice
+        let accounts = make_batch_accounts(vec![[30u8; 32], [31u8; 32], [31u8; 32], [33u8; 32], [32u8; 32]], vault_seed);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountCountMismatch);
+    }
+
+    #[test]
+    fn test_execute_batch_reusing_an_account_runs_in_steps() {
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 0);
+        // Vault 30 pays 31 and then 32; vault 33 pays 34
+        let call_targets = vec![
+            vec![[30u8; 32], [31u8; 32]],
+            vec![[30u8; 32], [32u8; 32]],
+            vec![[33u8; 32], [34u8; 32]],
+        ];
+
+        // All accounts at once: the first step only takes the first call's
+        let accounts = make_batch(
+            call_targets.clone(),
+            vec![[30u8; 32], [31u8; 32], [32u8; 32], [33u8; 32], [34u8; 32]],
+            vault_seed,
+        );
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountCountMismatch);
+
+        let accounts = make_batch(call_targets.clone(), vec![[30u8; 32], [31u8; 32]], vault_seed);
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+        assert_eq!(chained.len(), 1);
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.executed_calls, 1);
+
+        // The next step passes vault 30 as the first payment left it
+        let mut accounts = make_batch(call_targets, vec![[30u8; 32], [32u8; 32], [33u8; 32], [34u8; 32]], vault_seed);
+        accounts[2].account = post_states[2].account().clone();
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+        assert_eq!(chained.len(), 2);
+        let ids = |c: &ChainedCall| c.pre_states.iter().map(|p| *p.account_id.value()).collect::<Vec<_>>();
+        assert_eq!(ids(&chained[0]), vec![[30u8; 32], [32u8; 32]]);
+        assert_eq!(ids(&chained[1]), vec![[33u8; 32], [34u8; 32]]);
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(proposal.executed_calls, 3);
+    }
+
+    #[test]
+    fn test_execute_batch_foreign_seed_fails() {
+        let foreign_seed = multisig_core::vault_pda_seed_bytes(&[7u8; 32], 0);
+        let accounts = make_batch_accounts(vec![[30u8; 32], [31u8; 32], [33u8; 32], [32u8; 32]], foreign_seed);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ForeignPdaSeed);
     }
 
     // -- Config action tests --
@@ -310,12 +727,12 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
 
         assert!(chained.is_empty());
         let state: MultisigState = borsh::from_slice(
@@ -335,12 +752,12 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
 
         assert!(chained.is_empty());
         let state: MultisigState = borsh::from_slice(
@@ -351,7 +768,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Cannot remove member")]
     fn test_execute_remove_member_would_break_threshold() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
@@ -361,12 +777,13 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        handle(&accounts, 1);
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::RemovalBreaksThreshold);
     }
 
     #[test]
@@ -379,12 +796,12 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, chained) = handle(&accounts, 1);
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
 
         assert!(chained.is_empty());
         let state: MultisigState = borsh::from_slice(
@@ -394,7 +811,98 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "cannot exceed member count")]
+    fn test_execute_config_change_invalidates_prior_proposals() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let mut state = MultisigState::new([0u8; 32], 2, members);
+        state.transaction_index = 4; // proposals 2..=4 were created after this one
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::ChangeThreshold { new_threshold: 3 },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.stale_transaction_index, 4);
+        assert!(state.is_stale(4));
+        assert!(!state.is_stale(5));
+    }
+
+    #[test]
+    fn test_execute_set_time_lock() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let mut state = MultisigState::new([0u8; 32], 2, members);
+        state.last_clock = 5_000;
+        let state_data = borsh::to_vec(&state).unwrap();
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetTimeLock { time_lock: 600, clock_account: [77u8; 32] },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.time_lock, 600);
+        assert_eq!(state.clock_account, [77u8; 32]);
+        // The new clock starts from its own time
+        assert_eq!(state.last_clock, 0);
+    }
+
+    #[test]
+    fn test_execute_set_time_lock_without_clock_fails() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetTimeLock { time_lock: 600, clock_account: [0u8; 32] },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ClockAccountNotSet);
+    }
+
+    #[test]
+    fn test_execute_set_time_lock_on_state_account_fails() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_state(2, members);
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetTimeLock { time_lock: 600, clock_account: state_id() },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidClockConfig);
+    }
+
+    #[test]
     fn test_execute_change_threshold_too_high() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members);
@@ -404,11 +912,505 @@ mod tests {
         );
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+    }
+
+    fn make_config_batch_accounts(threshold: u8, members: Vec<[u8; 32]>, actions: Vec<ConfigAction>) -> Vec<AccountWithMetadata> {
+        let mut proposal = Proposal::new_config_batch(1, [1u8; 32], [0u8; 32], actions);
+        proposal.approve([2u8; 32]);
+
+        vec![
+            make_account(&state_id(), make_state(threshold, members), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_config_batch_rotates_member() {
+        // 2-of-2: removing first would break the threshold, but the add lands in the same batch
+        let accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            ConfigAction::RemoveMember { member: [2u8; 32] },
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            ConfigAction::AddMember { new_member: [5u8; 32] },
+            ConfigAction::ChangeThreshold { new_threshold: 3 },
+        ]);
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.members, vec![[1u8; 32], [4u8; 32], [5u8; 32]]);
+        assert_eq!(state.member_count, 3);
+        assert_eq!(state.threshold, 3);
+    }
+
+    #[test]
+    fn test_execute_config_batch_invalid_result_fails() {
+        let accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            ConfigAction::ChangeThreshold { new_threshold: 4 },
+        ]);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+    }
+
+    #[test]
+    fn test_execute_close_multisig_wipes_state() {
+        let accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![ConfigAction::CloseMultisig]);
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        assert!(Vec::from(post_states[0].account().data.clone()).is_empty());
+
+        // Nothing runs against it afterwards
+        let mut closed = accounts[0].clone();
+        closed.account = post_states[0].account().clone();
+        let err = crate::accounts::load_state(&closed).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidMultisigState);
+    }
+
+    #[test]
+    fn test_execute_config_batch_with_target_accounts_fails() {
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+        ]);
+        accounts.push(make_account(&[30u8; 32], vec![], false));
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::UnexpectedTargetAccounts);
+    }
+
+    #[test]
+    fn test_execute_add_member_claims_its_account() {
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            add_limit_action([5u8; 32]),
+        ]);
+        accounts.push(make_account(&[4u8; 32], vec![], false));
+        accounts.push(limit_account(&[5u8; 32], vec![]));
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(post_states.len(), 5);
+        assert!(post_states[3].requires_claim());
+        assert!(post_states[4].requires_claim());
+    }
+
+    #[test]
+    fn test_execute_add_member_with_used_account_fails() {
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+        ]);
+        accounts.push(make_account(&[4u8; 32], vec![1], false));
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AccountAlreadyInitialized);
+    }
+
+    #[test]
+    fn test_execute_replace_member() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        // The old key can vote for its own replacement
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::ReplaceMember { old: [2u8; 32], new: [4u8; 32] },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, members), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        // Swapped in place; N and M unchanged
+        assert_eq!(state.members, vec![[1u8; 32], [4u8; 32], [3u8; 32]]);
+        assert_eq!(state.member_count, 3);
+        assert_eq!(state.threshold, 2);
+
+        // The old key's votes no longer count; the new key starts with none
+        let mut other = Proposal::new_config(2, [1u8; 32], [0u8; 32], ConfigAction::ChangeThreshold { new_threshold: 3 });
+        other.approve([2u8; 32]);
+        assert!(!other.has_threshold(&state));
+    }
+
+    #[test]
+    fn test_execute_weighted_threshold() {
+        // Founder [1] weighs 2, advisors [2] and [3] weigh 1; threshold 3
+        let mut state = MultisigState::new([0u8; 32], 3, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.weights = vec![2, 1, 1];
+        let state_data = borsh::to_vec(&state).unwrap();
+
+        // Both advisors: weight 2 < 3
+        let accounts = vec![
+            make_account(&state_id(), state_data.clone(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal_with_approvals(vec![[2u8; 32], [3u8; 32]], vec![[30u8; 32]]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdNotReached);
+
+        // Founder and one advisor: weight 3
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+        let (_, chained) = handle(&accounts, 1).unwrap();
+        assert_eq!(chained.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_set_weight() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetWeight { member: [1u8; 32], weight: 3 },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, members), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        // An unweighted multisig becomes weighted, other members keep weight 1
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.weights, vec![3, 1, 1]);
+        assert_eq!(state.total_weight(), 5);
+    }
+
+    #[test]
+    fn test_execute_weight_change_below_threshold_fails() {
+        let mut state = MultisigState::new([0u8; 32], 4, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.weights = vec![2, 1, 1];
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32], [3u8; 32]],
+            ConfigAction::SetWeight { member: [1u8; 32], weight: 1 },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        // Total weight would drop to 3 with threshold 4
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+    }
+
+    #[test]
+    fn test_execute_remove_weighted_member_drops_weight() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.weights = vec![2, 3, 1];
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::RemoveMember { member: [2u8; 32] },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.members, vec![[1u8; 32], [3u8; 32]]);
+        assert_eq!(state.weights, vec![2, 1]);
+    }
+
+    fn make_bot_state() -> Vec<u8> {
+        // [3; 32] may only propose and execute; [2; 32] may only vote
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.permissions = vec![
+            multisig_core::PERMISSION_ALL,
+            multisig_core::PERMISSION_VOTE,
+            multisig_core::PERMISSION_INITIATE | PERMISSION_EXECUTE,
+        ];
+        borsh::to_vec(&state).unwrap()
+    }
+
+    #[test]
+    fn test_execute_by_bot_without_vote() {
+        let accounts = vec![
+            make_account(&state_id(), make_bot_state(), false),
+            make_account(&[3u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let (_, chained) = handle(&accounts, 1).unwrap();
+        assert_eq!(chained.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_without_execute_permission_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_bot_state(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingPermission);
+    }
+
+    #[test]
+    fn test_execute_non_voter_approval_not_counted() {
+        // The bot's approval carries no weight
+        let accounts = vec![
+            make_account(&state_id(), make_bot_state(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal_with_approvals(vec![[1u8; 32], [3u8; 32]], vec![[30u8; 32]]), false),
+            make_account(&[30u8; 32], vec![], false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdNotReached);
+    }
+
+    #[test]
+    fn test_execute_set_permissions() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::SetPermissions { member: [3u8; 32], permissions: multisig_core::PERMISSION_INITIATE | PERMISSION_EXECUTE },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, members), false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        handle(&accounts, 1);
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.permissions, vec![7, 7, 5]);
+        assert_eq!(state.total_weight(), 2);
+    }
+
+    #[test]
+    fn test_execute_set_permissions_leaving_no_executor_fails() {
+        let mut state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32], [2u8; 32]]);
+        state.permissions = vec![multisig_core::PERMISSION_ALL, multisig_core::PERMISSION_VOTE];
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32]],
+            ConfigAction::SetPermissions { member: [1u8; 32], permissions: multisig_core::PERMISSION_VOTE },
+        );
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidPermissions);
+    }
+
+    fn add_limit_action(limit_key: [u8; 32]) -> ConfigAction {
+        ConfigAction::AddSpendingLimit {
+            limit_key,
+            vault_index: 0,
+            token_program_id: [42u32; 8],
+            amount: 100,
+            period: 50,
+            members: vec![[2u8; 32]],
+        }
+    }
+
+    fn limit_account(limit_key: &[u8; 32], data: Vec<u8>) -> AccountWithMetadata {
+        let id = multisig_core::compute_spending_limit_pda(&[0u32; 8], &[0u8; 32], limit_key);
+        make_account(id.value(), data, false)
+    }
+
+    #[test]
+    fn test_execute_add_spending_limit_creates_pda() {
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            add_limit_action([5u8; 32]),
+        ]);
+        accounts.push(limit_account(&[5u8; 32], vec![]));
+
+        let (post_states, chained) = handle(&accounts, 1).unwrap();
+
+        assert!(chained.is_empty());
+        assert_eq!(post_states.len(), 4);
+        assert!(post_states[3].requires_claim());
+        let limit: multisig_core::SpendingLimit = borsh::from_slice(
+            &Vec::from(post_states[3].account().data.clone())
+        ).unwrap();
+        assert_eq!(limit.limit_key, [5u8; 32]);
+        assert_eq!(limit.remaining, 100);
+        assert_eq!(limit.members, vec![[2u8; 32]]);
+    }
+
+    #[test]
+    fn test_execute_add_spending_limit_wrong_account_fails() {
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            add_limit_action([5u8; 32]),
+        ]);
+        accounts.push(limit_account(&[6u8; 32], vec![]));
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountMismatch);
+
+        accounts.pop();
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountCountMismatch);
+    }
+
+    #[test]
+    fn test_execute_remove_spending_limit_clears_pda() {
+        let limit = multisig_core::SpendingLimit::new([0u8; 32], [5u8; 32], 0, [42u32; 8], 100, 0, vec![[2u8; 32]]);
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            ConfigAction::RemoveSpendingLimit { limit_key: [5u8; 32] },
+        ]);
+        accounts.push(limit_account(&[5u8; 32], borsh::to_vec(&limit).unwrap()));
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        assert!(Vec::from(post_states[3].account().data.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_execute_duplicate_spending_limit_key_fails() {
+        let mut accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32]], vec![
+            add_limit_action([5u8; 32]),
+            ConfigAction::RemoveSpendingLimit { limit_key: [5u8; 32] },
+        ]);
+        accounts.push(limit_account(&[5u8; 32], vec![]));
+        accounts.push(limit_account(&[5u8; 32], vec![]));
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidSpendingLimitParams);
+    }
+
+    fn class_threshold_accounts(state: &MultisigState, proposal_data: Vec<u8>, with_target: bool) -> Vec<AccountWithMetadata> {
+        let mut accounts = vec![
+            make_account(&state_id(), borsh::to_vec(state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+        if with_target {
+            accounts.push(make_account(&[30u8; 32], vec![], false));
+        }
+        accounts
+    }
+
+    #[test]
+    fn test_execute_config_below_config_threshold_fails() {
+        // 2-of-3 for transfers, but config changes need all three
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.config_threshold = 3;
+        let proposal_data = make_config_proposal(
+            vec![[1u8; 32], [2u8; 32]],
+            ConfigAction::ChangeThreshold { new_threshold: 1 },
+        );
+
+        let err = handle(&class_threshold_accounts(&state, proposal_data, false), 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdNotReached);
+
+        // The same two approvals still execute a transfer
+        let transfer = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+        let (_, chained) = handle(&class_threshold_accounts(&state, transfer, true), 1).unwrap();
+        assert_eq!(chained.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_uses_program_threshold() {
+        let mut state = MultisigState::new([0u8; 32], 1, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.program_thresholds = vec![multisig_core::ProgramThreshold { program_id: [42u32; 8], threshold: 3 }];
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let err = handle(&class_threshold_accounts(&state, proposal_data, true), 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdNotReached);
+    }
+
+    #[test]
+    fn test_execute_set_class_threshold() {
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let accounts = make_config_batch_accounts(2, members, vec![
+            ConfigAction::SetClassThreshold { class: multisig_core::ActionClass::Config, threshold: 3 },
+            ConfigAction::SetClassThreshold { class: multisig_core::ActionClass::Program([7u32; 8]), threshold: 1 },
+        ]);
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.config_threshold, 3);
+        assert_eq!(state.class_threshold(&multisig_core::ActionClass::Transfer), 2);
+        assert_eq!(state.class_threshold(&multisig_core::ActionClass::Program([7u32; 8])), 1);
+    }
+
+    #[test]
+    fn test_execute_class_threshold_too_high_fails() {
+        let accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32], [3u8; 32]], vec![
+            ConfigAction::SetClassThreshold { class: multisig_core::ActionClass::Transfer, threshold: 4 },
+        ]);
+
+        let err = handle(&accounts, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+    }
+
+    #[test]
+    fn test_execute_program_outside_allowlist_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.allowed_programs = vec![[7u32; 8]];
+        let proposal_data = make_proposal_with_approvals(vec![[1u8; 32], [2u8; 32]], vec![[30u8; 32]]);
+
+        let err = handle(&class_threshold_accounts(&state, proposal_data, true), 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProgramNotAllowed);
+    }
+
+    #[test]
+    fn test_execute_set_program_policy() {
+        use multisig_core::ProgramPolicy;
+        let accounts = make_config_batch_accounts(2, vec![[1u8; 32], [2u8; 32], [3u8; 32]], vec![
+            ConfigAction::SetProgramPolicy { target_program_id: [7u32; 8], policy: ProgramPolicy::Denied },
+            ConfigAction::SetProgramPolicy { target_program_id: [8u32; 8], policy: ProgramPolicy::Allowed },
+            // Moving a program to the other list takes it off the first one
+            ConfigAction::SetProgramPolicy { target_program_id: [7u32; 8], policy: ProgramPolicy::Allowed },
+        ]);
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.allowed_programs, vec![[8u32; 8], [7u32; 8]]);
+        assert!(state.denied_programs.is_empty());
     }
 }
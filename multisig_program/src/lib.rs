@@ -1,14 +1,34 @@
 pub mod create_multisig;
 pub mod propose;
 pub mod propose_config;
+pub mod propose_batch;
 pub mod approve;
 pub mod reject;
 pub mod execute;
+pub mod close_proposal;
+pub mod cancel;
+pub mod spending_limit;
+pub mod withdraw_vote;
+pub mod abstain;
+pub mod approve_and_execute;
+pub mod approve_with_signatures;
+pub mod voter_record;
+pub mod member_account;
+mod accounts;
+mod clock;
+mod dispatch;
+
+pub use dispatch::process;
 
 use nssa_core::program::{InstructionData, ProgramId};
-use multisig_core::ConfigAction;
+use multisig_core::{ActionClass, ConfigAction, Instruction, MemberSignature, MultisigError, ProgramPolicy, ProposalCall};
 use lez_framework::prelude::*;
 
+/// Surface a handler failure to the framework, keeping its stable MultisigError code.
+fn lez_error(e: MultisigError) -> LezError {
+    LezError::Custom { code: e.code(), message: e.to_string() }
+}
+
 /// Multisig program using #[lez_program] macro.
 /// Uses external multisig_core::Instruction enum for dispatch.
 #[lez_program(instruction = "multisig_core::Instruction")]
@@ -17,25 +37,49 @@ mod multisig_program {
 
     /// Create a new M-of-N multisig.
     /// multisig_state is initialized as a PDA derived from create_key.
+    /// weights: vote weight per member (in members order); empty = one vote each,
+    /// otherwise threshold is the approval weight required.
+    /// permissions: PERMISSION_* flags per member (Initiate = 1, Vote = 2, Execute = 4);
+    /// empty = every member has all three.
+    /// time_lock = 0 disables the execution delay; default_expiry = 0 lets proposals
+    /// without their own expiry live forever (clock_account is unused while both are 0).
+    /// multisig_state PDA seeds: ["multisig_state__", create_key]
     #[instruction]
     pub fn create_multisig(
-        #[account(init, pda = arg("create_key"))]
+        #[account(init, pda = [literal("multisig_state__"), arg("create_key")])]
         multisig_state: AccountWithMetadata,
         member_accounts: Vec<AccountWithMetadata>,
         create_key: [u8; 32],
         threshold: u8,
         members: Vec<[u8; 32]>,
+        time_lock: u64,
+        clock_account: [u8; 32],
+        default_expiry: u64,
+        weights: Vec<u8>,
+        permissions: Vec<u8>,
     ) -> LezResult {
         let accounts: Vec<AccountWithMetadata> = std::iter::once(multisig_state)
             .chain(member_accounts.into_iter())
             .collect();
-        let (post_states, chained_calls) =
-            crate::create_multisig::handle(&accounts, &create_key, threshold, &members);
+        let instruction = Instruction::CreateMultisig {
+            create_key,
+            threshold,
+            members,
+            time_lock,
+            clock_account,
+            default_expiry,
+            weights,
+            permissions,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Propose a new transaction.
     /// proposer must be a member signer. proposal is initialized as a new PDA.
+    /// expiry: lifetime in clock units; 0 takes the multisig's default_expiry.
+    /// clock: the multisig's clock account when the proposal expires or the multisig
+    /// is time-locked with threshold 1, else empty.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn propose(
@@ -45,28 +89,35 @@ mod multisig_program {
         proposer: AccountWithMetadata,
         #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
         proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
         target_program_id: ProgramId,
         target_instruction_data: Vec<u32>,
-        target_account_count: u8,
+        target_accounts: Vec<[u8; 32]>,
         pda_seeds: Vec<[u8; 32]>,
         authorized_indices: Vec<u8>,
+        expiry: u64,
         create_key: [u8; 32],
         proposal_index: u64,
     ) -> LezResult {
-        let accounts = vec![multisig_state, proposer, proposal];
-        let (post_states, chained_calls) = crate::propose::handle(
-            &accounts,
-            &target_program_id,
-            &target_instruction_data,
-            target_account_count,
-            &pda_seeds,
-            &authorized_indices,
-        );
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::Propose {
+            target_program_id,
+            target_instruction_data,
+            target_accounts,
+            pda_seeds,
+            authorized_indices,
+            expiry,
+            create_key,
+            proposal_index,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Approve an existing proposal.
-    /// approver must be a member signer.
+    /// approver must be a member signer. Expired proposals are refused.
+    /// clock: the multisig's clock account when time-locked or the proposal expires, else empty.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn approve(
@@ -76,17 +127,21 @@ mod multisig_program {
         approver: AccountWithMetadata,
         #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
         proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
         proposal_index: u64,
         create_key: [u8; 32],
     ) -> LezResult {
-        let accounts = vec![multisig_state, approver, proposal];
-        let (post_states, chained_calls) =
-            crate::approve::handle(&accounts, proposal_index);
+        let mut accounts = vec![multisig_state, approver, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::Approve { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Reject an existing proposal.
     /// rejector must be a member signer.
+    /// clock: optionally the multisig's clock account; an expired proposal is then
+    /// marked Expired instead of recording the rejection, by any signer.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn reject(
@@ -96,17 +151,25 @@ mod multisig_program {
         rejector: AccountWithMetadata,
         #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
         proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
         proposal_index: u64,
         create_key: [u8; 32],
     ) -> LezResult {
-        let accounts = vec![multisig_state, rejector, proposal];
-        let (post_states, chained_calls) =
-            crate::reject::handle(&accounts, proposal_index);
+        let mut accounts = vec![multisig_state, rejector, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::Reject { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Execute a fully-approved proposal.
-    /// executor must be a member signer. target_accounts are the rest accounts.
+    /// executor must be a member signer. target_accounts are the rest accounts and
+    /// must match the proposal's recorded target account IDs exactly (for a batch:
+    /// every call's accounts, call by call; for a config
+    /// proposal: optionally the accounts of the members it adds, claimed if fresh,
+    /// then the spending limit PDAs it creates or closes). A time-locked
+    /// multisig or an expiring proposal takes the clock account as the first rest
+    /// account, before the targets. Expired proposals are refused.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn execute(
@@ -122,13 +185,59 @@ mod multisig_program {
     ) -> LezResult {
         let mut accounts = vec![multisig_state, executor, proposal];
         accounts.extend(target_accounts);
-        let (post_states, chained_calls) =
-            crate::execute::handle(&accounts, proposal_index);
+        let instruction = Instruction::Execute { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Close a finished (non-Active) proposal, wiping its account data.
+    /// closer must be a member signer. older_proposals are the rest accounts:
+    /// further finished proposals of this multisig with index < proposal_index.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn close_proposal(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        closer: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        older_proposals: Vec<AccountWithMetadata>,
+        proposal_index: u64,
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, closer, proposal];
+        accounts.extend(older_proposals);
+        let instruction = Instruction::CloseProposal { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Cancel an Active proposal.
+    /// canceller must be a member signer. The proposer cancels immediately;
+    /// other members vote, and the proposal is cancelled at threshold votes.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn cancel(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        canceller: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        proposal_index: u64,
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let accounts = vec![multisig_state, canceller, proposal];
+        let instruction = Instruction::Cancel { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Propose adding a new member.
     /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn propose_add_member(
@@ -138,20 +247,22 @@ mod multisig_program {
         proposer: AccountWithMetadata,
         #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
         proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
         new_member: [u8; 32],
         create_key: [u8; 32],
         proposal_index: u64,
     ) -> LezResult {
-        let accounts = vec![multisig_state, proposer, proposal];
-        let (post_states, chained_calls) = crate::propose_config::handle(
-            &accounts,
-            ConfigAction::AddMember { new_member },
-        );
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeAddMember { new_member, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Propose removing a member.
     /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn propose_remove_member(
@@ -161,20 +272,22 @@ mod multisig_program {
         proposer: AccountWithMetadata,
         #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
         proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
         member: [u8; 32],
         create_key: [u8; 32],
         proposal_index: u64,
     ) -> LezResult {
-        let accounts = vec![multisig_state, proposer, proposal];
-        let (post_states, chained_calls) = crate::propose_config::handle(
-            &accounts,
-            ConfigAction::RemoveMember { member },
-        );
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeRemoveMember { member, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
 
     /// Propose changing the threshold.
     /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
     /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
     #[instruction]
     pub fn propose_change_threshold(
@@ -184,40 +297,490 @@ mod multisig_program {
         proposer: AccountWithMetadata,
         #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
         proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
         new_threshold: u8,
         create_key: [u8; 32],
         proposal_index: u64,
     ) -> LezResult {
-        let accounts = vec![multisig_state, proposer, proposal];
-        let (post_states, chained_calls) = crate::propose_config::handle(
-            &accounts,
-            ConfigAction::ChangeThreshold { new_threshold },
-        );
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeChangeThreshold { new_threshold, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
         Ok(LezOutput { post_states, chained_calls })
     }
-}
 
-// Legacy process() function for the existing guest binary.
-// The #[lez_program] macro generates main() and IDL, but the guest binary
-// (methods/guest/src/bin/multisig.rs) uses this for the risc0 entry point.
-pub fn process(
-    accounts: &[nssa_core::account::AccountWithMetadata],
-    instruction: &multisig_core::Instruction,
-) -> (Vec<nssa_core::program::AccountPostState>, Vec<nssa_core::program::ChainedCall>) {
-    use multisig_core::Instruction;
-    match instruction {
-        Instruction::CreateMultisig { create_key, threshold, members } =>
-            create_multisig::handle(accounts, create_key, *threshold, members),
-        Instruction::Propose { target_program_id, target_instruction_data, target_account_count, pda_seeds, authorized_indices, .. } =>
-            propose::handle(accounts, target_program_id, target_instruction_data, *target_account_count, pda_seeds, authorized_indices),
-        Instruction::Approve { proposal_index, .. } => approve::handle(accounts, *proposal_index),
-        Instruction::Reject { proposal_index, .. } => reject::handle(accounts, *proposal_index),
-        Instruction::Execute { proposal_index, .. } => execute::handle(accounts, *proposal_index),
-        Instruction::ProposeAddMember { new_member, .. } =>
-            propose_config::handle(accounts, ConfigAction::AddMember { new_member: *new_member }),
-        Instruction::ProposeRemoveMember { member, .. } =>
-            propose_config::handle(accounts, ConfigAction::RemoveMember { member: *member }),
-        Instruction::ProposeChangeThreshold { new_threshold, .. } =>
-            propose_config::handle(accounts, ConfigAction::ChangeThreshold { new_threshold: *new_threshold }),
+    /// Propose changing the execution time-lock and its clock account.
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_set_time_lock(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        time_lock: u64,
+        clock_account: [u8; 32],
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeSetTimeLock {
+            time_lock,
+            clock_account,
+            create_key,
+            proposal_index,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose an ordered batch of calls, executed atomically by one Execute.
+    /// No two calls may share a target account.
+    /// proposer must be a member signer. proposal is initialized as a new PDA.
+    /// expiry: lifetime in clock units; 0 takes the multisig's default_expiry.
+    /// clock: the multisig's clock account when the proposal expires or the multisig
+    /// is time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_batch(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        calls: Vec<ProposalCall>,
+        expiry: u64,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeBatch { calls, expiry, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose several config changes, applied together by one Execute.
+    /// proposer must be a member signer. proposal is initialized.
+    /// Only the resulting config is validated (at propose and again at execute).
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_config_batch(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        actions: Vec<ConfigAction>,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeConfigBatch { actions, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose replacing member `old` with `new` in place (key rotation).
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_replace_member(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        old: [u8; 32],
+        new: [u8; 32],
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeReplaceMember { old, new, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose changing a member's vote weight.
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_set_weight(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        member: [u8; 32],
+        weight: u8,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeSetWeight { member, weight, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose changing a member's permission flags (Initiate = 1, Vote = 2, Execute = 4).
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_set_permissions(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        member: [u8; 32],
+        permissions: u8,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeSetPermissions {
+            member,
+            permissions,
+            create_key,
+            proposal_index,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose creating a spending limit: members may then transfer up to amount
+    /// of token_program_id tokens from vault vault_index per period (0 = one-off)
+    /// without a proposal. Execute takes the uninitialized spending limit PDA as its target.
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    /// spending limit PDA seeds: ["multisig_limit__", create_key, limit_key]
+    #[instruction]
+    pub fn propose_add_spending_limit(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        limit_key: [u8; 32],
+        vault_index: u8,
+        token_program_id: ProgramId,
+        amount: u128,
+        period: u64,
+        members: Vec<[u8; 32]>,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeAddSpendingLimit {
+            limit_key,
+            vault_index,
+            token_program_id,
+            amount,
+            period,
+            members,
+            create_key,
+            proposal_index,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose closing a spending limit. Execute takes the spending limit PDA as its target.
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_remove_spending_limit(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        limit_key: [u8; 32],
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeRemoveSpendingLimit { limit_key, create_key, proposal_index };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Transfer amount from the vault under a spending limit, without a proposal.
+    /// spender must be a member signer listed in the limit. transfer_accounts are
+    /// the rest accounts: [the limit's vault, recipient holding], preceded by the multisig's
+    /// clock account when the limit has a period.
+    /// spending limit PDA seeds: ["multisig_limit__", create_key, limit_key]
+    #[instruction]
+    pub fn use_spending_limit(
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        spender: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_limit__"), arg("create_key"), arg("limit_key")])]
+        spending_limit: AccountWithMetadata,
+        transfer_accounts: Vec<AccountWithMetadata>,
+        amount: u128,
+        limit_key: [u8; 32],
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, spender, spending_limit];
+        accounts.extend(transfer_accounts);
+        let instruction = Instruction::UseSpendingLimit { amount, limit_key, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose a threshold for one class of proposals: Config, Transfer, or
+    /// Program(program_id) for calls to one target program. 0 restores the default.
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_set_class_threshold(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        class: ActionClass,
+        threshold: u8,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeSetClassThreshold {
+            class,
+            threshold,
+            create_key,
+            proposal_index,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Propose a program policy: Allowed (allowlist), Denied (denylist) or
+    /// Default (off both lists). With a non-empty allowlist, proposals may only
+    /// call listed programs; denied programs are never callable.
+    /// proposer must be a member signer. proposal is initialized.
+    /// clock: the multisig's clock account when it has a default_expiry or is
+    /// time-locked with threshold 1, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn propose_set_program_policy(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        proposer: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        target_program_id: ProgramId,
+        policy: ProgramPolicy,
+        create_key: [u8; 32],
+        proposal_index: u64,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, proposer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ProposeSetProgramPolicy {
+            target_program_id,
+            policy,
+            create_key,
+            proposal_index,
+        };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Withdraw the signer's approval, rejection or abstention, and its cancel vote, on an Active proposal.
+    /// voter must be a member signer.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn withdraw_vote(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        voter: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        proposal_index: u64,
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let accounts = vec![multisig_state, voter, proposal];
+        let instruction = Instruction::WithdrawVote { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Abstain on an Active proposal, replacing an earlier approval or rejection.
+    /// voter must be a member signer.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn abstain(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        voter: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        proposal_index: u64,
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let accounts = vec![multisig_state, voter, proposal];
+        let instruction = Instruction::Abstain { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Approve a proposal and, if this approval brings it to threshold, execute
+    /// it in the same transaction. Takes the same accounts as execute: approver
+    /// must be a member signer (without Execute permission it only approves), and
+    /// target_accounts (clock first when needed, then the targets) are validated
+    /// against the proposal. On a time-locked multisig it executes only once the
+    /// lock has elapsed since threshold was reached; until then it only approves.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn approve_and_execute(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        approver: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        target_accounts: Vec<AccountWithMetadata>,
+        proposal_index: u64,
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, approver, proposal];
+        accounts.extend(target_accounts);
+        let instruction = Instruction::ApproveAndExecute { proposal_index, create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Record approvals members signed off-chain over the proposal's approval digest.
+    /// relayer: any signer; it submits the bundle but does not approve.
+    /// signatures: one { public_key, signature } per approving member.
+    /// clock: the multisig's clock account when time-locked or the proposal expires, else empty.
+    /// proposal PDA seeds: ["multisig_prop___", create_key, proposal_index]
+    #[instruction]
+    pub fn approve_with_signatures(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(signer)]
+        relayer: AccountWithMetadata,
+        #[account(mut, pda = [literal("multisig_prop___"), arg("create_key"), arg("proposal_index")])]
+        proposal: AccountWithMetadata,
+        clock: Vec<AccountWithMetadata>,
+        proposal_index: u64,
+        create_key: [u8; 32],
+        signatures: Vec<MemberSignature>,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, relayer, proposal];
+        accounts.extend(clock);
+        let instruction = Instruction::ApproveWithSignatures { proposal_index, create_key, signatures };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Create a member's voter record, so they can act with their wallet key
+    /// through ActAsVoter instead of a claimed member account.
+    /// voter_record PDA seeds: ["multisig_voter__", create_key, member]
+    #[instruction]
+    pub fn create_voter_record(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(init, pda = [literal("multisig_voter__"), arg("create_key"), arg("member")])]
+        voter_record: AccountWithMetadata,
+        create_key: [u8; 32],
+        member: [u8; 32],
+    ) -> LezResult {
+        let accounts = vec![multisig_state, voter_record];
+        let instruction = Instruction::CreateVoterRecord { create_key, member };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Run a member-signed instruction through the member's voter record.
+    /// voter_record takes the wrapped instruction's signer slot; instruction_accounts
+    /// are the rest of its accounts, in its usual order.
+    /// instruction: the wrapped Instruction, risc0-serialized.
+    /// voter_signature: the member's signature over voter_call_digest at the record's nonce.
+    #[instruction]
+    pub fn act_as_voter(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(mut)]
+        voter_record: AccountWithMetadata,
+        instruction_accounts: Vec<AccountWithMetadata>,
+        create_key: [u8; 32],
+        voter_signature: MemberSignature,
+        instruction: Vec<u32>,
+    ) -> LezResult {
+        let mut accounts = vec![multisig_state, voter_record];
+        accounts.extend(instruction_accounts);
+        let instruction = Instruction::ActAsVoter { create_key, voter_signature, instruction };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
+    }
+
+    /// Move the whole native balance of a claimed member account, no longer a
+    /// member of this multisig (or any, once it is closed), to recipient, and
+    /// release the account. member_account must sign.
+    #[instruction]
+    pub fn release_member_account(
+        #[account(mut)]
+        multisig_state: AccountWithMetadata,
+        #[account(mut, signer)]
+        member_account: AccountWithMetadata,
+        #[account(mut)]
+        recipient: AccountWithMetadata,
+        create_key: [u8; 32],
+    ) -> LezResult {
+        let accounts = vec![multisig_state, member_account, recipient];
+        let instruction = Instruction::ReleaseMemberAccount { create_key };
+        let (post_states, chained_calls) = crate::process(&accounts, &instruction).map_err(crate::lez_error)?;
+        Ok(LezOutput { post_states, chained_calls })
     }
 }
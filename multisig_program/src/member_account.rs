@@ -0,0 +1,361 @@
+// Member accounts — claiming them for new members, and releasing them after removal.
+//
+// CreateMultisig claims each member account it is given, so it can take part in
+// later instructions. An AddMember or ReplaceMember config proposal does the
+// same for the member it brings in, at Execute: the new member's account may be
+// passed as a target account, and is claimed if fresh. Claiming writes a
+// MemberAccountRecord naming the multisig, which binds the account to it. An
+// account this multisig claimed before (a removed member's) joins it again as
+// it is; any other owned account is refused, since a wiped proposal or closed
+// spending limit PDA is also program-owned.
+//
+// LEZ never changes an account's owner, so removal can't hand a claimed account
+// back. Instead, once an account is no longer a member of the multisig that
+// claimed it, or that multisig is closed, ReleaseMemberAccount moves its native
+// balance out, which nothing but the multisig program can do, and marks its
+// record released. A released account stays owned by the program but is bound
+// to no multisig: CreateMultisig, AddMember and ReplaceMember claim it again
+// like a fresh one, for any multisig, as long as it signs.
+//
+// ReleaseMemberAccount accounts:
+// - accounts[0]: multisig_state PDA of the multisig that claimed the member
+//   account (or, once closed, the wiped PDA it left)
+// - accounts[1]: member account (must be authorized = is a signer; claimed by
+//   this multisig, not a member of it)
+// - accounts[2]: recipient account (receives the whole balance)
+
+use nssa_core::account::{Account, AccountWithMetadata};
+use nssa_core::program::{AccountPostState, ChainedCall, ProgramId};
+use multisig_core::{compute_multisig_state_pda, ConfigAction, MemberAccountRecord, MultisigError};
+
+/// The record of a member account: owned by this program and holding one.
+fn record_of(account: &Account, program_id: &ProgramId) -> Option<MemberAccountRecord> {
+    let data: Vec<u8> = account.data.clone().into();
+    if account.program_owner != *program_id {
+        return None;
+    }
+    borsh::from_slice(&data).ok()
+}
+
+/// `account` with a fresh record binding it to the multisig `create_key`.
+fn bind(account: &Account, create_key: &[u8; 32]) -> Account {
+    let mut bound = account.clone();
+    bound.data = borsh::to_vec(&MemberAccountRecord::new(*create_key)).unwrap().try_into().unwrap();
+    bound
+}
+
+/// Claim a member account for the multisig `create_key` of `program_id`: a
+/// fresh account, one this multisig claimed before (kept as it is), or a
+/// released one, which must sign.
+pub fn claim(
+    account: &AccountWithMetadata,
+    program_id: &ProgramId,
+    create_key: &[u8; 32],
+) -> Result<AccountPostState, MultisigError> {
+    if account.account == Account::default() {
+        return Ok(AccountPostState::new_claimed(bind(&account.account, create_key)));
+    }
+    match record_of(&account.account, program_id) {
+        Some(record) if record.released => {
+            // Only its holder decides which multisig it joins next
+            if !account.is_authorized {
+                return Err(MultisigError::MissingSignature);
+            }
+            Ok(AccountPostState::new(bind(&account.account, create_key)))
+        }
+        Some(record) if record.multisig_create_key == *create_key => {
+            Ok(AccountPostState::new(account.account.clone()))
+        }
+        _ => Err(MultisigError::AccountAlreadyInitialized),
+    }
+}
+
+/// Claim the accounts of the members an executed config proposal brings in.
+/// They are optional, but all or nothing, as at CreateMultisig: when the first
+/// target account is the first new member's, every new member's account leads
+/// `target_accounts`, in action order. Returns their post states and the
+/// remaining accounts.
+pub fn claim_new_members<'a>(
+    program_id: &ProgramId,
+    create_key: &[u8; 32],
+    actions: &[ConfigAction],
+    target_accounts: &'a [AccountWithMetadata],
+) -> Result<(Vec<AccountPostState>, &'a [AccountWithMetadata]), MultisigError> {
+    let new_members: Vec<[u8; 32]> = actions.iter().filter_map(ConfigAction::new_member).collect();
+    let passed = match (new_members.first(), target_accounts.first()) {
+        (Some(member), Some(account)) => account.account_id.value() == member,
+        _ => false,
+    };
+    if !passed {
+        return Ok((vec![], target_accounts));
+    }
+    if target_accounts.len() < new_members.len() {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let (member_accounts, rest) = target_accounts.split_at(new_members.len());
+    let mut post_states = Vec::new();
+    for (account, new_member) in member_accounts.iter().zip(&new_members) {
+        if account.account_id.value() != new_member {
+            return Err(MultisigError::MemberAccountMismatch);
+        }
+        post_states.push(claim(account, program_id, create_key)?);
+    }
+    Ok((post_states, rest))
+}
+
+/// Handle ReleaseMemberAccount. Dispatch leaves the state account to this
+/// handler, since a closed multisig's can't be loaded.
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    create_key: &[u8; 32],
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let member_account = &accounts[1];
+    let recipient_account = &accounts[2];
+
+    if !member_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
+
+    let program_id = multisig_account.account.program_owner;
+    if multisig_account.account_id != compute_multisig_state_pda(&program_id, create_key) {
+        return Err(MultisigError::MultisigStateMismatch);
+    }
+
+    // The record ties the account to one multisig, so the membership check
+    // below is against the multisig that claimed it, not one the caller picked
+    let record = record_of(&member_account.account, &program_id)
+        .filter(|record| record.multisig_create_key == *create_key)
+        .ok_or(MultisigError::NotAMemberAccount)?;
+
+    // Closing wipes the state but leaves the PDA owned; a closed multisig has no members
+    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
+    let closed = program_id != ProgramId::default() && state_data.is_empty();
+    if !closed {
+        let (state, _) = crate::accounts::load_state(multisig_account)?;
+        if state.is_member(member_account.account_id.value()) {
+            return Err(MultisigError::StillAMember);
+        }
+    }
+
+    let mut member_post = member_account.account.clone();
+    let mut recipient_post = recipient_account.account.clone();
+    recipient_post.balance += member_post.balance;
+    member_post.balance = 0;
+    member_post.data = borsh::to_vec(&MemberAccountRecord { released: true, ..record })
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    Ok((
+        vec![
+            AccountPostState::new(multisig_account.account.clone()),
+            AccountPostState::new(member_post),
+            AccountPostState::new(recipient_post),
+        ],
+        vec![],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::AccountId;
+    use multisig_core::MultisigState;
+
+    const PROGRAM_ID: ProgramId = [5u32; 8];
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, owned: bool, balance: u128) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        account.balance = balance;
+        if owned {
+            account.program_owner = PROGRAM_ID;
+        }
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: false,
+        }
+    }
+
+    fn state_account() -> AccountWithMetadata {
+        let state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        let id = compute_multisig_state_pda(&PROGRAM_ID, &[0u8; 32]);
+        make_account(id.value(), borsh::to_vec(&state).unwrap(), true, 0)
+    }
+
+    /// The state PDA a CloseMultisig left behind: owned, no data.
+    fn closed_state_account() -> AccountWithMetadata {
+        let id = compute_multisig_state_pda(&PROGRAM_ID, &[0u8; 32]);
+        make_account(id.value(), vec![], true, 0)
+    }
+
+    /// A member account claimed by the multisig `create_key`.
+    fn claimed_account(id: &[u8; 32], create_key: [u8; 32], balance: u128) -> AccountWithMetadata {
+        make_account(id, borsh::to_vec(&MemberAccountRecord::new(create_key)).unwrap(), true, balance)
+    }
+
+    /// A member account the multisig `create_key` claimed and then released.
+    fn released_account(id: &[u8; 32], create_key: [u8; 32]) -> AccountWithMetadata {
+        let record = MemberAccountRecord { multisig_create_key: create_key, released: true };
+        make_account(id, borsh::to_vec(&record).unwrap(), true, 0)
+    }
+
+    fn record(post_state: &AccountPostState) -> MemberAccountRecord {
+        borsh::from_slice(&Vec::from(post_state.account().data.clone())).unwrap()
+    }
+
+    fn release_from(state: AccountWithMetadata, member: AccountWithMetadata) -> Result<Vec<AccountPostState>, MultisigError> {
+        let mut member = member;
+        member.is_authorized = true;
+        let accounts = vec![state, member, make_account(&[40u8; 32], vec![], false, 5)];
+        handle(&accounts, &[0u8; 32]).map(|(post_states, _)| post_states)
+    }
+
+    fn release(member: AccountWithMetadata) -> Result<Vec<AccountPostState>, MultisigError> {
+        release_from(state_account(), member)
+    }
+
+    #[test]
+    fn test_release_moves_balance_and_releases_account() {
+        let post_states = release(claimed_account(&[4u8; 32], [0u8; 32], 100)).unwrap();
+
+        assert_eq!(post_states[1].account().balance, 0);
+        assert_eq!(post_states[1].account().program_owner, PROGRAM_ID);
+        assert!(record(&post_states[1]).released);
+        assert_eq!(post_states[2].account().balance, 105);
+    }
+
+    #[test]
+    fn test_release_by_current_member_fails() {
+        let err = release(claimed_account(&[2u8; 32], [0u8; 32], 100)).unwrap_err();
+        assert_eq!(err, MultisigError::StillAMember);
+    }
+
+    #[test]
+    fn test_release_after_multisig_closed() {
+        // [2] was still a member when the multisig closed
+        let post_states = release_from(closed_state_account(), claimed_account(&[2u8; 32], [0u8; 32], 100)).unwrap();
+
+        assert!(record(&post_states[1]).released);
+        assert_eq!(post_states[2].account().balance, 105);
+
+        // The wiped PDA must still be the state PDA of the instruction's create_key
+        let mut moved = closed_state_account();
+        moved.account_id = AccountId::new([97u8; 32]);
+        let err = release_from(moved, claimed_account(&[2u8; 32], [0u8; 32], 100)).unwrap_err();
+        assert_eq!(err, MultisigError::MultisigStateMismatch);
+    }
+
+    #[test]
+    fn test_release_of_account_not_claimed_by_this_multisig_fails() {
+        // Unowned, a wiped PDA (owned, no data), some other owned data
+        for account in [
+            make_account(&[4u8; 32], vec![], false, 100),
+            make_account(&[4u8; 32], vec![], true, 100),
+            make_account(&[4u8; 32], vec![1, 2, 3], true, 100),
+        ] {
+            assert_eq!(release(account).unwrap_err(), MultisigError::NotAMemberAccount);
+        }
+
+        // A member of this multisig can't pass as released by naming another one
+        let err = release(claimed_account(&[2u8; 32], [7u8; 32], 100)).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMemberAccount);
+    }
+
+    #[test]
+    fn test_release_needs_signature() {
+        let accounts = vec![
+            state_account(),
+            claimed_account(&[4u8; 32], [0u8; 32], 100),
+            make_account(&[40u8; 32], vec![], false, 0),
+        ];
+        let err = handle(&accounts, &[0u8; 32]).unwrap_err();
+        assert_eq!(err, MultisigError::MissingSignature);
+    }
+
+    #[test]
+    fn test_new_member_accounts_are_claimed_or_reused() {
+        let actions = vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            ConfigAction::ReplaceMember { old: [1u8; 32], new: [5u8; 32] },
+            ConfigAction::RemoveMember { member: [2u8; 32] },
+        ];
+        // [4] is fresh, [5] was claimed by this multisig before
+        let targets = vec![
+            make_account(&[4u8; 32], vec![], false, 0),
+            claimed_account(&[5u8; 32], [0u8; 32], 0),
+            make_account(&[60u8; 32], vec![], false, 0),
+        ];
+
+        let (post_states, rest) = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &targets).unwrap();
+
+        assert_eq!(post_states.len(), 2);
+        assert!(post_states[0].requires_claim());
+        assert_eq!(record(&post_states[0]), MemberAccountRecord::new([0u8; 32]));
+        assert!(!post_states[1].requires_claim());
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].account_id, AccountId::new([60u8; 32]));
+
+        // Without the first new member's account, none are taken
+        let (post_states, rest) = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &targets[2..]).unwrap();
+        assert!(post_states.is_empty());
+        assert_eq!(rest.len(), 1);
+    }
+
+    #[test]
+    fn test_new_member_accounts_out_of_order_fail() {
+        let actions = vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            ConfigAction::AddMember { new_member: [5u8; 32] },
+        ];
+
+        // The second account isn't the second new member's
+        let targets = vec![make_account(&[4u8; 32], vec![], false, 0), make_account(&[60u8; 32], vec![], false, 0)];
+        let err = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &targets).unwrap_err();
+        assert_eq!(err, MultisigError::MemberAccountMismatch);
+
+        // Some but not all of them
+        let err = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &targets[..1]).unwrap_err();
+        assert_eq!(err, MultisigError::NotEnoughAccounts);
+    }
+
+    #[test]
+    fn test_released_account_is_claimed_again_when_it_signs() {
+        let actions = vec![ConfigAction::AddMember { new_member: [4u8; 32] }];
+        // Released by another multisig, or by this one
+        for create_key in [[7u8; 32], [0u8; 32]] {
+            let mut account = released_account(&[4u8; 32], create_key);
+            let err = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &[account.clone()]).unwrap_err();
+            assert_eq!(err, MultisigError::MissingSignature);
+
+            account.is_authorized = true;
+            let (post_states, _) = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &[account]).unwrap();
+            assert!(!post_states[0].requires_claim());
+            assert_eq!(record(&post_states[0]), MemberAccountRecord::new([0u8; 32]));
+        }
+    }
+
+    #[test]
+    fn test_new_member_account_owned_elsewhere_fails() {
+        let actions = vec![ConfigAction::AddMember { new_member: [4u8; 32] }];
+        let mut foreign = make_account(&[4u8; 32], vec![], false, 0);
+        foreign.account.program_owner = [9u32; 8];
+
+        // Another program's account, a wiped PDA, another multisig's member account
+        for account in [
+            foreign,
+            make_account(&[4u8; 32], vec![], true, 0),
+            claimed_account(&[4u8; 32], [7u8; 32], 0),
+        ] {
+            let err = claim_new_members(&PROGRAM_ID, &[0u8; 32], &actions, &[account]).unwrap_err();
+            assert_eq!(err, MultisigError::AccountAlreadyInitialized);
+        }
+    }
+}
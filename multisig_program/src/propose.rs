@@ -1,59 +1,122 @@
 // Propose handler — creates a new proposal as a separate PDA account.
+// `create_proposal` holds the steps every propose instruction shares;
+// ProposeBatch and the config proposals go through it too.
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership, increment tx_index)
-// - accounts[1]: proposer (must be authorized signer, must be member)
+// - accounts[1]: proposer (must be authorized signer, member with Initiate permission)
 // - accounts[2]: proposal PDA account (must be Account::default() = uninitialized)
+// - accounts[3]: clock account (when the proposal expires, or the multisig is
+//   time-locked with threshold 1)
 
 use nssa_core::account::{Account, AccountWithMetadata};
 use nssa_core::program::{AccountPostState, ChainedCall, InstructionData, ProgramId};
-use multisig_core::{MultisigState, Proposal};
+use multisig_core::{
+    is_multisig_pda_seed, validate_target_accounts, MultisigError, MultisigState, Proposal, PERMISSION_INITIATE,
+    PERMISSION_VOTE,
+};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
     target_program_id: &ProgramId,
     target_instruction_data: &InstructionData,
-    target_account_count: u8,
+    target_accounts: &[[u8; 32]],
     pda_seeds: &[[u8; 32]],
     authorized_indices: &[u8],
-) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
-    assert!(accounts.len() >= 3, "Propose requires multisig_state + proposer + proposal accounts");
+    expiry: u64,
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    validate_target_accounts(target_accounts)?;
+    if authorized_indices.iter().any(|i| *i as usize >= target_accounts.len()) {
+        return Err(MultisigError::AuthorizedIndexOutOfRange);
+    }
+
+    create_proposal(accounts, expiry, proposal_index, |state, proposer_id| {
+        // Calls outside the program policy never reach voters
+        state.check_program(target_program_id)?;
+
+        // PDA seeds must belong to this multisig — a foreign seed would let our members
+        // authorize another multisig's vault in the ChainedCall
+        if !pda_seeds.iter().all(|seed| is_multisig_pda_seed(&state.create_key, seed)) {
+            return Err(MultisigError::ForeignPdaSeed);
+        }
+
+        Ok(Proposal::new(
+            proposal_index,
+            proposer_id,
+            state.create_key,
+            target_program_id.clone(),
+            target_instruction_data.clone(),
+            target_accounts.to_vec(),
+            pda_seeds.to_vec(),
+            authorized_indices.to_vec(),
+        ))
+    })
+}
+
+/// Create a proposal at `proposal_index` — the part every propose instruction
+/// shares. Checks the proposer and the proposal account, then calls `build`
+/// with the current state and the proposer's ID to validate the instruction's
+/// own action and build the proposal. Increments the multisig's tx_index and
+/// returns the post states `[state, proposer, proposal (claimed), (clock)]`.
+pub fn create_proposal(
+    accounts: &[AccountWithMetadata],
+    expiry: u64,
+    proposal_index: u64,
+    build: impl FnOnce(&MultisigState, [u8; 32]) -> Result<Proposal, MultisigError>,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
 
     let multisig_account = &accounts[0];
     let proposer_account = &accounts[1];
     let proposal_account = &accounts[2];
 
-    assert!(proposer_account.is_authorized, "Proposer must sign the transaction");
+    if !proposer_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
 
     // Proposal account must be uninitialized
-    assert!(
-        proposal_account.account == Account::default(),
-        "Proposal account must be uninitialized"
-    );
+    if proposal_account.account != Account::default() {
+        return Err(MultisigError::AccountAlreadyInitialized);
+    }
 
     // Read and update multisig state (increment transaction_index)
-    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let mut state: MultisigState = borsh::from_slice(&state_data)
-        .expect("Failed to deserialize multisig state");
+    let (mut state, program_id) = crate::accounts::load_state(multisig_account)?;
 
     let proposer_id = *proposer_account.account_id.value();
-    assert!(state.is_member(&proposer_id), "Proposer is not a multisig member");
-
-    let proposal_index = state.next_proposal_index();
-
-    // Create the proposal
-    let proposal = Proposal::new(
-        proposal_index,
-        proposer_id,
-        state.create_key,
-        target_program_id.clone(),
-        target_instruction_data.clone(),
-        target_account_count,
-        pda_seeds.to_vec(),
-        authorized_indices.to_vec(),
-    );
-
-    // Serialize updated multisig state (with incremented tx_index)
+    if !state.is_member(&proposer_id) {
+        return Err(MultisigError::NotAMember);
+    }
+    state.require_permission(&proposer_id, PERMISSION_INITIATE)?;
+
+    let mut proposal = build(&state, proposer_id)?;
+
+    // Two proposers who read the same counter derive the same proposal PDA;
+    // only the first one's proposal can take it
+    if proposal_index != state.next_proposal_index() {
+        return Err(MultisigError::ProposalIndexConflict);
+    }
+    crate::accounts::check_new_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
+    let clock_account = accounts.get(3);
+
+    // Track which vaults the multisig uses (for vault queries)
+    for vault_index in proposal.vault_indices() {
+        state.record_vault(vault_index);
+    }
+
+    // Proposers without Vote permission (e.g. a bot key) don't auto-approve
+    if !state.has_permission(&proposer_id, PERMISSION_VOTE) {
+        proposal.approved.clear();
+    }
+
+    proposal.expires_at = crate::clock::expires_at(&mut state, expiry, clock_account)?;
+
+    // The proposer's auto-approval reaches threshold when M = 1
+    crate::clock::record_threshold_reached(&mut state, &mut proposal, clock_account)?;
+
+    // Serialize updated multisig state (with incremented tx_index and the clock time read)
     let state_bytes = borsh::to_vec(&state).unwrap();
     let mut multisig_post = multisig_account.account.clone();
     multisig_post.data = state_bytes.try_into().unwrap();
@@ -65,21 +128,24 @@ pub fn handle(
 
     let proposer_post = proposer_account.account.clone();
 
-    (
-        vec![
-            AccountPostState::new(multisig_post),
-            AccountPostState::new(proposer_post),
-            AccountPostState::new_claimed(proposal_post),
-        ],
-        vec![],
-    )
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(proposer_post),
+        AccountPostState::new_claimed(proposal_post),
+    ];
+    if let Some(clock) = clock_account {
+        post_states.push(AccountPostState::new(clock.account.clone()));
+    }
+
+    Ok((post_states, vec![]))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use nssa_core::account::{Account, AccountId};
-    use multisig_core::MultisigState;
+    use multisig_core::{MultisigState, MAX_BATCH_ACCOUNTS};
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
 
     fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
         let mut account = Account::default();
@@ -91,19 +157,36 @@ mod tests {
         }
     }
 
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
     fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
         borsh::to_vec(&MultisigState::new([0u8; 32], threshold, members)).unwrap()
     }
 
+    fn make_propose_accounts() -> Vec<AccountWithMetadata> {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        vec![
+            make_account(&state_id(), make_state(2, members), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ]
+    }
+
     #[test]
     fn test_propose_creates_proposal_and_increments_index() {
         let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
         let state_data = make_state(2, members.clone());
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false), // multisig state
+            make_account(&state_id(), state_data, false), // multisig state
             make_account(&[1u8; 32], vec![], true),         // proposer (member)
-            make_account(&[20u8; 32], vec![], false),        // proposal PDA (uninitialized)
+            make_account(&proposal_id(1), vec![], false),        // proposal PDA (uninitialized)
         ];
 
         let program_id: ProgramId = [42u32; 8];
@@ -111,10 +194,12 @@ mod tests {
             &accounts,
             &program_id,
             &vec![0u32],
-            1,
-            &[],
+            &[[30u8; 32]],
             &[],
-        );
+            &[0],
+            0,
+            1,
+        ).unwrap();
 
         assert!(chained.is_empty());
         assert_eq!(post_states.len(), 3);
@@ -133,37 +218,246 @@ mod tests {
         assert_eq!(proposal.proposer, [1u8; 32]);
         assert_eq!(proposal.approved, vec![[1u8; 32]]);
         assert_eq!(proposal.status, multisig_core::ProposalStatus::Active);
+        assert_eq!(proposal.target_accounts, vec![[30u8; 32]]);
+    }
+
+    #[test]
+    fn test_propose_authorized_index_out_of_range_fails() {
+        let members = vec![[1u8; 32], [2u8; 32]];
+        let state_data = make_state(2, members);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        // Only one target account, but index 1 is marked authorized
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[1], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AuthorizedIndexOutOfRange);
+    }
+
+    #[test]
+    fn test_propose_too_many_or_repeated_target_accounts_fail() {
+        let program_id: ProgramId = [42u32; 8];
+
+        let targets: Vec<[u8; 32]> = (0..=MAX_BATCH_ACCOUNTS as u8).map(|i| [100 + i; 32]).collect();
+        let err = handle(&make_propose_accounts(), &program_id, &vec![0u32], &targets, &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::TooManyTargetAccounts);
+
+        // The same account twice would be refused by LEZ at every Execute
+        let targets = [[30u8; 32], [31u8; 32], [30u8; 32]];
+        let err = handle(&make_propose_accounts(), &program_id, &vec![0u32], &targets, &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::DuplicateTargetAccount);
+    }
+
+    #[test]
+    fn test_propose_with_own_vault_seed() {
+        let accounts = make_propose_accounts();
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 0);
+
+        let program_id: ProgramId = [42u32; 8];
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[vault_seed], &[0], 0, 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.pda_seeds, vec![vault_seed]);
+    }
+
+    #[test]
+    fn test_propose_with_indexed_vault_records_vault() {
+        let accounts = make_propose_accounts();
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 3);
+
+        let program_id: ProgramId = [42u32; 8];
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[vault_seed], &[0], 0, 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.vault_indices(), vec![3]);
+        let state: MultisigState = borsh::from_slice(
+            &Vec::from(post_states[0].account().data.clone())
+        ).unwrap();
+        assert_eq!(state.vaults, vec![3]);
+    }
+
+    #[test]
+    fn test_propose_other_multisig_vault_seed_fails() {
+        let accounts = make_propose_accounts();
+        // Vault seed of a different multisig (create_key [7; 32]) under the same program
+        let foreign_seed = multisig_core::vault_pda_seed_bytes(&[7u8; 32], 0);
+
+        let program_id: ProgramId = [42u32; 8];
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[foreign_seed], &[0], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ForeignPdaSeed);
+    }
+
+    #[test]
+    fn test_propose_arbitrary_seed_fails() {
+        let accounts = make_propose_accounts();
+
+        let program_id: ProgramId = [42u32; 8];
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[[0xAAu8; 32]], &[0], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ForeignPdaSeed);
     }
 
     #[test]
-    #[should_panic(expected = "not a multisig member")]
     fn test_propose_non_member_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[99u8; 32], vec![], true), // NOT a member
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let program_id: ProgramId = [42u32; 8];
-        handle(&accounts, &program_id, &vec![0u32], 1, &[], &[]);
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
     }
 
     #[test]
-    #[should_panic(expected = "must sign")]
     fn test_propose_unsigned_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], false), // not authorized
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let program_id: ProgramId = [42u32; 8];
-        handle(&accounts, &program_id, &vec![0u32], 1, &[], &[]);
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingSignature);
+    }
+
+    #[test]
+    fn test_propose_with_taken_index_fails() {
+        // Proposal 1 was created after the proposer read the counter
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.transaction_index = 1;
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let program_id: ProgramId = [42u32; 8];
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProposalIndexConflict);
+
+        // Retrying with the next index succeeds
+        let mut accounts = accounts;
+        accounts[2] = make_account(&proposal_id(2), vec![], false);
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 2).unwrap();
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.index, 2);
+    }
+
+    fn make_expiring_accounts(default_expiry: u64, clock: Option<[u8; 32]>) -> Vec<AccountWithMetadata> {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.clock_account = [77u8; 32];
+        state.default_expiry = default_expiry;
+        let mut accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+        if let Some(id) = clock {
+            accounts.push(make_account(&id, 1_000u64.to_le_bytes().to_vec(), false));
+        }
+        accounts
+    }
+
+    #[test]
+    fn test_propose_with_expiry_sets_expires_at() {
+        let accounts = make_expiring_accounts(0, Some([77u8; 32]));
+
+        let program_id: ProgramId = [42u32; 8];
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 500, 1).unwrap();
+
+        assert_eq!(post_states.len(), 4);
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.expires_at, Some(1_500));
+    }
+
+    #[test]
+    fn test_propose_uses_default_expiry() {
+        let accounts = make_expiring_accounts(200, Some([77u8; 32]));
+
+        let program_id: ProgramId = [42u32; 8];
+        let (post_states, _) = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.expires_at, Some(1_200));
+    }
+
+    #[test]
+    fn test_propose_with_expiry_without_clock_fails() {
+        let accounts = make_expiring_accounts(200, None);
+
+        let program_id: ProgramId = [42u32; 8];
+        let err = handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingClockAccount);
+    }
+
+    fn make_bot_accounts(proposer: [u8; 32]) -> Vec<AccountWithMetadata> {
+        // [3; 32] is a bot key: Initiate | Execute, no Vote
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.permissions = vec![
+            multisig_core::PERMISSION_ALL,
+            multisig_core::PERMISSION_VOTE,
+            PERMISSION_INITIATE | multisig_core::PERMISSION_EXECUTE,
+        ];
+        vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&proposer, vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ]
+    }
+
+    #[test]
+    fn test_propose_by_non_voter_has_no_approvals() {
+        let program_id: ProgramId = [42u32; 8];
+        let (post_states, _) = handle(&make_bot_accounts([3u8; 32]), &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.proposer, [3u8; 32]);
+        assert!(proposal.approved.is_empty());
+    }
+
+    #[test]
+    fn test_propose_without_initiate_permission_fails() {
+        let program_id: ProgramId = [42u32; 8];
+        let err = handle(&make_bot_accounts([2u8; 32]), &program_id, &vec![0u32], &[[30u8; 32]], &[], &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MissingPermission);
+    }
+
+    #[test]
+    fn test_propose_respects_program_policy() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.allowed_programs = vec![[42u32; 8]];
+        state.denied_programs = vec![[66u32; 8]];
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+        let propose = |program_id: ProgramId| handle(&accounts, &program_id, &vec![0u32], &[[30u8; 32]], &[], &[0], 0, 1);
+
+        assert!(propose([42u32; 8]).is_ok());
+        // Not on the allowlist
+        assert_eq!(propose([43u32; 8]).unwrap_err(), MultisigError::ProgramNotAllowed);
+        // Denied
+        assert_eq!(propose([66u32; 8]).unwrap_err(), MultisigError::ProgramNotAllowed);
     }
 }
@@ -0,0 +1,211 @@
+// ProposeBatch handler — creates a proposal holding an ordered list of calls.
+//
+// Execute emits one ChainedCall per call, in order. Calls on disjoint accounts
+// run in one transaction and succeed or fail as a whole; a call on an account
+// an earlier call uses, e.g. a second payment from the same vault, runs in a
+// later Execute against the account's new state (see batch_step_len).
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership, increment tx_index)
+// - accounts[1]: proposer (must be authorized signer, member with Initiate permission)
+// - accounts[2]: proposal PDA account (must be Account::default() = uninitialized)
+// - accounts[3]: clock account (when the proposal expires, or the multisig is
+//   time-locked with threshold 1)
+
+use nssa_core::account::AccountWithMetadata;
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{
+    batch_accounts, is_multisig_pda_seed, validate_target_accounts, MultisigError, Proposal,
+    ProposalCall, MAX_BATCH_ACCOUNTS, MAX_BATCH_CALLS,
+};
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    calls: &[ProposalCall],
+    expiry: u64,
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if calls.is_empty() {
+        return Err(MultisigError::EmptyBatch);
+    }
+    if calls.len() > MAX_BATCH_CALLS || batch_accounts(calls).len() > MAX_BATCH_ACCOUNTS {
+        return Err(MultisigError::BatchTooLarge);
+    }
+    for call in calls {
+        validate_target_accounts(&call.target_accounts)?;
+        if call.authorized_indices.iter().any(|i| *i as usize >= call.target_accounts.len()) {
+            return Err(MultisigError::AuthorizedIndexOutOfRange);
+        }
+    }
+
+    crate::propose::create_proposal(accounts, expiry, proposal_index, |state, proposer_id| {
+        // Calls outside the program policy never reach voters
+        for call in calls {
+            state.check_program(&call.target_program_id)?;
+        }
+
+        // Every call's PDA seeds must belong to this multisig
+        let seeds_ok = calls
+            .iter()
+            .flat_map(|c| c.pda_seeds.iter())
+            .all(|seed| is_multisig_pda_seed(&state.create_key, seed));
+        if !seeds_ok {
+            return Err(MultisigError::ForeignPdaSeed);
+        }
+
+        Ok(Proposal::new_batch(proposal_index, proposer_id, state.create_key, calls.to_vec()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::{Account, AccountId};
+    use nssa_core::program::ProgramId;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda, MultisigState};
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
+    fn make_accounts() -> Vec<AccountWithMetadata> {
+        let state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ]
+    }
+
+    fn make_call(target_accounts: Vec<[u8; 32]>, pda_seeds: Vec<[u8; 32]>) -> ProposalCall {
+        let program_id: ProgramId = [42u32; 8];
+        ProposalCall {
+            target_program_id: program_id,
+            target_instruction_data: vec![0u32],
+            target_accounts,
+            pda_seeds,
+            authorized_indices: vec![0],
+        }
+    }
+
+    #[test]
+    fn test_propose_batch_stores_calls() {
+        let vault_seed = |i| multisig_core::vault_pda_seed_bytes(&[0u8; 32], i);
+        let calls = vec![
+            make_call(vec![[30u8; 32], [31u8; 32]], vec![vault_seed(0)]),
+            make_call(vec![[33u8; 32], [32u8; 32]], vec![vault_seed(1)]),
+        ];
+
+        let (post_states, chained) = handle(&make_accounts(), &calls, 0, 1).unwrap();
+
+        assert!(chained.is_empty());
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.calls, calls);
+        assert_eq!(proposal.approved, vec![[1u8; 32]]);
+        assert_eq!(proposal.execute_accounts(), vec![[30u8; 32], [31u8; 32], [33u8; 32], [32u8; 32]]);
+    }
+
+    #[test]
+    fn test_propose_batch_paying_several_from_one_vault() {
+        let vault_seed = multisig_core::vault_pda_seed_bytes(&[0u8; 32], 0);
+        let calls = vec![
+            make_call(vec![[30u8; 32], [31u8; 32]], vec![vault_seed]),
+            make_call(vec![[30u8; 32], [32u8; 32]], vec![vault_seed]),
+        ];
+
+        let (post_states, _) = handle(&make_accounts(), &calls, 0, 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.calls, calls);
+        // The second payment waits for the next Execute
+        assert_eq!(proposal.execute_accounts(), vec![[30u8; 32], [31u8; 32]]);
+    }
+
+    #[test]
+    fn test_propose_empty_batch_fails() {
+        let err = handle(&make_accounts(), &[], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::EmptyBatch);
+    }
+
+    #[test]
+    fn test_propose_batch_too_many_calls_fails() {
+        let calls = vec![make_call(vec![[30u8; 32]], vec![]); MAX_BATCH_CALLS + 1];
+
+        let err = handle(&make_accounts(), &calls, 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::BatchTooLarge);
+    }
+
+    #[test]
+    fn test_propose_batch_too_many_accounts_fails() {
+        let targets: Vec<[u8; 32]> = (0..=MAX_BATCH_ACCOUNTS as u8).map(|i| [100 + i; 32]).collect();
+        let calls = vec![make_call(targets, vec![])];
+
+        let err = handle(&make_accounts(), &calls, 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::BatchTooLarge);
+    }
+
+    #[test]
+    fn test_propose_batch_call_repeating_account_fails() {
+        // Calls may share accounts, but one call can't name an account twice
+        let calls = vec![
+            make_call(vec![[30u8; 32], [31u8; 32]], vec![]),
+            make_call(vec![[32u8; 32], [32u8; 32]], vec![]),
+        ];
+
+        let err = handle(&make_accounts(), &calls, 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::DuplicateTargetAccount);
+    }
+
+    #[test]
+    fn test_propose_batch_authorized_index_out_of_range_fails() {
+        let mut call = make_call(vec![[30u8; 32]], vec![]);
+        call.authorized_indices = vec![1];
+
+        let err = handle(&make_accounts(), &[call], 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AuthorizedIndexOutOfRange);
+    }
+
+    #[test]
+    fn test_propose_batch_foreign_seed_fails() {
+        let foreign_seed = multisig_core::vault_pda_seed_bytes(&[7u8; 32], 0);
+        let calls = vec![
+            make_call(vec![[30u8; 32]], vec![]),
+            make_call(vec![[31u8; 32]], vec![foreign_seed]),
+        ];
+
+        let err = handle(&make_accounts(), &calls, 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ForeignPdaSeed);
+    }
+
+    #[test]
+    fn test_propose_batch_denied_program_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32]]);
+        state.denied_programs = vec![[42u32; 8]];
+        let mut accounts = make_accounts();
+        accounts[0] = make_account(&state_id(), borsh::to_vec(&state).unwrap(), false);
+        let mut allowed = make_call(vec![[30u8; 32]], vec![]);
+        allowed.target_program_id = [43u32; 8];
+        let calls = vec![allowed, make_call(vec![[31u8; 32]], vec![])];
+
+        let err = handle(&accounts, &calls, 0, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ProgramNotAllowed);
+    }
+}
@@ -1,81 +1,129 @@
-// ProposeConfig handler — creates a config change proposal (add/remove member, change threshold).
+// ProposeConfig handler — creates a config change proposal (add/remove/replace member, change threshold, class thresholds, weights or permissions, program policy, set time-lock, add/remove spending limit).
+// `handle_batch` creates one proposal carrying several actions, applied together on execute.
+// CloseMultisig has no instruction of its own and is proposed through it.
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership, increment tx_index)
-// - accounts[1]: proposer (must be authorized signer, must be member)
+// - accounts[1]: proposer (must be authorized signer, member with Initiate permission)
 // - accounts[2]: proposal PDA account (must be Account::default() = uninitialized)
+// - accounts[3]: clock account (when the multisig has a default_expiry, or is
+//   time-locked with threshold 1)
+//
+// Config proposals always take the multisig's default_expiry.
 
-use nssa_core::account::{Account, AccountWithMetadata};
+use nssa_core::account::AccountWithMetadata;
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{ConfigAction, MultisigState, Proposal};
+use multisig_core::{
+    ConfigAction, MultisigError, MultisigState, Proposal, MAX_CONFIG_BATCH_ACTIONS, PERMISSION_ALL,
+};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
     config_action: ConfigAction,
-) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
-    assert!(accounts.len() >= 3, "ProposeConfig requires multisig_state + proposer + proposal accounts");
-
-    let multisig_account = &accounts[0];
-    let proposer_account = &accounts[1];
-    let proposal_account = &accounts[2];
-
-    assert!(proposer_account.is_authorized, "Proposer must sign the transaction");
-
-    assert!(
-        proposal_account.account == Account::default(),
-        "Proposal account must be uninitialized"
-    );
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    propose(accounts, vec![config_action], false, proposal_index)
+}
 
-    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let mut state: MultisigState = borsh::from_slice(&state_data)
-        .expect("Failed to deserialize multisig state");
+/// Propose several config actions as one proposal.
+pub fn handle_batch(
+    accounts: &[AccountWithMetadata],
+    config_actions: Vec<ConfigAction>,
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if config_actions.is_empty() {
+        return Err(MultisigError::EmptyBatch);
+    }
+    if config_actions.len() > MAX_CONFIG_BATCH_ACTIONS {
+        return Err(MultisigError::BatchTooLarge);
+    }
+    propose(accounts, config_actions, true, proposal_index)
+}
 
-    let proposer_id = *proposer_account.account_id.value();
-    assert!(state.is_member(&proposer_id), "Proposer is not a multisig member");
+fn propose(
+    accounts: &[AccountWithMetadata],
+    mut config_actions: Vec<ConfigAction>,
+    batch: bool,
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    crate::propose::create_proposal(accounts, 0, proposal_index, move |state, proposer_id| {
+        if batch {
+            // A batch must leave a valid config when applied to the current one
+            state.clone().apply_config(&config_actions)?;
+            Ok(Proposal::new_config_batch(proposal_index, proposer_id, state.create_key, config_actions))
+        } else {
+            validate_action(state, &config_actions[0])?;
+            Ok(Proposal::new_config(proposal_index, proposer_id, state.create_key, config_actions.remove(0)))
+        }
+    })
+}
 
-    // Basic validation at propose time
-    match &config_action {
+/// Basic validation of a single action at propose time. Membership and
+/// threshold may change before execute, so the full check happens there.
+fn validate_action(state: &MultisigState, config_action: &ConfigAction) -> Result<(), MultisigError> {
+    match config_action {
         ConfigAction::AddMember { new_member } => {
-            assert!(!state.is_member(new_member), "Account is already a member");
-            assert!(state.member_count < 10, "Maximum 10 members");
+            if state.is_member(new_member) {
+                return Err(MultisigError::AlreadyMember);
+            }
+            if state.member_count >= 10 {
+                return Err(MultisigError::TooManyMembers);
+            }
         }
         ConfigAction::RemoveMember { member } => {
-            assert!(state.is_member(member), "Account is not a member");
+            if !state.is_member(member) {
+                return Err(MultisigError::MemberNotFound);
+            }
         }
         ConfigAction::ChangeThreshold { new_threshold } => {
-            assert!(*new_threshold >= 1, "Threshold must be at least 1");
+            if *new_threshold < 1 {
+                return Err(MultisigError::InvalidThreshold);
+            }
+        }
+        ConfigAction::SetTimeLock { time_lock, clock_account } => {
+            let mut timed = state.clone();
+            timed.time_lock = *time_lock;
+            timed.clock_account = *clock_account;
+            timed.validate_clock()?;
         }
+        ConfigAction::SetWeight { member, weight } => {
+            if !state.is_member(member) {
+                return Err(MultisigError::MemberNotFound);
+            }
+            if *weight < 1 {
+                return Err(MultisigError::InvalidWeights);
+            }
+        }
+        ConfigAction::SetPermissions { member, permissions } => {
+            if !state.is_member(member) {
+                return Err(MultisigError::MemberNotFound);
+            }
+            if permissions & !PERMISSION_ALL != 0 {
+                return Err(MultisigError::InvalidPermissions);
+            }
+        }
+        ConfigAction::ReplaceMember { old, new } => {
+            if !state.is_member(old) {
+                return Err(MultisigError::MemberNotFound);
+            }
+            if state.is_member(new) {
+                return Err(MultisigError::AlreadyMember);
+            }
+        }
+        ConfigAction::AddSpendingLimit { vault_index, token_program_id, amount, members, .. } => {
+            state.validate_spending_limit(*vault_index, *amount, members)?;
+            state.check_program(token_program_id)?;
+        }
+        ConfigAction::RemoveSpendingLimit { .. } => {}
+        ConfigAction::SetClassThreshold { threshold, .. } => {
+            if *threshold as u32 > state.total_weight() {
+                return Err(MultisigError::ThresholdExceedsMemberCount);
+            }
+        }
+        ConfigAction::SetProgramPolicy { .. } => {}
+        ConfigAction::CloseMultisig => {}
     }
-
-    let proposal_index = state.next_proposal_index();
-
-    let proposal = Proposal::new_config(
-        proposal_index,
-        proposer_id,
-        state.create_key,
-        config_action,
-    );
-
-    // Serialize updated multisig state
-    let state_bytes = borsh::to_vec(&state).unwrap();
-    let mut multisig_post = multisig_account.account.clone();
-    multisig_post.data = state_bytes.try_into().unwrap();
-
-    // Serialize proposal into new account
-    let proposal_bytes = borsh::to_vec(&proposal).unwrap();
-    let mut proposal_post = Account::default();
-    proposal_post.data = proposal_bytes.try_into().unwrap();
-
-    let proposer_post = proposer_account.account.clone();
-
-    (
-        vec![
-            AccountPostState::new(multisig_post),
-            AccountPostState::new(proposer_post),
-            AccountPostState::new_claimed(proposal_post),
-        ],
-        vec![],
-    )
+    Ok(())
 }
 
 #[cfg(test)]
@@ -83,6 +131,7 @@ mod tests {
     use super::*;
     use nssa_core::account::{Account, AccountId};
     use multisig_core::MultisigState;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
 
     fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
         let mut account = Account::default();
@@ -94,6 +143,14 @@ mod tests {
         }
     }
 
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
     fn make_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
         borsh::to_vec(&MultisigState::new([0u8; 32], threshold, members)).unwrap()
     }
@@ -104,13 +161,13 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let action = ConfigAction::AddMember { new_member: [4u8; 32] };
-        let (post_states, chained) = handle(&accounts, action);
+        let (post_states, chained) = handle(&accounts, action, 1).unwrap();
 
         assert!(chained.is_empty());
         assert_eq!(post_states.len(), 3);
@@ -119,7 +176,7 @@ mod tests {
             &Vec::from(post_states[2].account().data.clone())
         ).unwrap();
         assert_eq!(proposal.config_action, Some(ConfigAction::AddMember { new_member: [4u8; 32] }));
-        assert_eq!(proposal.target_account_count, 0);
+        assert!(proposal.target_accounts.is_empty());
     }
 
     #[test]
@@ -128,13 +185,13 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let action = ConfigAction::RemoveMember { member: [2u8; 32] };
-        let (post_states, chained) = handle(&accounts, action);
+        let (post_states, chained) = handle(&accounts, action, 1).unwrap();
 
         assert!(chained.is_empty());
         let proposal: Proposal = borsh::from_slice(
@@ -149,13 +206,13 @@ mod tests {
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
         let action = ConfigAction::ChangeThreshold { new_threshold: 3 };
-        let (post_states, _) = handle(&accounts, action);
+        let (post_states, _) = handle(&accounts, action, 1).unwrap();
 
         let proposal: Proposal = borsh::from_slice(
             &Vec::from(post_states[2].account().data.clone())
@@ -164,62 +221,237 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "already a member")]
     fn test_propose_add_existing_member_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::AddMember { new_member: [2u8; 32] });
+        let err = handle(&accounts, ConfigAction::AddMember { new_member: [2u8; 32] }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyMember);
     }
 
     #[test]
-    #[should_panic(expected = "not a member")]
     fn test_propose_remove_non_member_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::RemoveMember { member: [99u8; 32] });
+        let err = handle(&accounts, ConfigAction::RemoveMember { member: [99u8; 32] }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MemberNotFound);
     }
 
     #[test]
-    #[should_panic(expected = "at least 1")]
     fn test_propose_change_threshold_zero_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[1u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::ChangeThreshold { new_threshold: 0 });
+        let err = handle(&accounts, ConfigAction::ChangeThreshold { new_threshold: 0 }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidThreshold);
     }
 
     #[test]
-    #[should_panic(expected = "not a multisig member")]
     fn test_propose_config_non_member_fails() {
         let members = vec![[1u8; 32], [2u8; 32]];
         let state_data = make_state(2, members);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[99u8; 32], vec![], true),
-            make_account(&[20u8; 32], vec![], false),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let err = handle(&accounts, ConfigAction::AddMember { new_member: [4u8; 32] }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+
+    #[test]
+    fn test_propose_config_batch() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+        let actions = vec![
+            ConfigAction::RemoveMember { member: [2u8; 32] },
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+        ];
+
+        let (post_states, _) = handle_batch(&accounts, actions.clone(), 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.config_action, None);
+        assert_eq!(proposal.config_batch, actions);
+        assert_eq!(proposal.config_actions(), actions);
+    }
+
+    #[test]
+    fn test_propose_config_batch_invalid_result_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        // Leaves one member with threshold 2
+        let err = handle_batch(&accounts, vec![ConfigAction::RemoveMember { member: [2u8; 32] }], 1).unwrap_err();
+        assert_eq!(err, MultisigError::RemovalBreaksThreshold);
+    }
+
+    #[test]
+    fn test_propose_empty_config_batch_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let err = handle_batch(&accounts, vec![], 1).unwrap_err();
+        assert_eq!(err, MultisigError::EmptyBatch);
+    }
+
+    #[test]
+    fn test_propose_replace_member_at_member_cap() {
+        // 10 members: AddMember would hit the cap, ReplaceMember doesn't change N
+        let members: Vec<[u8; 32]> = (1..=10u8).map(|i| [i; 32]).collect();
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, members), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let action = ConfigAction::ReplaceMember { old: [2u8; 32], new: [42u8; 32] };
+        let (post_states, _) = handle(&accounts, action.clone(), 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(
+            &Vec::from(post_states[2].account().data.clone())
+        ).unwrap();
+        assert_eq!(proposal.config_action, Some(action));
+    }
+
+    #[test]
+    fn test_propose_replace_member_invalid_keys_fail() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        let err = handle(&accounts, ConfigAction::ReplaceMember { old: [99u8; 32], new: [4u8; 32] }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::MemberNotFound);
+        let err = handle(&accounts, ConfigAction::ReplaceMember { old: [2u8; 32], new: [1u8; 32] }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyMember);
+    }
+
+    #[test]
+    fn test_propose_batch_adding_member_twice_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+
+        // The second add would list [4] twice and count its weight twice
+        let actions = vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+        ];
+        let err = handle_batch(&accounts, actions, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyMember);
+        let actions = vec![
+            ConfigAction::AddMember { new_member: [4u8; 32] },
+            ConfigAction::ReplaceMember { old: [2u8; 32], new: [4u8; 32] },
+        ];
+        let err = handle_batch(&accounts, actions, 1).unwrap_err();
+        assert_eq!(err, MultisigError::AlreadyMember);
+    }
+
+    #[test]
+    fn test_propose_add_spending_limit_invalid_params_fail() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+        let action = |amount: u128, members: Vec<[u8; 32]>| ConfigAction::AddSpendingLimit {
+            limit_key: [5u8; 32],
+            vault_index: 0,
+            token_program_id: [42u32; 8],
+            amount,
+            period: 100,
+            members,
+        };
+
+        let err = handle(&accounts, action(0, vec![[2u8; 32]]), 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidSpendingLimitParams);
+        let err = handle(&accounts, action(50, vec![]), 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidSpendingLimitParams);
+        let err = handle(&accounts, action(50, vec![[99u8; 32]]), 1).unwrap_err();
+        assert_eq!(err, MultisigError::MemberNotFound);
+        assert!(handle(&accounts, action(50, vec![[2u8; 32]]), 1).is_ok());
+
+        let err = handle(&accounts, ConfigAction::AddSpendingLimit {
+            limit_key: [5u8; 32],
+            vault_index: multisig_core::MAX_VAULTS,
+            token_program_id: [42u32; 8],
+            amount: 50,
+            period: 100,
+            members: vec![[2u8; 32]],
+        }, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidVaultIndex);
+    }
+
+    #[test]
+    fn test_propose_class_threshold_above_total_weight_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
+        ];
+        let action = |threshold: u8| ConfigAction::SetClassThreshold {
+            class: multisig_core::ActionClass::Config,
+            threshold,
+        };
+
+        let err = handle(&accounts, action(4), 1).unwrap_err();
+        assert_eq!(err, MultisigError::ThresholdExceedsMemberCount);
+        assert!(handle(&accounts, action(3), 1).is_ok());
+    }
+
+    #[test]
+    fn test_propose_time_lock_without_clock_fails() {
+        let accounts = vec![
+            make_account(&state_id(), make_state(2, vec![[1u8; 32], [2u8; 32]]), false),
+            make_account(&[1u8; 32], vec![], true),
+            make_account(&proposal_id(1), vec![], false),
         ];
 
-        handle(&accounts, ConfigAction::AddMember { new_member: [4u8; 32] });
+        let action = ConfigAction::SetTimeLock { time_lock: 600, clock_account: [0u8; 32] };
+        let err = handle(&accounts, action, 1).unwrap_err();
+        assert_eq!(err, MultisigError::ClockAccountNotSet);
+        // Turning the time-lock off needs no clock
+        let action = ConfigAction::SetTimeLock { time_lock: 0, clock_account: [0u8; 32] };
+        assert!(handle(&accounts, action, 1).is_ok());
+        // A member's account is no clock
+        let action = ConfigAction::SetTimeLock { time_lock: 600, clock_account: [2u8; 32] };
+        let err = handle(&accounts, action, 1).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidClockConfig);
     }
 }
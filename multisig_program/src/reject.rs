@@ -1,49 +1,75 @@
-// Reject handler — any member rejects an existing proposal
+// Reject handler — a member with Vote permission rejects an existing proposal
 //
 // Expected accounts:
 // - accounts[0]: multisig_state PDA (read membership/threshold)
 // - accounts[1]: rejector account (must be authorized = is a signer)
 // - accounts[2]: proposal PDA account (owned by multisig program)
+// - accounts[3]: clock account (optional; an expired proposal is then marked Expired
+//   instead of recording the rejection)
+//
+// Approve and Execute refuse an expired proposal but can't write its status,
+// so marking it Expired is left to Reject. Any signer may do that, member or
+// not: it records no vote, and only the clock decides the outcome.
 
 use nssa_core::account::AccountWithMetadata;
 use nssa_core::program::{AccountPostState, ChainedCall};
-use multisig_core::{MultisigState, Proposal, ProposalStatus};
+use multisig_core::{MultisigError, Proposal, ProposalStatus, PERMISSION_VOTE};
 
 pub fn handle(
     accounts: &[AccountWithMetadata],
-    _proposal_index: u64,
-) -> (Vec<AccountPostState>, Vec<ChainedCall>) {
-    assert!(accounts.len() >= 3, "Reject requires multisig_state + rejector + proposal accounts");
+    proposal_index: u64,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
 
     let multisig_account = &accounts[0];
     let rejector_account = &accounts[1];
     let proposal_account = &accounts[2];
 
-    assert!(rejector_account.is_authorized, "Rejector must sign the transaction");
-
-    let state_data: Vec<u8> = multisig_account.account.data.clone().into();
-    let state: MultisigState = borsh::from_slice(&state_data)
-        .expect("Failed to deserialize multisig state");
-
-    let rejector_id = *rejector_account.account_id.value();
-    assert!(state.is_member(&rejector_id), "Rejector is not a multisig member");
+    if !rejector_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
 
-    let threshold = state.threshold;
-    let member_count = state.member_count;
+    let (mut state, program_id) = crate::accounts::load_state(multisig_account)?;
 
     // Read and update proposal
+    crate::accounts::check_proposal(proposal_account, &program_id, &state.create_key, proposal_index)?;
     let proposal_data: Vec<u8> = proposal_account.account.data.clone().into();
     let mut proposal: Proposal = borsh::from_slice(&proposal_data)
-        .expect("Failed to deserialize proposal");
+        .map_err(|_| MultisigError::InvalidProposal)?;
+
+    if proposal.multisig_create_key != state.create_key {
+        return Err(MultisigError::ProposalMultisigMismatch);
+    }
+    if proposal.status != ProposalStatus::Active {
+        return Err(MultisigError::ProposalNotActive);
+    }
+
+    let clock_account = accounts.get(3);
+    if clock_account.is_some() && crate::clock::is_expired(&mut state, &proposal, clock_account)? {
+        proposal.status = ProposalStatus::Expired;
+    } else {
+        let rejector_id = *rejector_account.account_id.value();
+        if !state.is_member(&rejector_id) {
+            return Err(MultisigError::NotAMember);
+        }
+        state.require_permission(&rejector_id, PERMISSION_VOTE)?;
 
-    assert_eq!(proposal.multisig_create_key, state.create_key, "Proposal does not belong to this multisig");
-    assert_eq!(proposal.status, ProposalStatus::Active, "Proposal is not active");
+        let is_new = proposal.reject(rejector_id);
+        if !is_new {
+            return Err(MultisigError::AlreadyRejected);
+        }
 
-    let is_new = proposal.reject(rejector_id);
-    assert!(is_new, "Member has already rejected this proposal");
+        // A switched vote can drop the proposal back below threshold; the
+        // time-lock restarts if it reaches threshold again
+        if !proposal.has_threshold(&state) {
+            proposal.threshold_reached_at = None;
+        }
 
-    if proposal.is_dead(threshold, member_count) {
-        proposal.status = ProposalStatus::Rejected;
+        if proposal.is_dead(&state) {
+            proposal.status = ProposalStatus::Rejected;
+        }
     }
 
     // Write back proposal
@@ -51,17 +77,21 @@ pub fn handle(
     let mut proposal_post = proposal_account.account.clone();
     proposal_post.data = proposal_bytes.try_into().unwrap();
 
-    let multisig_post = multisig_account.account.clone();
+    // The state records the clock time read, if any
+    let mut multisig_post = multisig_account.account.clone();
+    multisig_post.data = borsh::to_vec(&state).unwrap().try_into().unwrap();
     let rejector_post = rejector_account.account.clone();
 
-    (
-        vec![
-            AccountPostState::new(multisig_post),
-            AccountPostState::new(rejector_post),
-            AccountPostState::new(proposal_post),
-        ],
-        vec![],
-    )
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(rejector_post),
+        AccountPostState::new(proposal_post),
+    ];
+    if let Some(clock) = clock_account {
+        post_states.push(AccountPostState::new(clock.account.clone()));
+    }
+
+    Ok((post_states, vec![]))
 }
 
 #[cfg(test)]
@@ -70,6 +100,7 @@ mod tests {
     use nssa_core::account::{Account, AccountId};
     use nssa_core::program::ProgramId;
     use multisig_core::MultisigState;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda};
 
     fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
         let mut account = Account::default();
@@ -81,6 +112,14 @@ mod tests {
         }
     }
 
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&[0u32; 8], &[0u8; 32]).value()
+    }
+
+    fn proposal_id(index: u64) -> [u8; 32] {
+        *compute_proposal_pda(&[0u32; 8], &[0u8; 32], index).value()
+    }
+
     fn make_multisig_state(threshold: u8, members: Vec<[u8; 32]>) -> Vec<u8> {
         let mut state = MultisigState::new([0u8; 32], threshold, members);
         state.transaction_index = 1;
@@ -95,7 +134,7 @@ mod tests {
             [0u8; 32],
             fake_program_id,
             vec![0u32],
-            1,
+            vec![[30u8; 32]],
             vec![],
             vec![],
         );
@@ -109,12 +148,12 @@ mod tests {
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
             make_account(&[2u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), proposal_data, false),
         ];
 
-        let (post_states, _) = handle(&accounts, 1);
+        let (post_states, _) = handle(&accounts, 1).unwrap();
 
         let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
         assert_eq!(proposal.rejected.len(), 1);
@@ -128,13 +167,136 @@ mod tests {
         let proposal_data = make_proposal([1u8; 32]);
 
         let accounts = vec![
-            make_account(&[10u8; 32], state_data, false),
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+    }
+
+    #[test]
+    fn test_reject_ignores_removed_member_votes() {
+        // [9; 32] rejected earlier, then was removed from the multisig
+        let members = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        let state_data = make_multisig_state(2, members);
+        let mut proposal: Proposal = borsh::from_slice(&make_proposal([1u8; 32])).unwrap();
+        proposal.reject([9u8; 32]);
+
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        // Only one current member rejected: 2 of 3 can still approve
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+    }
+
+    #[test]
+    fn test_reject_with_clock_marks_expired_proposal() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        state.clock_account = [77u8; 32];
+        let mut proposal: Proposal = borsh::from_slice(&make_proposal([1u8; 32])).unwrap();
+        proposal.expires_at = Some(1_000);
+
+        let accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&[77u8; 32], 1_500u64.to_le_bytes().to_vec(), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+
+        assert_eq!(post_states.len(), 4);
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Expired);
+        assert!(proposal.rejected.is_empty());
+        let state: MultisigState = borsh::from_slice(&Vec::from(post_states[0].account().data.clone())).unwrap();
+        assert_eq!(state.last_clock, 1_500);
+    }
+
+    #[test]
+    fn test_reject_with_rewound_clock_fails() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        state.clock_account = [77u8; 32];
+        state.last_clock = 2_000;
+        let mut proposal: Proposal = borsh::from_slice(&make_proposal([1u8; 32])).unwrap();
+        proposal.expires_at = Some(1_000);
+
+        // The multisig already saw 2000, so a clock reading 1500 has gone back
+        let mut accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
             make_account(&[2u8; 32], vec![], true),
-            make_account(&[20u8; 32], proposal_data, false),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&[77u8; 32], 1_500u64.to_le_bytes().to_vec(), false),
         ];
+        assert_eq!(handle(&accounts, 1).unwrap_err(), MultisigError::ClockWentBackwards);
 
-        let (post_states, _) = handle(&accounts, 1);
+        // The same time again is fine
+        accounts[3] = make_account(&[77u8; 32], 2_000u64.to_le_bytes().to_vec(), false);
+        assert!(handle(&accounts, 1).is_ok());
+    }
+
+    #[test]
+    fn test_non_member_marks_expired_proposal() {
+        let mut state = MultisigState::new([0u8; 32], 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        state.clock_account = [77u8; 32];
+        let mut proposal: Proposal = borsh::from_slice(&make_proposal([1u8; 32])).unwrap();
+        proposal.expires_at = Some(1_000);
+
+        let mut accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&state).unwrap(), false),
+            make_account(&[99u8; 32], vec![], true),
+            make_account(&proposal_id(1), borsh::to_vec(&proposal).unwrap(), false),
+            make_account(&[77u8; 32], 1_500u64.to_le_bytes().to_vec(), false),
+        ];
+
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+        let expired: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(expired.status, ProposalStatus::Expired);
+
+        // Before expiry a non-member still can't reject
+        accounts[3] = make_account(&[77u8; 32], 500u64.to_le_bytes().to_vec(), false);
+        assert_eq!(handle(&accounts, 1).unwrap_err(), MultisigError::NotAMember);
+    }
 
+    #[test]
+    fn test_reject_weighted_dead_proposal() {
+        // Weights 2/2/1, threshold 4 of 5
+        let mut state = MultisigState::new([0u8; 32], 4, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.transaction_index = 1;
+        state.weights = vec![2, 2, 1];
+        let state_data = borsh::to_vec(&state).unwrap();
+
+        // Weight 1 rejects: 4 remaining, can still pass
+        let accounts = vec![
+            make_account(&state_id(), state_data.clone(), false),
+            make_account(&[3u8; 32], vec![], true),
+            make_account(&proposal_id(1), make_proposal([1u8; 32]), false),
+        ];
+        let (post_states, _) = handle(&accounts, 1).unwrap();
+        let proposal_data = Vec::from(post_states[2].account().data.clone());
+        let proposal: Proposal = borsh::from_slice(&proposal_data).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+
+        // Weight 2 rejects as well: 2 remaining < 4
+        let accounts = vec![
+            make_account(&state_id(), state_data, false),
+            make_account(&[2u8; 32], vec![], true),
+            make_account(&proposal_id(1), proposal_data, false),
+        ];
+        let (post_states, _) = handle(&accounts, 1).unwrap();
         let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
         assert_eq!(proposal.status, ProposalStatus::Rejected);
     }
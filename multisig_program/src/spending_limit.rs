@@ -0,0 +1,366 @@
+// UseSpendingLimit handler — moves tokens out of a vault under a spending limit.
+//
+// A spending limit is created by an approved AddSpendingLimit config proposal.
+// Its members can then transfer up to `amount` per `period` from the limit's
+// vault without a proposal: this emits the token program's Transfer as a ChainedCall
+// with the vault PDA seed, and records the remaining allowance in the limit PDA.
+//
+// Expected accounts:
+// - accounts[0]: multisig_state PDA (read membership and clock account)
+// - accounts[1]: spender (must be authorized signer, member listed in the limit)
+// - accounts[2]: spending limit PDA (owned by multisig program)
+// - accounts[3]: clock account (only when the limit has a period)
+// - accounts[3..] / accounts[4..]: [vault PDA at the limit's vault_index, recipient holding]
+//
+// The program derives the expected limit and vault PDAs from the multisig
+// state account's program_owner, so substituted accounts are refused.
+//
+// `execute_config` creates and closes limit PDAs for Execute of config proposals.
+
+use nssa_core::account::{Account, AccountWithMetadata};
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{
+    compute_spending_limit_pda, compute_vault_pda, vault_pda_seed, ConfigAction, MultisigError,
+    MultisigState, SpendingLimit,
+};
+use token_core::Instruction as TokenInstruction;
+
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    amount: u128,
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 3 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let spender_account = &accounts[1];
+    let limit_account = &accounts[2];
+
+    if !spender_account.is_authorized {
+        return Err(MultisigError::MissingSignature);
+    }
+
+    let (mut state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    let spender_id = *spender_account.account_id.value();
+    if !state.is_member(&spender_id) {
+        return Err(MultisigError::NotAMember);
+    }
+
+    let limit_data: Vec<u8> = limit_account.account.data.clone().into();
+    let mut limit: SpendingLimit = borsh::from_slice(&limit_data)
+        .map_err(|_| MultisigError::InvalidSpendingLimit)?;
+
+    if limit.multisig_create_key != state.create_key
+        || limit_account.account.program_owner != program_id
+        || limit_account.account_id
+            != compute_spending_limit_pda(&program_id, &state.create_key, &limit.limit_key)
+    {
+        return Err(MultisigError::SpendingLimitMismatch);
+    }
+    if !limit.members.contains(&spender_id) {
+        return Err(MultisigError::MissingPermission);
+    }
+    state.check_program(&limit.token_program_id)?;
+
+    // Limits with a period take the clock account ahead of the transfer accounts
+    let (clock_account, transfer_accounts) = if limit.period > 0 {
+        (accounts.get(3), accounts.get(4..).unwrap_or(&[]))
+    } else {
+        (None, &accounts[3..])
+    };
+    if transfer_accounts.len() != 2 {
+        return Err(MultisigError::TargetAccountCountMismatch);
+    }
+    let vault_account = &transfer_accounts[0];
+    let recipient_account = &transfer_accounts[1];
+
+    if vault_account.account_id != compute_vault_pda(&program_id, &state.create_key, limit.vault_index) {
+        return Err(MultisigError::VaultMismatch);
+    }
+
+    if limit.period > 0 {
+        limit.refresh(crate::clock::now(&mut state, clock_account)?);
+    }
+    limit.spend(amount)?;
+
+    // Encoded from the token program's own instruction type
+    let instruction_data = risc0_zkvm::serde::to_vec(&TokenInstruction::Transfer {
+        amount_to_transfer: amount,
+    })
+    .unwrap();
+
+    // The vault signs through its PDA seed
+    let mut vault_pre = vault_account.clone();
+    vault_pre.is_authorized = true;
+
+    let chained_call = ChainedCall {
+        program_id: limit.token_program_id,
+        instruction_data,
+        pre_states: vec![vault_pre, recipient_account.clone()],
+        pda_seeds: vec![vault_pda_seed(&state.create_key, limit.vault_index)],
+    };
+
+    let limit_bytes = borsh::to_vec(&limit).unwrap();
+    let mut limit_post = limit_account.account.clone();
+    limit_post.data = limit_bytes.try_into().unwrap();
+
+    // The state records the clock time read, if any
+    let mut multisig_post = multisig_account.account.clone();
+    multisig_post.data = borsh::to_vec(&state).unwrap().try_into().unwrap();
+
+    let mut post_states = vec![
+        AccountPostState::new(multisig_post),
+        AccountPostState::new(spender_account.account.clone()),
+        AccountPostState::new(limit_post),
+    ];
+    if let Some(clock) = clock_account {
+        post_states.push(AccountPostState::new(clock.account.clone()));
+    }
+    post_states.push(AccountPostState::new(vault_account.account.clone()));
+    post_states.push(AccountPostState::new(recipient_account.account.clone()));
+
+    Ok((post_states, vec![chained_call]))
+}
+
+/// Create and close the spending limit PDAs of an executed config proposal.
+/// `limit_accounts` must be the PDAs of the actions' limits, in action order.
+pub fn execute_config(
+    program_id: &nssa_core::program::ProgramId,
+    state: &MultisigState,
+    actions: &[ConfigAction],
+    limit_accounts: &[AccountWithMetadata],
+) -> Result<Vec<AccountPostState>, MultisigError> {
+    let limit_actions: Vec<&ConfigAction> = actions
+        .iter()
+        .filter(|a| a.spending_limit_key().is_some())
+        .collect();
+    if limit_actions.is_empty() && !limit_accounts.is_empty() {
+        return Err(MultisigError::UnexpectedTargetAccounts);
+    }
+    if limit_accounts.len() != limit_actions.len() {
+        return Err(MultisigError::TargetAccountCountMismatch);
+    }
+
+    let mut post_states = Vec::new();
+    for (action, account) in limit_actions.into_iter().zip(limit_accounts) {
+        let limit_key = action.spending_limit_key().unwrap();
+        if account.account_id != compute_spending_limit_pda(program_id, &state.create_key, &limit_key) {
+            return Err(MultisigError::TargetAccountMismatch);
+        }
+
+        match action {
+            ConfigAction::AddSpendingLimit { vault_index, token_program_id, amount, period, members, .. } => {
+                if account.account != Account::default() {
+                    return Err(MultisigError::AccountAlreadyInitialized);
+                }
+                let limit = SpendingLimit::new(
+                    state.create_key,
+                    limit_key,
+                    *vault_index,
+                    *token_program_id,
+                    *amount,
+                    *period,
+                    members.clone(),
+                );
+                let mut limit_post = Account::default();
+                limit_post.data = borsh::to_vec(&limit).unwrap().try_into().unwrap();
+                post_states.push(AccountPostState::new_claimed(limit_post));
+            }
+            _ => {
+                // Closing wipes the data; like a closed proposal, the PDA keeps its
+                // owner and can't be re-created under the same limit_key
+                let data: Vec<u8> = account.account.data.clone().into();
+                borsh::from_slice::<SpendingLimit>(&data)
+                    .map_err(|_| MultisigError::InvalidSpendingLimit)?;
+                let mut closed = account.account.clone();
+                closed.data = Vec::new().try_into().unwrap();
+                post_states.push(AccountPostState::new(closed));
+            }
+        }
+    }
+    Ok(post_states)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nssa_core::account::AccountId;
+    use nssa_core::program::ProgramId;
+    use multisig_core::compute_multisig_state_pda;
+
+    const PROGRAM_ID: ProgramId = [0u32; 8];
+    const TOKEN_PROGRAM_ID: ProgramId = [42u32; 8];
+    const CREATE_KEY: [u8; 32] = [0u8; 32];
+    const LIMIT_KEY: [u8; 32] = [5u8; 32];
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, authorized: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: authorized,
+        }
+    }
+
+    fn make_state() -> MultisigState {
+        let mut state = MultisigState::new(CREATE_KEY, 2, vec![[1u8; 32], [2u8; 32], [3u8; 32]]);
+        state.clock_account = [50u8; 32];
+        state
+    }
+
+    fn make_limit(period: u64) -> SpendingLimit {
+        SpendingLimit::new(CREATE_KEY, LIMIT_KEY, 0, TOKEN_PROGRAM_ID, 100, period, vec![[1u8; 32], [2u8; 32]])
+    }
+
+    fn limit_id() -> [u8; 32] {
+        *compute_spending_limit_pda(&PROGRAM_ID, &CREATE_KEY, &LIMIT_KEY).value()
+    }
+
+    fn state_id() -> [u8; 32] {
+        *compute_multisig_state_pda(&PROGRAM_ID, &CREATE_KEY).value()
+    }
+
+    fn vault_id() -> [u8; 32] {
+        *compute_vault_pda(&PROGRAM_ID, &CREATE_KEY, 0).value()
+    }
+
+    fn make_accounts(spender: [u8; 32], limit: &SpendingLimit, clock: Option<u64>) -> Vec<AccountWithMetadata> {
+        let mut accounts = vec![
+            make_account(&state_id(), borsh::to_vec(&make_state()).unwrap(), false),
+            make_account(&spender, vec![], true),
+            make_account(&limit_id(), borsh::to_vec(limit).unwrap(), false),
+        ];
+        if let Some(now) = clock {
+            accounts.push(make_account(&[50u8; 32], now.to_le_bytes().to_vec(), false));
+        }
+        accounts.push(make_account(&vault_id(), vec![], false));
+        accounts.push(make_account(&[40u8; 32], vec![], false));
+        accounts
+    }
+
+    fn limit_after(post_states: &[AccountPostState]) -> SpendingLimit {
+        borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap()
+    }
+
+    #[test]
+    fn test_use_spending_limit_emits_token_transfer() {
+        let accounts = make_accounts([1u8; 32], &make_limit(0), None);
+
+        let (post_states, chained) = handle(&accounts, 60).unwrap();
+
+        assert_eq!(post_states.len(), 5);
+        assert_eq!(limit_after(&post_states).remaining, 40);
+        assert_eq!(chained.len(), 1);
+        let call = &chained[0];
+        assert_eq!(call.program_id, TOKEN_PROGRAM_ID);
+        assert_eq!(
+            call.instruction_data,
+            risc0_zkvm::serde::to_vec(&TokenInstruction::Transfer { amount_to_transfer: 60 }).unwrap()
+        );
+        assert_eq!(*call.pre_states[0].account_id.value(), vault_id());
+        assert!(call.pre_states[0].is_authorized);
+        assert!(!call.pre_states[1].is_authorized);
+        assert_eq!(call.pda_seeds, vec![vault_pda_seed(&CREATE_KEY, 0)]);
+    }
+
+    #[test]
+    fn test_use_spending_limit_over_allowance_fails() {
+        let mut limit = make_limit(0);
+        limit.remaining = 30;
+        let accounts = make_accounts([1u8; 32], &limit, None);
+
+        let err = handle(&accounts, 31).unwrap_err();
+        assert_eq!(err, MultisigError::SpendingLimitExceeded);
+    }
+
+    #[test]
+    fn test_use_spending_limit_resets_each_period() {
+        let mut limit = make_limit(100);
+        limit.remaining = 0;
+        limit.last_reset = 200;
+
+        // Same period: nothing left
+        let err = handle(&make_accounts([1u8; 32], &limit, Some(299)), 1).unwrap_err();
+        assert_eq!(err, MultisigError::SpendingLimitExceeded);
+
+        // Next period: allowance is refilled
+        let (post_states, _) = handle(&make_accounts([1u8; 32], &limit, Some(300)), 70).unwrap();
+        let limit = limit_after(&post_states);
+        assert_eq!(limit.remaining, 30);
+        assert_eq!(limit.last_reset, 300);
+    }
+
+    #[test]
+    fn test_use_periodic_spending_limit_without_clock_fails() {
+        let accounts = make_accounts([1u8; 32], &make_limit(100), None);
+
+        // The vault is taken as the clock account, leaving one transfer account
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::TargetAccountCountMismatch);
+    }
+
+    #[test]
+    fn test_use_spending_limit_member_not_in_limit_fails() {
+        let accounts = make_accounts([3u8; 32], &make_limit(0), None);
+
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::MissingPermission);
+    }
+
+    #[test]
+    fn test_use_spending_limit_removed_member_fails() {
+        let mut limit = make_limit(0);
+        limit.members.push([9u8; 32]);
+        let accounts = make_accounts([9u8; 32], &limit, None);
+
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+
+    #[test]
+    fn test_use_spending_limit_wrong_vault_fails() {
+        let mut accounts = make_accounts([1u8; 32], &make_limit(0), None);
+        accounts[3] = make_account(&[31u8; 32], vec![], false);
+
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::VaultMismatch);
+    }
+
+    #[test]
+    fn test_use_other_multisig_spending_limit_fails() {
+        let mut limit = make_limit(0);
+        limit.multisig_create_key = [7u8; 32];
+        let accounts = make_accounts([1u8; 32], &limit, None);
+
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::SpendingLimitMismatch);
+    }
+
+    #[test]
+    fn test_use_spending_limit_without_signature_fails() {
+        let mut accounts = make_accounts([1u8; 32], &make_limit(0), None);
+        accounts[1].is_authorized = false;
+
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::MissingSignature);
+    }
+
+    #[test]
+    fn test_use_spending_limit_on_indexed_vault() {
+        let mut limit = make_limit(0);
+        limit.vault_index = 2;
+        let mut accounts = make_accounts([1u8; 32], &limit, None);
+
+        // The default vault is refused for a limit on vault 2
+        let err = handle(&accounts, 10).unwrap_err();
+        assert_eq!(err, MultisigError::VaultMismatch);
+
+        let vault_2 = compute_vault_pda(&PROGRAM_ID, &CREATE_KEY, 2);
+        accounts[3] = make_account(vault_2.value(), vec![], false);
+        let (_, chained) = handle(&accounts, 10).unwrap();
+        assert_eq!(chained[0].pda_seeds, vec![vault_pda_seed(&CREATE_KEY, 2)]);
+    }
+}
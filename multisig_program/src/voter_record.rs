@@ -0,0 +1,291 @@
+// Voter records — membership without claiming the member's own account.
+//
+// CreateMultisig claims every member account, so a member needs a fresh
+// keypair per multisig. A voter record is a program-owned PDA per
+// (multisig, member) that stands in for the member instead: the member signs
+// an instruction off-chain with their ordinary wallet key, and ActAsVoter runs
+// it with the record in the signer slot. The same wallet key can then sit on
+// any number of multisigs, and existing multisigs migrate by creating records.
+//
+// CreateVoterRecord accounts:
+// - accounts[0]: multisig_state PDA (read membership)
+// - accounts[1]: voter record PDA (must be Account::default() = uninitialized)
+//
+// ActAsVoter accounts: the wrapped instruction's own, with the member's voter
+// record PDA as accounts[1] (where its signer would go).
+
+use nssa_core::account::{Account, AccountId, AccountWithMetadata};
+use nssa_core::program::{AccountPostState, ChainedCall};
+use multisig_core::{
+    account_id_from_public_key, compute_voter_record_pda, verify_approval, voter_call_digest,
+    Instruction, MemberSignature, MultisigError, VoterRecord,
+};
+
+/// Handle CreateVoterRecord: anyone may create a member's record, since it
+/// grants nothing without the member's signature.
+pub fn handle(
+    accounts: &[AccountWithMetadata],
+    member: &[u8; 32],
+) -> Result<(Vec<AccountPostState>, Vec<ChainedCall>), MultisigError> {
+    if accounts.len() < 2 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let record_account = &accounts[1];
+
+    let (state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    if !state.is_member(member) {
+        return Err(MultisigError::NotAMember);
+    }
+    if record_account.account != Account::default() {
+        return Err(MultisigError::AccountAlreadyInitialized);
+    }
+    if record_account.account_id != compute_voter_record_pda(&program_id, &state.create_key, member) {
+        return Err(MultisigError::VoterRecordMismatch);
+    }
+
+    let record = VoterRecord::new(state.create_key, *member);
+    let mut record_post = Account::default();
+    record_post.data = borsh::to_vec(&record).unwrap().try_into().unwrap();
+
+    Ok((
+        vec![
+            AccountPostState::new(multisig_account.account.clone()),
+            AccountPostState::new_claimed(record_post),
+        ],
+        vec![],
+    ))
+}
+
+/// Check an ActAsVoter call and unwrap it: returns the wrapped instruction and
+/// its accounts, with accounts[1] presented as the signing member. The record
+/// carries its incremented nonce, so the wrapped handler's post state for the
+/// signer slot writes it back.
+pub fn authorize(
+    accounts: &[AccountWithMetadata],
+    voter_signature: &MemberSignature,
+    instruction: &[u32],
+) -> Result<(Vec<AccountWithMetadata>, Instruction), MultisigError> {
+    if accounts.len() < 2 {
+        return Err(MultisigError::NotEnoughAccounts);
+    }
+
+    let multisig_account = &accounts[0];
+    let record_account = &accounts[1];
+
+    let (state, program_id) = crate::accounts::load_state(multisig_account)?;
+
+    let member = account_id_from_public_key(&voter_signature.public_key);
+    if record_account.account_id != compute_voter_record_pda(&program_id, &state.create_key, &member)
+        || record_account.account.program_owner != program_id
+    {
+        return Err(MultisigError::VoterRecordMismatch);
+    }
+
+    let record_data: Vec<u8> = record_account.account.data.clone().into();
+    let mut record: VoterRecord = borsh::from_slice(&record_data)
+        .map_err(|_| MultisigError::InvalidVoterRecord)?;
+    if record.multisig_create_key != state.create_key || record.member != member {
+        return Err(MultisigError::VoterRecordMismatch);
+    }
+
+    let digest = voter_call_digest(&program_id, &state.create_key, &member, record.nonce, instruction);
+    if !verify_approval(&voter_signature.public_key, &voter_signature.signature, &digest) {
+        return Err(MultisigError::InvalidVoterSignature);
+    }
+
+    let inner: Instruction = risc0_zkvm::serde::from_slice(instruction)
+        .map_err(|_| MultisigError::NotVoterCallable)?;
+    // Only instructions with a member signer in accounts[1]
+    if matches!(
+        inner,
+        Instruction::CreateMultisig { .. }
+            | Instruction::ApproveWithSignatures { .. }
+            | Instruction::CreateVoterRecord { .. }
+            | Instruction::ActAsVoter { .. }
+            | Instruction::ReleaseMemberAccount { .. }
+    ) {
+        return Err(MultisigError::NotVoterCallable);
+    }
+
+    record.nonce += 1;
+    let mut record_post = record_account.account.clone();
+    record_post.data = borsh::to_vec(&record).unwrap().try_into().unwrap();
+
+    let mut inner_accounts = accounts.to_vec();
+    inner_accounts[1] = AccountWithMetadata {
+        account_id: AccountId::new(member),
+        account: record_post,
+        is_authorized: true,
+    };
+
+    Ok((inner_accounts, inner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::schnorr::SigningKey;
+    use multisig_core::{compute_multisig_state_pda, compute_proposal_pda, MultisigState, Proposal, ProposalStatus};
+    use nssa_core::program::ProgramId;
+
+    const PROGRAM_ID: ProgramId = [5u32; 8];
+
+    fn make_account(id: &[u8; 32], data: Vec<u8>, owned: bool) -> AccountWithMetadata {
+        let mut account = Account::default();
+        account.data = data.try_into().unwrap();
+        if owned {
+            account.program_owner = PROGRAM_ID;
+        }
+        AccountWithMetadata {
+            account_id: AccountId::new(*id),
+            account,
+            is_authorized: false,
+        }
+    }
+
+    fn signing_key(secret: u8) -> SigningKey {
+        SigningKey::from_bytes(&[secret; 32]).unwrap()
+    }
+
+    fn member_of(secret: u8) -> [u8; 32] {
+        account_id_from_public_key(&signing_key(secret).verifying_key().to_bytes().into())
+    }
+
+    fn state_account() -> AccountWithMetadata {
+        let state = MultisigState::new([0u8; 32], 2, vec![member_of(1), member_of(2), member_of(3)]);
+        let id = compute_multisig_state_pda(&PROGRAM_ID, &[0u8; 32]);
+        make_account(id.value(), borsh::to_vec(&state).unwrap(), true)
+    }
+
+    fn record_account(member: [u8; 32], nonce: u64) -> AccountWithMetadata {
+        let mut record = VoterRecord::new([0u8; 32], member);
+        record.nonce = nonce;
+        let id = compute_voter_record_pda(&PROGRAM_ID, &[0u8; 32], &member);
+        make_account(id.value(), borsh::to_vec(&record).unwrap(), true)
+    }
+
+    fn sign_call(secret: u8, nonce: u64, instruction: &[u32]) -> MemberSignature {
+        sign_call_for(&PROGRAM_ID, secret, nonce, instruction)
+    }
+
+    fn sign_call_for(program_id: &ProgramId, secret: u8, nonce: u64, instruction: &[u32]) -> MemberSignature {
+        let key = signing_key(secret);
+        let digest = voter_call_digest(program_id, &[0u8; 32], &member_of(secret), nonce, instruction);
+        MemberSignature {
+            public_key: key.verifying_key().to_bytes().into(),
+            signature: key.sign_raw(&digest, &[0u8; 32]).unwrap().to_bytes().to_vec(),
+        }
+    }
+
+    fn approve_words() -> Vec<u32> {
+        risc0_zkvm::serde::to_vec(&Instruction::Approve { proposal_index: 1, create_key: [0u8; 32] }).unwrap()
+    }
+
+    fn proposal_account() -> AccountWithMetadata {
+        let proposal = Proposal::new(1, member_of(1), [0u8; 32], [42u32; 8], vec![0u32], vec![[30u8; 32]], vec![], vec![]);
+        let id = compute_proposal_pda(&PROGRAM_ID, &[0u8; 32], 1);
+        make_account(id.value(), borsh::to_vec(&proposal).unwrap(), true)
+    }
+
+    #[test]
+    fn test_create_voter_record() {
+        let id = compute_voter_record_pda(&PROGRAM_ID, &[0u8; 32], &member_of(2));
+        let accounts = vec![state_account(), make_account(id.value(), vec![], false)];
+
+        let (post_states, _) = handle(&accounts, &member_of(2)).unwrap();
+
+        assert!(post_states[1].requires_claim());
+        let record: VoterRecord = borsh::from_slice(&Vec::from(post_states[1].account().data.clone())).unwrap();
+        assert_eq!(record, VoterRecord::new([0u8; 32], member_of(2)));
+    }
+
+    #[test]
+    fn test_create_voter_record_for_non_member_fails() {
+        let id = compute_voter_record_pda(&PROGRAM_ID, &[0u8; 32], &member_of(4));
+        let accounts = vec![state_account(), make_account(id.value(), vec![], false)];
+
+        let err = handle(&accounts, &member_of(4)).unwrap_err();
+        assert_eq!(err, MultisigError::NotAMember);
+    }
+
+    #[test]
+    fn test_create_voter_record_at_wrong_address_fails() {
+        let accounts = vec![state_account(), make_account(&[21u8; 32], vec![], false)];
+
+        let err = handle(&accounts, &member_of(2)).unwrap_err();
+        assert_eq!(err, MultisigError::VoterRecordMismatch);
+    }
+
+    #[test]
+    fn test_voter_call_approves_as_member() {
+        let words = approve_words();
+        let accounts = vec![state_account(), record_account(member_of(2), 0), proposal_account()];
+
+        let (inner_accounts, inner) = authorize(&accounts, &sign_call(2, 0, &words), &words).unwrap();
+        let Instruction::Approve { proposal_index, .. } = inner else { panic!("expected Approve") };
+        let (post_states, _) = crate::approve::handle(&inner_accounts, proposal_index).unwrap();
+
+        let proposal: Proposal = borsh::from_slice(&Vec::from(post_states[2].account().data.clone())).unwrap();
+        assert_eq!(proposal.approved, vec![member_of(1), member_of(2)]);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        // The record's bumped nonce is written back through the signer slot
+        let record: VoterRecord = borsh::from_slice(&Vec::from(post_states[1].account().data.clone())).unwrap();
+        assert_eq!(record.nonce, 1);
+    }
+
+    #[test]
+    fn test_voter_call_replay_fails() {
+        let words = approve_words();
+        let signature = sign_call(2, 0, &words);
+        let accounts = vec![state_account(), record_account(member_of(2), 1), proposal_account()];
+
+        let err = authorize(&accounts, &signature, &words).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidVoterSignature);
+    }
+
+    #[test]
+    fn test_voter_call_signed_for_other_program_fails() {
+        let words = approve_words();
+        let signature = sign_call_for(&[6u32; 8], 2, 0, &words);
+        let accounts = vec![state_account(), record_account(member_of(2), 0), proposal_account()];
+
+        let err = authorize(&accounts, &signature, &words).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidVoterSignature);
+    }
+
+    #[test]
+    fn test_voter_call_through_other_members_record_fails() {
+        let words = approve_words();
+        let accounts = vec![state_account(), record_account(member_of(3), 0), proposal_account()];
+
+        let err = authorize(&accounts, &sign_call(2, 0, &words), &words).unwrap_err();
+        assert_eq!(err, MultisigError::VoterRecordMismatch);
+    }
+
+    #[test]
+    fn test_voter_call_with_tampered_instruction_fails() {
+        let words = approve_words();
+        let signature = sign_call(2, 0, &words);
+        let other = risc0_zkvm::serde::to_vec(&Instruction::Reject { proposal_index: 1, create_key: [0u8; 32] }).unwrap();
+        let accounts = vec![state_account(), record_account(member_of(2), 0), proposal_account()];
+
+        let err = authorize(&accounts, &signature, &other).unwrap_err();
+        assert_eq!(err, MultisigError::InvalidVoterSignature);
+    }
+
+    #[test]
+    fn test_nested_voter_call_fails() {
+        let words = risc0_zkvm::serde::to_vec(&Instruction::CreateVoterRecord {
+            create_key: [0u8; 32],
+            member: member_of(2),
+        })
+        .unwrap();
+        let accounts = vec![state_account(), record_account(member_of(2), 0)];
+
+        let err = authorize(&accounts, &sign_call(2, 0, &words), &words).unwrap_err();
+        assert_eq!(err, MultisigError::NotVoterCallable);
+    }
+}
@@ -359,6 +359,11 @@ CREATE_OUT=$("$MULTISIG_CLI" \
     --create-key              "$CREATE_KEY" \
     --threshold               1 \
     --members                 "$M1_HEX" \
+    --time-lock               0 \
+    --clock-account           0000000000000000000000000000000000000000000000000000000000000000 \
+    --default-expiry          0 \
+    --weights                 1 \
+    --permissions             7 \
     --member-accounts-account "$M1_ACCOUNT" 2>&1) || true
 
 echo "$CREATE_OUT"
@@ -561,10 +566,10 @@ ALPHA = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz'
 n = int.from_bytes(h, 'big')
 b58 = ''
 while n: b58 = ALPHA[n % 58] + b58; n //= 58
-print(seed.hex(), b58)
+print(seed.hex(), b58, h.hex())
 PYEOF
 )
-read MULTISIG_VAULT_SEED MULTISIG_VAULT_PDA <<< "$VAULT_COMPUTED"
+read MULTISIG_VAULT_SEED MULTISIG_VAULT_PDA MULTISIG_VAULT_HEX <<< "$VAULT_COMPUTED"
 ok "Vault seed (hex)   : $MULTISIG_VAULT_SEED"
 ok "Multisig vault PDA : $MULTISIG_VAULT_PDA"
 echo ""
@@ -650,9 +655,10 @@ read PROP_TOKEN _PT_HEX <<< $(new_account "prop-token")
     --proposal-account        "$PROP_TOKEN" \
     --target-program-id       "$TOKEN_PROGRAM_ID" \
     --target-instruction-data "$TARGET_INSTRUCTION_DATA" \
-    --target-account-count    2 \
+    --target-accounts         "$MULTISIG_VAULT_HEX,$_REC_HEX" \
     --pda-seeds               "$MULTISIG_VAULT_SEED" \
     --authorized-indices      0 \
+    --expiry                  0 \
     --create-key              "$CREATE_KEY" \
     --proposal-index          3 2>&1 \
   && ok "Proposal created — 200 LEZToken transfer stored as ChainedCall" \
@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+"""Route the generated FFI client's PDA derivation through multisig_core::pda.
+
+lez-client-gen emits a `compute_pda(seeds)` that returns the hashed seed itself
+as the account address (it never applies the program ID), plus per-account
+`compute_*_pda` helpers that hash non-constant segments unpadded. Neither
+matches the addresses the program derives, so every generated wrapper would
+send the wrong proposal, multisig_state, spending_limit and voter_record
+accounts.
+
+This rewrites the generated file in place:
+  * `compute_pda` takes the program ID and delegates to
+    `multisig_core::pda::compute_pda`;
+  * every call site passes the wrapper's `program_id`;
+  * the generated `compute_*_pda` helpers and their sha2 import are removed.
+
+Run after lez-client-gen (see `make generate-ffi`). Fails loudly if the
+generated code no longer has the expected shape, so a generator change is
+noticed rather than silently shipped.
+
+Usage: patch-ffi-pda.py lez-multisig-ffi/src/multisig.rs
+"""
+
+import re
+import sys
+
+GENERATED_COMPUTE_PDA = re.compile(
+    r"fn compute_pda\(seeds: &\[&\[u8\]\]\) -> AccountId \{\n.*?\n\}\n",
+    re.DOTALL,
+)
+
+PATCHED_COMPUTE_PDA = """/// PDA address for raw IDL seed segments, derived by multisig_core::pda.
+pub fn compute_pda(program_id: &ProgramId, seeds: &[&[u8]]) -> AccountId {
+    multisig_core::pda::compute_pda(program_id, seeds)
+}
+"""
+
+GENERATED_HELPERS = re.compile(
+    r"\n/// Compute PDA for `\w+` account\.\n.*?\n\}\n",
+    re.DOTALL,
+)
+
+
+def fail(msg):
+    sys.exit(f"patch-ffi-pda: {msg}")
+
+
+def main():
+    if len(sys.argv) != 2:
+        fail("usage: patch-ffi-pda.py <multisig.rs>")
+    path = sys.argv[1]
+    with open(path) as f:
+        src = f.read()
+
+    src, n = GENERATED_COMPUTE_PDA.subn(PATCHED_COMPUTE_PDA, src)
+    if n != 1:
+        fail(f"expected one generated compute_pda, found {n}")
+
+    src, calls = re.subn(r"\bcompute_pda\(&\[", "compute_pda(&program_id, &[", src)
+    if calls == 0:
+        fail("no compute_pda call sites found")
+
+    src, helpers = GENERATED_HELPERS.subn("\n", src)
+    if helpers == 0:
+        fail("no generated compute_*_pda helpers found")
+    src = src.rstrip("\n") + "\n"
+
+    # The generated hashing was the only user of sha2.
+    src = src.replace("use sha2::{Sha256, Digest};\n", "", 1)
+    if "Sha256" in src:
+        fail("generated code still hashes with Sha256 outside compute_pda")
+
+    with open(path, "w") as f:
+        f.write(src)
+    print(f"patch-ffi-pda: {calls} call sites, {helpers} helpers removed")
+
+
+if __name__ == "__main__":
+    main()